@@ -26,6 +26,8 @@ criterion_group!(
     recursive_backtracking::generate_100_x_100,
     recursive_division::generate_10_x_10,
     recursive_division::generate_100_x_100,
+    river::generate_10_x_10,
+    river::generate_100_x_100,
     sidewinder::generate_10_x_10,
     sidewinder::generate_100_x_100,
 );
@@ -42,7 +44,7 @@ mod aldous_broder {
                 OrthogonalMazeBuilder::new()
                     .height(10)
                     .width(10)
-                    .algorithm(Box::new(AldousBroder))
+                    .algorithm(Box::new(AldousBroder::new()))
                     .build()
                     .unwrap();
             })
@@ -55,7 +57,7 @@ mod aldous_broder {
                 OrthogonalMazeBuilder::new()
                     .height(100)
                     .width(100)
-                    .algorithm(Box::new(AldousBroder))
+                    .algorithm(Box::new(AldousBroder::new()))
                     .build()
                     .unwrap();
             })
@@ -282,7 +284,7 @@ mod kruskal {
                 OrthogonalMazeBuilder::new()
                     .height(10)
                     .width(10)
-                    .algorithm(Box::new(Kruskal))
+                    .algorithm(Box::new(Kruskal::new()))
                     .build()
                     .unwrap();
             })
@@ -295,7 +297,7 @@ mod kruskal {
                 OrthogonalMazeBuilder::new()
                     .height(100)
                     .width(100)
-                    .algorithm(Box::new(Kruskal))
+                    .algorithm(Box::new(Kruskal::new()))
                     .build()
                     .unwrap();
             })
@@ -393,6 +395,36 @@ mod recursive_division {
     }
 }
 
+mod river {
+    use super::*;
+
+    pub fn generate_10_x_10(c: &mut Criterion) {
+        c.bench_function("river/generate_10_x_10", |b| {
+            b.iter(|| {
+                OrthogonalMazeBuilder::new()
+                    .height(10)
+                    .width(10)
+                    .algorithm(Box::new(River::new()))
+                    .build()
+                    .unwrap();
+            })
+        });
+    }
+
+    pub fn generate_100_x_100(c: &mut Criterion) {
+        c.bench_function("river/generate_100_x_100", |b| {
+            b.iter(|| {
+                OrthogonalMazeBuilder::new()
+                    .height(100)
+                    .width(100)
+                    .algorithm(Box::new(River::new()))
+                    .build()
+                    .unwrap();
+            })
+        });
+    }
+}
+
 mod sidewinder {
     use super::*;
 
@@ -20,6 +20,7 @@ criterion_group!(
     ascii_narrow::format_100_x_100,
     image::format_10_x_10,
     image::format_50_x_50,
+    image::render_500_x_500,
 );
 criterion_main!(benches);
 
@@ -50,6 +51,14 @@ mod image {
             })
         });
     }
+
+    /// Renders a large image without saving it, which scales with the cores with `rayon` enabled
+    pub fn render_500_x_500(c: &mut Criterion) {
+        let maze = maze!(500, 500).unwrap();
+        c.bench_function("image/render_500_x_500", |b| {
+            b.iter(|| maze.format(Image::new().wall(2).passage(4).margin(10)))
+        });
+    }
 }
 
 mod ascii_narrow {
@@ -0,0 +1,119 @@
+/// Times every algorithm on a maze of a given size and prints a table to pick one of them
+///
+/// `cargo run --release --example benchmark -- [size] [runs]`, e.g. `-- 200 10` averages 10 runs
+/// of a 200x200 maze. Peak memory is estimated by counting the bytes allocated during a run.
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+use bevy_knossos::maze::*;
+
+/// The system allocator keeping track of the allocated bytes and their peak
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let allocated = ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(allocated, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Timing and memory of generating a maze with an algorithm
+struct Report {
+    name: &'static str,
+    average: Duration,
+    peak_bytes: usize,
+}
+
+/// Generates a `size`x`size` maze `runs` times with a given algorithm, and returns the average
+/// generation time along with the highest number of bytes allocated during a single run
+fn benchmark(algorithm: fn() -> Box<dyn Algorithm>, size: usize, runs: u32) -> Report {
+    let name = algorithm().name();
+    let mut total = Duration::ZERO;
+    let mut peak_bytes = 0;
+
+    for seed in 0..runs {
+        let builder = OrthogonalMazeBuilder::new()
+            .width(size)
+            .height(size)
+            .algorithm(algorithm())
+            .seed(seed as u64);
+
+        // Only the memory allocated on top of what was in use before the run is counted
+        let before = ALLOCATED.load(Ordering::Relaxed);
+        PEAK.store(before, Ordering::Relaxed);
+        let start = Instant::now();
+        let maze = builder.build().unwrap();
+        total += start.elapsed();
+        peak_bytes = peak_bytes.max(PEAK.load(Ordering::Relaxed) - before);
+        drop(maze);
+    }
+
+    Report {
+        name,
+        average: total / runs.max(1),
+        peak_bytes,
+    }
+}
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let size = args
+        .next()
+        .map_or(100, |arg| arg.parse().expect("Invalid size"));
+    let runs = args
+        .next()
+        .map_or(5, |arg| arg.parse().expect("Invalid runs"));
+
+    // Variants of an algorithm share its name, so they are labeled separately
+    let algorithms: [(&str, fn() -> Box<dyn Algorithm>); 14] = [
+        ("", || Box::new(AldousBroder::new())),
+        ("NorthEast", || Box::new(BinaryTree::new(Bias::NorthEast))),
+        ("", || Box::new(Eller)),
+        ("Newest", || Box::new(GrowingTree::new(Method::Newest))),
+        ("Oldest", || Box::new(GrowingTree::new(Method::Oldest))),
+        ("Random", || Box::new(GrowingTree::new(Method::Random))),
+        ("Middle", || Box::new(GrowingTree::new(Method::Middle))),
+        ("", || Box::new(HuntAndKill::new())),
+        ("", || Box::new(Kruskal::new())),
+        ("", || Box::new(Prim::new())),
+        ("", || Box::new(RecursiveBacktracking)),
+        ("", || Box::new(RecursiveDivision)),
+        ("", || Box::new(River::new())),
+        ("", || Box::new(Sidewinder)),
+    ];
+
+    println!("A {size}x{size} maze, averaged over {runs} runs\n");
+    println!("{:<24} {:>12} {:>12}", "algorithm", "time", "peak memory");
+    for (variant, algorithm) in algorithms {
+        let report = benchmark(algorithm, size, runs);
+        let label = if variant.is_empty() {
+            report.name.to_string()
+        } else {
+            format!("{} ({})", report.name, variant)
+        };
+        println!(
+            "{:<24} {:>12.3?} {:>9} KiB",
+            label,
+            report.average,
+            report.peak_bytes.div_ceil(1024)
+        );
+    }
+}
@@ -49,7 +49,7 @@ fn main() {
     let maze = OrthogonalMazeBuilder::new()
         .height(10)
         .width(15)
-        .algorithm(Box::new(Kruskal))
+        .algorithm(Box::new(Kruskal::new()))
         .build()
         .unwrap();
 
@@ -133,7 +133,7 @@ fn main() {
     let maze = OrthogonalMazeBuilder::new()
         .height(25)
         .width(20)
-        .algorithm(Box::new(AldousBroder))
+        .algorithm(Box::new(AldousBroder::new()))
         .build()
         .unwrap();
 
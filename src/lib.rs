@@ -65,9 +65,17 @@
 //!
 //! Read more about [maze formatters](maze::formatters)
 //!
+//! ## WebAssembly
+//! The generation and the byte-returning exports work without the filesystem, so the crate builds
+//! for `wasm32-unknown-unknown`. Build with `RUSTFLAGS='--cfg getrandom_backend="wasm_js"'` to
+//! let the random number generator get entropy through JavaScript.
+//! ```rust,no_run
+//! let png: Vec<u8> = bevy_knossos::generate_png_bytes(10, 10, 42);
+//! ```
+//!
 //! # Algorithms
 //!
-//! You can find 10 different algorithms supported by this crate. Each of them has its own pros and
+//! You can find 11 different algorithms supported by this crate. Each of them has its own pros and
 //! cons: some of them are impressively efficient, some of them are slower but generate splendid
 //! mazes that look hard to puzzle out, and others are extremely flexible and customizable. Do give
 //! each of them a shot and find the best one that suits you:
@@ -81,13 +89,17 @@
 //! - [`Prim`](maze::Prim)
 //! - [`RecursiveBacktracking`](maze::RecursiveBacktracking)
 //! - [`RecursiveDivision`](maze::RecursiveDivision)
+//! - [`River`](maze::River)
 //! - [`Sidewinder`](maze::Sidewinder)
+//!
+//! Different algorithms can also generate different parts of a single maze with
+//! [`Blend`](maze::Blend).
 
 mod utils;
 
 pub mod maze;
 use bevy::app::Plugin;
-use maze::Cell;
+use maze::{Cell, Image, OrthogonalMazeBuilder};
 pub use utils::color::Color;
 pub use utils::types::{Coords, CellSize, CoordsComponent, Goal, Start};
 
@@ -119,3 +131,36 @@ impl Plugin for KnossosPlugin {
         }
     }
 }
+
+/// Generates a maze of a given size from a given seed and returns it rendered as a PNG image with
+/// the default [Image] settings
+///
+/// The same arguments always return the same bytes. Nothing is written to the filesystem, so this
+/// is a simple entrypoint for WebAssembly.
+///
+/// # Panics
+/// Panics if the width or the height is zero.
+///
+/// # Example
+/// ```
+/// let png = bevy_knossos::generate_png_bytes(5, 5, 42);
+///
+/// assert!(png.starts_with(b"\x89PNG"));
+/// assert_eq!(png, bevy_knossos::generate_png_bytes(5, 5, 42));
+/// ```
+pub fn generate_png_bytes(width: usize, height: usize, seed: u64) -> Vec<u8> {
+    assert!(
+        width > 0 && height > 0,
+        "A maze must have at least one cell"
+    );
+
+    OrthogonalMazeBuilder::new()
+        .width(width)
+        .height(height)
+        .seed(seed)
+        .build()
+        .expect("A maze without start coords always builds")
+        .format(Image::new())
+        .to_png_bytes()
+        .expect("Encoding a PNG in memory never fails")
+}
@@ -1,9 +1,11 @@
 use std::fmt;
+use std::path::Path;
 use std::str::FromStr;
 
 use clap::{Parser, Subcommand, ValueEnum};
 use bevy_knossos::Color;
 use bevy_knossos::maze::{self, formatters, MazeSaveError};
+use bevy_knossos::maze::formatters::{Formatter, StringWrapper};
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
 enum Algorithm {
@@ -16,6 +18,7 @@ enum Algorithm {
     Prim,
     RecursiveBacktracking,
     RecursiveDivision,
+    River,
     Sidewinder,
 }
 
@@ -83,6 +86,10 @@ enum Commands {
         /// Start coordinate for maze algorithm
         start_coords: Option<Coords>,
 
+        #[arg(short = 'S', long, default_value = None)]
+        /// Seed for the random number generator, so that the same options generate the same maze
+        seed: Option<u64>,
+
         /// Bias to use for the "Binary Tree" algorithm
         #[arg(
             long,
@@ -111,9 +118,9 @@ enum Commands {
 enum OutputCommands {
     /// Save to a text file with an ASCII representation of a maze
     Ascii {
-        /// Output path
+        /// Output path, printing to stdout if missing. Defaults to the `.txt` extension
         #[arg(short = 'O', long)]
-        output_path: String,
+        output_path: Option<String>,
 
         /// Output type
         #[arg(
@@ -130,9 +137,9 @@ enum OutputCommands {
     /// Save to a text file as an ASCII game map for pseudo 3D games that use ray casting
     /// for modeling and rendering the map
     GameMap {
-        /// Output path
+        /// Output path, printing to stdout if missing. Defaults to the `.txt` extension
         #[arg(short = 'O', long)]
-        output_path: String,
+        output_path: Option<String>,
 
         /// Distance between any two walls
         #[arg(long, default_value_t = 3)]
@@ -152,7 +159,7 @@ enum OutputCommands {
     },
     /// Save to PNG or JPG file
     Image {
-        /// Output path
+        /// Output path. Defaults to the `.png` extension
         #[arg(short = 'O', long)]
         output_path: String,
 
@@ -190,24 +197,31 @@ fn main() -> Result<(), maze::MazeSaveError> {
             bias,
             growing_method,
             start_coords,
+            seed,
         } => {
             let algorithm: Box<dyn maze::Algorithm> = match algorithm {
-                Algorithm::AldousBroder => Box::new(maze::AldousBroder),
+                Algorithm::AldousBroder => Box::new(maze::AldousBroder::new()),
                 Algorithm::BinaryTree => Box::new(maze::BinaryTree::new(bias)),
                 Algorithm::Eller => Box::new(maze::Eller),
                 Algorithm::GrowingTree => Box::new(maze::GrowingTree::new(growing_method)),
                 Algorithm::HuntAndKill => Box::new(maze::HuntAndKill::new()),
-                Algorithm::Kruskal => Box::new(maze::Kruskal),
+                Algorithm::Kruskal => Box::new(maze::Kruskal::new()),
                 Algorithm::Prim => Box::new(maze::Prim::new()),
                 Algorithm::RecursiveBacktracking => Box::new(maze::RecursiveBacktracking),
                 Algorithm::RecursiveDivision => Box::new(maze::RecursiveDivision),
+                Algorithm::River => Box::new(maze::River::new()),
                 Algorithm::Sidewinder => Box::new(maze::Sidewinder),
             };
 
-            let maze = start_coords
-                .map_or_else(maze::OrthogonalMazeBuilder::new, |coords| {
-                    maze::OrthogonalMazeBuilder::new().start_coords((coords.0, coords.1))
-                })
+            let builder = start_coords.map_or_else(maze::OrthogonalMazeBuilder::new, |coords| {
+                maze::OrthogonalMazeBuilder::new().start_coords((coords.0, coords.1))
+            });
+            let builder = match seed {
+                Some(seed) => builder.seed(seed),
+                None => builder,
+            };
+
+            let maze = builder
                 .height(height)
                 .width(width)
                 .algorithm(algorithm)
@@ -223,10 +237,10 @@ fn main() -> Result<(), maze::MazeSaveError> {
                 } => {
                     match output_type {
                         AsciiOutputType::Narrow => {
-                            result = maze.save(output_path.as_str(), formatters::AsciiNarrow)
+                            result = save_or_print(&maze, output_path, formatters::AsciiNarrow)
                         }
                         AsciiOutputType::Broad => {
-                            result = maze.save(output_path.as_str(), formatters::AsciiBroad)
+                            result = save_or_print(&maze, output_path, formatters::AsciiBroad)
                         }
                     };
                 }
@@ -238,16 +252,18 @@ fn main() -> Result<(), maze::MazeSaveError> {
                     with_start_goal,
                 } => {
                     result = match with_start_goal {
-                        true => maze.save(
-                            output_path.as_str(),
+                        true => save_or_print(
+                            &maze,
+                            output_path,
                             maze::GameMap::new()
                                 .span(span)
                                 .passage(passage)
                                 .wall(wall)
                                 .with_start_goal(),
                         ),
-                        false => maze.save(
-                            output_path.as_str(),
+                        false => save_or_print(
+                            &maze,
+                            output_path,
                             maze::GameMap::new().span(span).passage(passage).wall(wall),
                         ),
                     };
@@ -261,7 +277,7 @@ fn main() -> Result<(), maze::MazeSaveError> {
                     wall_color,
                 } => {
                     result = maze.save(
-                        output_path.as_str(),
+                        &with_default_extension(output_path, "png"),
                         maze::Image::new()
                             .wall(wall_size)
                             .passage(passage_size)
@@ -283,6 +299,31 @@ fn main() -> Result<(), maze::MazeSaveError> {
     }
 }
 
+/// Saves a maze formatted as text to a given path, or returns the text to be printed to stdout if
+/// there is no path
+fn save_or_print<F>(
+    maze: &maze::OrthogonalMaze,
+    output_path: Option<String>,
+    formatter: F,
+) -> Result<String, MazeSaveError>
+where
+    F: Formatter<StringWrapper>,
+{
+    match output_path {
+        Some(path) => maze.save(&with_default_extension(path, "txt"), formatter),
+        None => Ok(maze.format(formatter).0.trim_end_matches('\n').to_string()),
+    }
+}
+
+/// Appends a given extension to a path without one
+fn with_default_extension(path: String, extension: &str) -> String {
+    if Path::new(&path).extension().is_some() {
+        path
+    } else {
+        format!("{}.{}", path, extension)
+    }
+}
+
 fn hex_to_rgb(s: &str) -> Result<Color, ParseHexError> {
     let s = s.strip_prefix('#').map_or(s, |hex| hex);
 
@@ -333,4 +374,20 @@ mod tests {
         use clap::CommandFactory;
         Cli::command().debug_assert()
     }
+
+    #[test]
+    fn default_extension() {
+        assert_eq!(
+            with_default_extension("maze".to_string(), "png"),
+            "maze.png"
+        );
+        assert_eq!(
+            with_default_extension("dir/maze".to_string(), "txt"),
+            "dir/maze.txt"
+        );
+        assert_eq!(
+            with_default_extension("maze.jpg".to_string(), "png"),
+            "maze.jpg"
+        );
+    }
 }
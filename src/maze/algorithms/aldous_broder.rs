@@ -1,18 +1,67 @@
 use super::Algorithm;
 use crate::{
-    maze::grid::{Grid, cell::Cell},
+    maze::grid::{Grid, cell::Cell, pole::Pole},
     utils::types::Coords,
 };
 use rand::prelude::*;
 
+/// The maximum momentum of the random walk
+const MAX_MOMENTUM: f64 = 0.99;
+
 /// The Aldous-Broder's algorithm for generating mazes.
 ///
 /// This is an easy one to implement. And yet, it is also one of the least intelligent algorithms,
 /// since the latest steps may take so much time that you may not want to wait until it's finished.
 /// It is not even guaranteed to finish if you get really unlucky with the random.
-pub struct AldousBroder;
+///
+/// The random walk can be biased with a [momentum](AldousBroder::momentum) that prefers continuing
+/// in the same direction, which produces longer corridors while still generating a perfect maze.
+pub struct AldousBroder {
+    momentum: f64,
+}
+
+impl AldousBroder {
+    /// Create a new instance of the algorithm with a uniform random walk
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::AldousBroder;
+    ///
+    /// let algorithm = AldousBroder::new();
+    /// ```
+    pub const fn new() -> AldousBroder {
+        AldousBroder { momentum: 0.0 }
+    }
+
+    /// Sets a momentum of the random walk and returns itself
+    ///
+    /// The momentum in `[0, 1]` is the probability of continuing in the same direction as the
+    /// previous step. `0` is a uniform walk and higher values favor straight lines. Values out of
+    /// the range are clamped, and the momentum never exceeds `0.99` since a walk that
+    /// always goes straight would never leave the outer walls of a grid.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::{AldousBroder, OrthogonalMazeBuilder};
+    ///
+    /// let maze = OrthogonalMazeBuilder::new()
+    ///     .algorithm(Box::new(AldousBroder::new().momentum(0.8)))
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert!(maze.is_valid());
+    /// ```
+    pub const fn momentum(mut self, momentum: f64) -> Self {
+        self.momentum = momentum;
+        self
+    }
+}
 
-impl AldousBroder {}
+impl Default for AldousBroder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 /// An implementation of Aldous-Broder's algorithm for generating mazes.
 ///
@@ -26,14 +75,27 @@ impl AldousBroder {}
 ///
 /// 3. Repeats step 2 until all vertices have been visited.
 impl Algorithm for AldousBroder {
-    fn generate(&mut self, grid: &mut Grid, start_coords: Option<Coords>) {
-        let (mut x, mut y) = start_coords.unwrap_or_else(|| get_start_coords(grid));
+    fn generate(&mut self, grid: &mut Grid, start_coords: Option<Coords>, rng: &mut dyn RngCore) {
+        let (mut x, mut y) = start_coords.unwrap_or_else(|| get_start_coords(grid, rng));
 
         let mut remaining = grid.width() * grid.height() - 1; // the number of remaining unvisited cells
 
+        let momentum = if self.momentum.is_nan() {
+            0.0
+        } else {
+            self.momentum.clamp(0.0, MAX_MOMENTUM)
+        };
+        let mut last_dir = None;
+
         while remaining > 0 {
-            let mut directions = [Cell::NORTH, Cell::SOUTH, Cell::WEST, Cell::EAST];
-            directions.shuffle(&mut rand::rng());
+            let mut directions = Pole::shuffled(rng).map(Cell::from);
+
+            // With the momentum probability, try to keep going in the same direction first
+            if let Some(dir) = last_dir.filter(|_| rng.random_bool(momentum)) {
+                if let Some(pos) = directions.iter().position(|d| *d == dir) {
+                    directions.swap(0, pos);
+                }
+            }
 
             for dir in directions {
                 let next_cell = grid.get_next_cell_coords((x, y), dir);
@@ -49,6 +111,7 @@ impl Algorithm for AldousBroder {
 
                 x = nx;
                 y = ny;
+                last_dir = Some(dir);
                 break;
             }
         }
@@ -63,8 +126,7 @@ impl Algorithm for AldousBroder {
     }
 }
 
-fn get_start_coords(grid: &Grid) -> Coords {
-    let mut rng = rand::rng();
+fn get_start_coords(grid: &Grid, rng: &mut dyn RngCore) -> Coords {
     let y = rng.random_range(0..grid.height());
     let x = rng.random_range(0..grid.width());
     (x, y)
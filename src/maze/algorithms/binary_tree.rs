@@ -2,14 +2,14 @@ use super::Algorithm;
 use crate::maze::grid::cell::Cell;
 use crate::maze::grid::Grid;
 use crate::utils::types::Coords;
-use clap::ValueEnum;
 use rand::prelude::*;
 
 /// An enumeration over supported biases for the "Binary Tree" algorithm
 ///
 /// Each bias represents the two of four sides of the maze that will be spanned
 /// by a single corridor.
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
 pub enum Bias {
     /// Produces two long corridors on the Northern and Western sides of the maze
     NorthWest,
@@ -100,14 +100,14 @@ impl BinaryTree {
 ///
 /// The `generate` function will warn in case a start_coords is passed.
 impl Algorithm for BinaryTree {
-    fn generate(&mut self, grid: &mut Grid, _c: Option<Coords>) {
+    fn generate(&mut self, grid: &mut Grid, _c: Option<Coords>, rng: &mut dyn RngCore) {
         if _c.is_some() {
             eprintln!("Algorithm `{}` doesn't suppoer `start_coords`", self.name())
         }
         for y in 0..grid.height() {
             for x in 0..grid.width() {
                 let dirs = self.populate_dirs((x, y), grid);
-                if let Some(dir) = dirs.choose(&mut rand::rng()) {
+                if let Some(dir) = dirs.choose(rng) {
                     grid.carve_passage((x, y), *dir).ok();
                 }
             }
@@ -0,0 +1,155 @@
+use super::Algorithm;
+use crate::maze::grid::{cell::Cell, Grid};
+use crate::utils::{arena::join_parts, types::Coords};
+use rand::prelude::*;
+
+/// A sub-rectangle of a grid generated with its own algorithm
+struct Region {
+    origin: Coords,
+    size: (usize, usize),
+    algorithm: Box<dyn Algorithm>,
+}
+
+/// A combination of algorithms, each generating its own part of the maze
+///
+/// A base algorithm generates the whole maze, and every added region is generated again with
+/// another algorithm, so a maze can mix the textures of different algorithms, e.g. the orderly
+/// top of a [BinaryTree](super::BinaryTree) with the chaotic bottom of [Prim](super::Prim).
+/// Regions added later are laid over the earlier ones where they overlap. The parts are stitched
+/// together at the seams afterwards, so the result is still a single perfect maze.
+///
+/// # Example
+/// ```
+/// use bevy_knossos::maze::*;
+///
+/// let maze = OrthogonalMazeBuilder::new()
+///     .width(20)
+///     .height(20)
+///     .algorithm(Box::new(
+///         Blend::new(Box::new(Prim::new())).region(
+///             (0, 0),
+///             (20, 10),
+///             Box::new(BinaryTree::new(Bias::NorthEast)),
+///         ),
+///     ))
+///     .build()
+///     .unwrap();
+///
+/// assert!(maze.is_valid());
+/// ```
+pub struct Blend {
+    base: Box<dyn Algorithm>,
+    regions: Vec<Region>,
+}
+
+impl Blend {
+    /// Create a new instance of the algorithm generating the parts of the maze out of any region
+    /// with a given base algorithm
+    pub const fn new(base: Box<dyn Algorithm>) -> Blend {
+        Blend {
+            base,
+            regions: vec![],
+        }
+    }
+
+    /// Adds a region of `size` cells with the top left corner at `origin` generated with a given
+    /// algorithm and returns itself
+    ///
+    /// A region is clipped to the bounds of the maze. The cells of a region smaller than the
+    /// [minimum size](Algorithm::min_size) of its algorithm are left to the algorithms below it.
+    pub fn region(
+        mut self,
+        origin: Coords,
+        size: (usize, usize),
+        algorithm: Box<dyn Algorithm>,
+    ) -> Self {
+        self.regions.push(Region {
+            origin,
+            size,
+            algorithm,
+        });
+        self
+    }
+}
+
+/// An implementation of a combination of algorithms for generating mazes
+///
+/// Here is how it works:
+///
+/// 1. Generates the whole grid with the base algorithm, and every region on a separate grid of its
+///    size with its own algorithm.
+///
+/// 2. Marks every cell as owned by the last region covering it, or by the base algorithm, and
+///    keeps only the passages between two cells of the same owner. Every part thus becomes one or
+///    more trees of passages.
+///
+/// 3. Goes through the walls between the trees in random order, and carves a passage through a
+///    wall whenever the cells on its sides are not connected yet, like
+///    [Kruskal](super::Kruskal) does, until all the trees join into one.
+impl Algorithm for Blend {
+    fn generate(&mut self, grid: &mut Grid, start_coords: Option<Coords>, rng: &mut dyn RngCore) {
+        let (width, height) = (grid.width(), grid.height());
+        let index = |(x, y): Coords| y * width + x;
+
+        // Owners of cells, where 0 is the base algorithm and the rest are regions after it
+        let mut owners = vec![0; width * height];
+        let mut parts = vec![];
+        for (i, region) in self.regions.iter_mut().enumerate() {
+            let (left, top) = region.origin;
+            let columns = region.size.0.min(width.saturating_sub(left));
+            let rows = region.size.1.min(height.saturating_sub(top));
+            let (min_columns, min_rows) = region.algorithm.min_size();
+            if columns == 0 || rows == 0 || columns < min_columns || rows < min_rows {
+                continue;
+            }
+
+            let mut part = Grid::new(columns, rows);
+            region.algorithm.generate(&mut part, None, rng);
+            for y in top..top + rows {
+                for x in left..left + columns {
+                    owners[index((x, y))] = i + 1;
+                }
+            }
+            parts.push((i + 1, (left, top), part));
+        }
+
+        let mut base = Grid::new(width, height);
+        self.base.generate(&mut base, start_coords, rng);
+        parts.push((0, (0, 0), base));
+
+        for (owner, (left, top), part) in parts {
+            for y in 0..part.height() {
+                for x in 0..part.width() {
+                    let coords = (left + x, top + y);
+                    if owners[index(coords)] != owner {
+                        continue;
+                    }
+
+                    for direction in [Cell::EAST, Cell::SOUTH] {
+                        let Ok(next) = grid.get_next_cell_coords(coords, direction) else {
+                            continue;
+                        };
+                        if owners[index(next)] == owner && part.is_carved((x, y), direction) {
+                            grid.carve_passage(coords, direction).unwrap();
+                        }
+                    }
+                }
+            }
+        }
+
+        // Joins the parts through the walls they share, or within a base part split by the regions
+        join_parts(grid, None, rng);
+    }
+
+    fn has_start_coords(&self) -> bool {
+        self.base.has_start_coords()
+    }
+
+    fn min_size(&self) -> (usize, usize) {
+        self.base.min_size()
+    }
+
+    fn name(&self) -> &'static str {
+        "Blend"
+    }
+}
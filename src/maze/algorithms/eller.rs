@@ -1,15 +1,18 @@
 use super::{Algorithm, BOOL_TRUE_PROBABILITY};
 use crate::{
     maze::grid::{Grid, cell::Cell as GridCell},
-    utils::types::Coords,
+    utils::{rand::shuffle, types::Coords},
 };
 use rand::prelude::*;
-use std::{cell::RefCell, collections::HashMap};
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 struct CellId(usize);
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 struct SetId(usize);
 
 #[derive(Debug, Clone, Copy)]
@@ -82,10 +85,14 @@ impl State {
         cell.coords
     }
 
-    fn sets(&self) -> HashMap<SetId, Vec<CellId>> {
-        let mut sets: HashMap<SetId, Vec<CellId>> = HashMap::new();
+    fn sets(&self) -> BTreeMap<SetId, Vec<CellId>> {
+        let mut sets: BTreeMap<SetId, Vec<CellId>> = BTreeMap::new();
+
+        // Sets and their cells are ordered to keep a seeded generation reproducible
+        let mut cells: Vec<_> = self.cells.iter().collect();
+        cells.sort_by_key(|(id, _)| **id);
 
-        self.cells.iter().for_each(|(id, cell)| {
+        cells.into_iter().for_each(|(id, cell)| {
             let cell = cell.borrow();
 
             if let Some(cells) = sets.get_mut(&cell.set_id) {
@@ -108,9 +115,13 @@ pub struct Eller;
 
 impl Eller {
     /// Randomly joins adjacent cells, but only if they are not in the same set
-    fn connect_disjoint_sets(&self, state: &mut State, grid: &mut Grid, is_last_row: bool) {
-        let mut rng = rand::rng();
-
+    fn connect_disjoint_sets(
+        &self,
+        state: &mut State,
+        grid: &mut Grid,
+        is_last_row: bool,
+        rng: &mut dyn RngCore,
+    ) {
         for c in 1..state.width {
             let cell_id = CellId(c);
             let next_cell_id = CellId(c + 1);
@@ -133,6 +144,7 @@ impl Eller {
         state: &mut State,
         grid: &mut Grid,
         is_last_row: bool,
+        rng: &mut dyn RngCore,
     ) -> State {
         let mut next_state = state.next();
 
@@ -141,7 +153,7 @@ impl Eller {
         }
 
         for (set_id, cells) in state.sets() {
-            for cell_id in self.cells_to_connect(cells) {
+            for cell_id in self.cells_to_connect(cells, rng) {
                 let (x, y) = state.get_cell_coords(cell_id);
                 grid.carve_passage((x, y), GridCell::SOUTH).unwrap();
                 next_state.add(cell_id, set_id, (x, y + 1));
@@ -152,11 +164,9 @@ impl Eller {
     }
 
     /// Selects random cells to carve vertical passages from
-    fn cells_to_connect(&self, cells: Vec<CellId>) -> Vec<CellId> {
-        let mut rng = rand::rng();
-
+    fn cells_to_connect(&self, cells: Vec<CellId>, rng: &mut dyn RngCore) -> Vec<CellId> {
         let mut cells = cells;
-        cells.shuffle(&mut rng);
+        shuffle(&mut cells, rng);
 
         let connect_count = if cells.len() >= 2 {
             rng.random_range(1..cells.len())
@@ -198,7 +208,7 @@ impl Eller {
 ///
 /// The `generate` function will warn in case a start_coords is passed.
 impl Algorithm for Eller {
-    fn generate(&mut self, grid: &mut Grid, _c: Option<Coords>) {
+    fn generate(&mut self, grid: &mut Grid, _c: Option<Coords>, rng: &mut dyn RngCore) {
         if _c.is_some() {
             eprintln!("Algorithm `{}` doesn't suppoer `start_coords`", self.name())
         }
@@ -206,8 +216,8 @@ impl Algorithm for Eller {
 
         for row in 0..grid.height() {
             let is_last_row = row == grid.height() - 1;
-            self.connect_disjoint_sets(&mut state, grid, is_last_row);
-            state = self.add_vertical_connections(&mut state, grid, is_last_row);
+            self.connect_disjoint_sets(&mut state, grid, is_last_row, rng);
+            state = self.add_vertical_connections(&mut state, grid, is_last_row, rng);
         }
     }
 
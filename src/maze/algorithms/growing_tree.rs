@@ -1,14 +1,14 @@
 use super::Algorithm;
 use crate::maze::grid::{Grid, cell::Cell};
-use crate::utils::types::Coords;
-use clap::ValueEnum;
+use crate::utils::{rand::shuffle, types::Coords};
 use rand::prelude::*;
 
 /// An enumeration over supported cell selection methods for the "Growing Tree" algorithm
 ///
 /// Each method represents the way a new cell is selected causing the "Growing Tree" algorithm
 /// to imitate other algorithms or theirs combinations
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
 pub enum Method {
     /// Selects the most recently added cell, thus imitating the recursive backtracker
     Newest,
@@ -47,6 +47,7 @@ pub enum Method {
 /// You can find plenty of supported methods in the [Method] enum.
 pub struct GrowingTree {
     method: Method,
+    spiral_bias: f64,
 }
 
 impl GrowingTree {
@@ -59,12 +60,40 @@ impl GrowingTree {
     /// let algorithm = GrowingTree::new(Method::Newest);
     /// ```
     pub const fn new(method: Method) -> GrowingTree {
-        GrowingTree { method }
+        GrowingTree {
+            method,
+            spiral_bias: 0.0,
+        }
     }
 
-    fn choose_index(&self, ceil: usize) -> usize {
-        let mut rng = rand::rng();
+    /// Sets a probability of growing the tree in a spiral from a cell and returns itself
+    ///
+    /// Instead of trying the neighbours of a chosen cell in a random order, the algorithm keeps
+    /// going in the direction it entered the cell and turns clockwise before turning
+    /// counter-clockwise when it can't, which winds the corridors into spirals. With the
+    /// [Newest](Method::Newest) method and a bias of 1, the maze starts with a single spiral
+    /// corridor from the start cell. A bias of 0 keeps the random order, while values out of the
+    /// `0..=1` range are clamped. The maze stays perfect regardless of the bias.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let maze = OrthogonalMazeBuilder::new()
+    ///     .width(12)
+    ///     .height(12)
+    ///     .algorithm(Box::new(GrowingTree::new(Method::Newest).spiral_bias(0.9)))
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert!(maze.is_valid());
+    /// ```
+    pub const fn spiral_bias(mut self, bias: f64) -> Self {
+        self.spiral_bias = bias;
+        self
+    }
 
+    fn choose_index(&self, ceil: usize, rng: &mut dyn RngCore) -> usize {
         match self.method {
             Method::Oldest => 0,
             Method::Newest => ceil - 1,
@@ -113,17 +142,41 @@ impl GrowingTree {
 ///
 /// 4. Repeats #3 until the C is empty.
 impl Algorithm for GrowingTree {
-    fn generate(&mut self, grid: &mut Grid, start_coords: Option<Coords>) {
+    fn generate(&mut self, grid: &mut Grid, start_coords: Option<Coords>, rng: &mut dyn RngCore) {
         let mut directions = [Cell::NORTH, Cell::SOUTH, Cell::WEST, Cell::EAST];
         let mut cells = vec![];
-        let start_coords = start_coords.unwrap_or_else(|| get_rand_coords(grid));
+        let start_coords = start_coords.unwrap_or_else(|| get_rand_coords(grid, rng));
         cells.push(start_coords);
 
+        // Directions the cells were entered in, only tracked for growing spirals
+        let spiral_bias = if self.spiral_bias.is_nan() {
+            0.0
+        } else {
+            self.spiral_bias.clamp(0.0, 1.0)
+        };
+        let tracked = if spiral_bias > 0.0 {
+            grid.width() * grid.height()
+        } else {
+            0
+        };
+        let mut headings: Vec<Option<Cell>> = vec![None; tracked];
+
         while !cells.is_empty() {
-            let mut index = Some(self.choose_index(cells.len()));
+            let mut index = Some(self.choose_index(cells.len(), rng));
             let coords = cells[index.unwrap_or(0)];
 
-            directions.shuffle(&mut rand::rng());
+            let heading = grid
+                .index(coords)
+                .and_then(|i| headings.get(i).copied().flatten());
+            match heading {
+                // Straight ahead, then clockwise, then counter-clockwise, and back the last
+                Some(heading) if rng.random_bool(spiral_bias) => {
+                    let clockwise = heading.rotated_cw();
+                    let back = clockwise.rotated_cw();
+                    directions = [heading, clockwise, back.rotated_cw(), back];
+                }
+                _ => shuffle(&mut directions, rng),
+            }
             for dir in directions {
                 let next = match grid.get_next_cell_coords(coords, dir) {
                     Ok(next) => next,
@@ -135,6 +188,9 @@ impl Algorithm for GrowingTree {
                 }
 
                 if let Ok(next) = grid.carve_passage(coords, dir) {
+                    if let Some(heading) = grid.index(next).and_then(|i| headings.get_mut(i)) {
+                        *heading = Some(dir);
+                    }
                     cells.push(next);
                     index = None;
                     break;
@@ -156,8 +212,7 @@ impl Algorithm for GrowingTree {
     }
 }
 
-fn get_rand_coords(grid: &Grid) -> Coords {
-    let mut rng = rand::rng();
+fn get_rand_coords(grid: &Grid, rng: &mut dyn RngCore) -> Coords {
     let x = rng.random_range(0..grid.width());
     let y = rng.random_range(0..grid.height());
     (x, y)
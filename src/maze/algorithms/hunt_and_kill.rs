@@ -1,6 +1,6 @@
 use super::Algorithm;
 use crate::{
-    maze::grid::{Grid, cell::Cell},
+    maze::grid::{Grid, cell::Cell, pole::Pole},
     utils::types::Coords,
 };
 use rand::prelude::*;
@@ -22,11 +22,8 @@ impl HuntAndKill {
         }
     }
 
-    fn walk(&self, coords: Coords, grid: &mut Grid) -> Option<Coords> {
-        let mut directions = [Cell::NORTH, Cell::SOUTH, Cell::WEST, Cell::EAST];
-        directions.shuffle(&mut rand::rng());
-
-        for dir in directions {
+    fn walk(&self, coords: Coords, grid: &mut Grid, rng: &mut dyn RngCore) -> Option<Coords> {
+        for dir in Pole::shuffled(rng).map(Cell::from) {
             if let Ok(next_coords) = grid.get_next_cell_coords(coords, dir) {
                 if !grid.is_cell_visited(next_coords) {
                     return grid.carve_passage(coords, dir).ok();
@@ -99,11 +96,12 @@ impl Default for HuntAndKill {
 /// candidate cell, this implementation has a simple optimization that speeds up the later stages of
 /// the algorithm. Thus, this algorithm is still pretty fast
 impl Algorithm for HuntAndKill {
-    fn generate(&mut self, grid: &mut Grid, start_coords: Option<Coords>) {
-        let (mut x, mut y) = start_coords.unwrap_or_else(|| get_start_coords(grid));
+    fn generate(&mut self, grid: &mut Grid, start_coords: Option<Coords>, rng: &mut dyn RngCore) {
+        let (mut x, mut y) = start_coords.unwrap_or_else(|| get_start_coords(grid, rng));
+        self.hunt_start_index = 0;
 
         loop {
-            if let Some((nx, ny)) = self.walk((x, y), grid) {
+            if let Some((nx, ny)) = self.walk((x, y), grid, rng) {
                 x = nx;
                 y = ny;
             } else if let Some((nx, ny)) = self.hunt(grid) {
@@ -124,8 +122,7 @@ impl Algorithm for HuntAndKill {
     }
 }
 
-fn get_start_coords(grid: &Grid) -> Coords {
-    let mut rng = rand::rng();
+fn get_start_coords(grid: &Grid, rng: &mut dyn RngCore) -> Coords {
     let y = rng.random_range(0..grid.height());
     let x = rng.random_range(0..grid.width());
     (x, y)
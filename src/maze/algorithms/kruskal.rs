@@ -1,9 +1,10 @@
-use rand::prelude::SliceRandom;
+use rand::prelude::*;
 
 use super::Algorithm;
 use crate::maze::grid::cell::Cell;
 use crate::maze::grid::Grid;
 use crate::utils::arena::{ArenaTree, NodeId};
+use crate::utils::rand::shuffle;
 use crate::utils::types::Coords;
 
 type Edge = (usize, usize, Cell);
@@ -14,7 +15,57 @@ type Edges = Vec<Edge>;
 /// Kruskal’s algorithm is a method for producing a minimal spanning tree from a weighted graph.
 /// The randomized version of it can be used for generating a rather convincing maze very
 /// effectively.
-pub struct Kruskal;
+///
+/// With a [room chance](Kruskal::room_chance), walls between already connected cells are randomly
+/// carved too, which opens up rooms and loops.
+pub struct Kruskal {
+    room_chance: f64,
+}
+
+impl Kruskal {
+    /// Create a new instance of the algorithm generating a perfect maze
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::Kruskal;
+    ///
+    /// let algorithm = Kruskal::new();
+    /// ```
+    pub const fn new() -> Kruskal {
+        Kruskal { room_chance: 0.0 }
+    }
+
+    /// Sets a chance of carving a wall between two already connected cells and returns itself
+    ///
+    /// The chance in `[0, 1]` is the probability of carving an edge that would otherwise be thrown
+    /// away since it connects cells of the same tree. `0` generates a perfect maze, while higher
+    /// values produce more open and loopy mazes. Values out of the range are clamped.
+    ///
+    /// This intentionally breaks the tree property: a maze with a non-zero chance may have
+    /// multiple paths between two cells.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::{Kruskal, OrthogonalMazeBuilder};
+    ///
+    /// let maze = OrthogonalMazeBuilder::new()
+    ///     .algorithm(Box::new(Kruskal::new().room_chance(0.2)))
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert!(maze.is_valid());
+    /// ```
+    pub const fn room_chance(mut self, room_chance: f64) -> Self {
+        self.room_chance = room_chance;
+        self
+    }
+}
+
+impl Default for Kruskal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 /// An implementation of the Kruskal's algorithm for generating mazes.
 /// Does not support start coords.
@@ -24,7 +75,7 @@ pub struct Kruskal;
 /// 1. Throw all the edges in the graph into a set.
 ///
 /// 2. Pull out the edge at random. If the edge connects two disjoint trees, join the trees.
-///    Otherwise, throw that edge away.
+///    Otherwise, throw that edge away, unless it is carved with the room chance.
 ///
 /// 3. Repeat until there are no more edges left in the set.
 ///  
@@ -32,13 +83,18 @@ pub struct Kruskal;
 ///
 /// The `generate` function will warn in case a start_coords is passed.
 impl Algorithm for Kruskal {
-    fn generate(&mut self, grid: &mut Grid, _c: Option<Coords>) {
+    fn generate(&mut self, grid: &mut Grid, _c: Option<Coords>, rng: &mut dyn RngCore) {
         if _c.is_some() {
             eprintln!("Algorithm `{}` doesn't suppoer `start_coords`", self.name())
         }
+        let room_chance = if self.room_chance.is_nan() {
+            0.0
+        } else {
+            self.room_chance.clamp(0.0, 1.0)
+        };
         let mut arena = populate_arena(grid);
         let mut edges: Edges = populate_edges(grid);
-        edges.shuffle(&mut rand::rng());
+        shuffle(&mut edges, rng);
 
         while !edges.is_empty() {
             let edge: Option<Edge> = edges.pop();
@@ -54,6 +110,8 @@ impl Algorithm for Kruskal {
             if !arena.connected(node1, node2) {
                 arena.connect(node1, node2);
                 grid.carve_passage((x, y), direction).unwrap();
+            } else if room_chance > 0.0 && rng.random_bool(room_chance) {
+                grid.carve_passage((x, y), direction).unwrap();
             }
         }
     }
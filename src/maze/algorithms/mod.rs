@@ -2,6 +2,7 @@
 
 mod aldous_broder;
 mod binary_tree;
+mod blend;
 mod eller;
 mod growing_tree;
 mod hunt_and_kill;
@@ -9,32 +10,52 @@ mod kruskal;
 mod prim;
 mod recursive_backtracking;
 mod recursive_division;
+mod river;
 mod sidewinder;
 
 pub use aldous_broder::AldousBroder;
 pub use binary_tree::{Bias, BinaryTree};
+pub use blend::Blend;
 pub use eller::Eller;
 pub use growing_tree::{GrowingTree, Method};
 pub use hunt_and_kill::HuntAndKill;
 pub use kruskal::Kruskal;
 pub use prim::Prim;
-pub use recursive_backtracking::RecursiveBacktracking;
+pub use recursive_backtracking::{BacktrackingStepper, OrderedBacktracking, RecursiveBacktracking};
 pub use recursive_division::RecursiveDivision;
+pub use river::River;
 pub use sidewinder::Sidewinder;
 
 use crate::{maze::grid::Grid, utils::types::Coords};
+use rand::RngCore;
 
 pub(super) const BOOL_TRUE_PROBABILITY: f64 = 0.5;
 
 /// A trait for generating a maze using a selected algorithm
-pub trait Algorithm {
+///
+/// An algorithm is stored in a built maze for [regenerating](crate::maze::OrthogonalMaze::regenerate)
+/// it, so it must be [Send] to let the maze be shared across threads, e.g. as a Bevy resource.
+pub trait Algorithm: Send {
     /// Runs algorithm through the given Grid object, thus mutating the grid and generating a new
     /// maze.
-    fn generate(&mut self, grid: &mut Grid, start_coords: Option<Coords>);
+    ///
+    /// All the random choices are drawn from a given random number generator, so the same seed
+    /// always generates the same maze. Any generator implementing [RngCore] can be used, e.g. a
+    /// seeded [StdRng](rand::rngs::StdRng) or a custom deterministic one.
+    fn generate(&mut self, grid: &mut Grid, start_coords: Option<Coords>, rng: &mut dyn RngCore);
 
     /// Verifies if algorithm supports start coords
     fn has_start_coords(&self) -> bool;
 
+    /// Returns the minimum `(width, height)` of a grid the algorithm supports
+    ///
+    /// Every algorithm supports single-row and single-column grids by default, and all the built-in
+    /// ones do. Override it when an algorithm cannot carve a smaller grid, so the builder rejects
+    /// it with a [BuildError](crate::maze::BuildError) instead of generating an invalid maze.
+    fn min_size(&self) -> (usize, usize) {
+        (1, 1)
+    }
+
     // Cannot be a const because of dyn-trait compatibility
     /// Algorithm name
     fn name(&self) -> &'static str;
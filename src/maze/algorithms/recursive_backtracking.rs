@@ -1,18 +1,121 @@
 use super::Algorithm;
-use crate::maze::grid::{Grid, cell::Cell};
+use crate::maze::errors::BuildError;
+use crate::maze::grid::{Grid, cell::Cell, pole::Pole};
 use crate::utils::types::Coords;
-use rand::prelude::*;
+use rand::RngCore;
+use std::array::IntoIter;
 
 /// The "Recursive Backtracking" algorithm for generating mazes
 ///
 /// This algorithm quite effectively creates narrow passages with multiple dead-ends which makes it
 /// easy to get lost, thus eventually making it hard to solve the maze.
 ///
-/// In most cases, this algorithm is fast. However, due to its recursive nature, it requires stack
+/// In most cases, this algorithm is fast. However, due to its recursive nature, it requires memory
 /// space proportional to the longest acyclic path, which is, in the worst case, the entire maze. So
 /// for exceptionally large mazes this algorithm can be fairly inefficient.
 pub struct RecursiveBacktracking;
 
+impl RecursiveBacktracking {
+    /// Create a new instance of the algorithm trying the directions of every cell in a given order
+    /// instead of a random one
+    ///
+    /// Orders are indexed by cells in the row-major order, i.e. the order at index `y * width + x`
+    /// is the one of the cell at `(x, y)`, and are repeated for the cells past the last order, so a
+    /// single order applies to every cell. Once the algorithm carves into a cell, it tries the
+    /// directions of that cell one by one, and carves a passage in a direction as soon as it leads
+    /// to an unvisited cell. Without any orders, every cell tries `N`, `S`, `W` and `E` in turn.
+    ///
+    /// Every order must be a permutation of the four poles, i.e. list each of them exactly once, as
+    /// a cell never tries a direction missing from its order, which would leave a part of the maze
+    /// unreachable. Returns a [BuildError] naming the first order that is not a permutation.
+    ///
+    /// The generation never draws from the random number generator, so the maze only depends on
+    /// the orders, the start coords and the size, e.g. for fixtures which must stay the same after
+    /// updating `rand`.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let build = |seed| {
+    ///     OrthogonalMazeBuilder::new()
+    ///         .algorithm(Box::new(
+    ///             RecursiveBacktracking::from_order(&[
+    ///                 [Pole::E, Pole::S, Pole::W, Pole::N],
+    ///                 [Pole::S, Pole::E, Pole::N, Pole::W],
+    ///             ])
+    ///             .unwrap(),
+    ///         ))
+    ///         .seed(seed)
+    ///         .build()
+    ///         .unwrap()
+    /// };
+    ///
+    /// assert!(build(1).is_valid());
+    /// assert_eq!(build(1), build(2));
+    /// assert!(RecursiveBacktracking::from_order(&[[Pole::N; 4]]).is_err());
+    /// ```
+    pub fn from_order(orders: &[[Pole; 4]]) -> Result<OrderedBacktracking, BuildError> {
+        if let Some(idx) = orders
+            .iter()
+            .position(|order| !Pole::all().iter().all(|pole| order.contains(pole)))
+        {
+            return Err(BuildError::reason(format!(
+                "The order at index {} must list each of `N`, `S`, `W` and `E` exactly once",
+                idx
+            )));
+        }
+
+        Ok(OrderedBacktracking {
+            orders: orders.to_vec(),
+        })
+    }
+
+    /// Returns a [BacktrackingStepper] generating a maze on a given grid one passage at a time,
+    /// e.g. for showing the generation frame by frame
+    ///
+    /// The stepper starts at the start coords, or the top left cell without them, and once it's
+    /// done, the grid is the same as the one [generate](Algorithm::generate) makes with the same
+    /// start coords and random number generator.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    /// use rand::{rngs::StdRng, SeedableRng};
+    ///
+    /// let mut grid = OrthogonalMaze::new(5, 5).get_grid_mut().clone();
+    /// let mut rng = StdRng::seed_from_u64(1);
+    /// let mut stepper = RecursiveBacktracking::stepper(&mut grid, None, &mut rng);
+    ///
+    /// let mut passages = 0;
+    /// while let Some([from, to]) = stepper.step() {
+    ///     assert!(stepper.grid().is_cell_visited(from));
+    ///     assert!(stepper.grid().is_cell_visited(to));
+    ///     passages += 1;
+    /// }
+    ///
+    /// assert_eq!(passages, 5 * 5 - 1);
+    /// ```
+    pub fn stepper<'a>(
+        grid: &'a mut Grid,
+        start_coords: Option<Coords>,
+        rng: &'a mut dyn RngCore,
+    ) -> BacktrackingStepper<'a> {
+        BacktrackingStepper::new(
+            grid,
+            start_coords.unwrap_or((0, 0)),
+            Box::new(|_| Pole::shuffled(rng)),
+        )
+    }
+}
+
+/// The "Recursive Backtracking" algorithm trying the directions of cells in preset orders
+///
+/// See [RecursiveBacktracking::from_order] for how the orders are applied.
+pub struct OrderedBacktracking {
+    orders: Vec<[Pole; 4]>,
+}
+
 /// An implementation of the "Recursive Backtracking" algorithm for generating mazes.
 ///
 /// Here is how it works:
@@ -28,9 +131,40 @@ pub struct RecursiveBacktracking;
 /// 4. The algorithm ends when the process has backed all the way up to the starting
 ///    point.
 impl Algorithm for RecursiveBacktracking {
-    fn generate(&mut self, grid: &mut Grid, start_coords: Option<Coords>) {
+    fn generate(&mut self, grid: &mut Grid, start_coords: Option<Coords>, rng: &mut dyn RngCore) {
+        let mut stepper = RecursiveBacktracking::stepper(grid, start_coords, rng);
+        while stepper.step().is_some() {}
+    }
+
+    fn has_start_coords(&self) -> bool {
+        true
+    }
+
+    fn name(&self) -> &'static str {
+        "RecursiveBacktracking"
+    }
+}
+
+/// An implementation of the "Recursive Backtracking" algorithm for generating mazes, which works
+/// like the one of [RecursiveBacktracking], except for choosing walls at each point in the order
+/// preset for it rather than randomly.
+impl Algorithm for OrderedBacktracking {
+    fn generate(&mut self, grid: &mut Grid, start_coords: Option<Coords>, _rng: &mut dyn RngCore) {
         let start_coords = start_coords.unwrap_or((0, 0));
-        carve_passages_from(start_coords, grid);
+        let width = grid.width();
+        let orders = &self.orders;
+        let mut stepper = BacktrackingStepper::new(
+            grid,
+            start_coords,
+            Box::new(|(x, y)| {
+                if orders.is_empty() {
+                    Pole::all()
+                } else {
+                    orders[(y * width + x) % orders.len()]
+                }
+            }),
+        );
+        while stepper.step().is_some() {}
     }
 
     fn has_start_coords(&self) -> bool {
@@ -42,22 +176,117 @@ impl Algorithm for RecursiveBacktracking {
     }
 }
 
-fn carve_passages_from(coords: Coords, grid: &mut Grid) {
-    let mut dirs = [Cell::NORTH, Cell::SOUTH, Cell::WEST, Cell::EAST];
-    dirs.shuffle(&mut rand::rng());
+/// A "Recursive Backtracking" generation carving one passage per [step](BacktrackingStepper::step)
+///
+/// See [RecursiveBacktracking::stepper] for how to make one. Instead of recursing, the stepper keeps
+/// the cells it went through on a stack, along with the walls of every cell it hasn't tried yet.
+pub struct BacktrackingStepper<'a> {
+    grid: &'a mut Grid,
+    directions: Box<dyn FnMut(Coords) -> [Pole; 4] + 'a>,
+    stack: Vec<(Coords, IntoIter<Pole, 4>)>,
+}
+
+impl<'a> BacktrackingStepper<'a> {
+    /// Create a new stepper starting at given coords, which tries the walls of every cell in the
+    /// order a given function returns for the cell
+    fn new(
+        grid: &'a mut Grid,
+        start_coords: Coords,
+        mut directions: Box<dyn FnMut(Coords) -> [Pole; 4] + 'a>,
+    ) -> BacktrackingStepper<'a> {
+        let stack = vec![(start_coords, directions(start_coords).into_iter())];
+        BacktrackingStepper {
+            grid,
+            directions,
+            stack,
+        }
+    }
+
+    /// Carves the next passage and returns the coords of the cell it's carved from and of the
+    /// newly visited cell, or `None` once the generation is done
+    ///
+    /// Backing up from the cells without unvisited neighbours happens within the step carving the
+    /// next passage.
+    pub fn step(&mut self) -> Option<[Coords; 2]> {
+        while let Some((coords, dirs)) = self.stack.last_mut() {
+            let coords = *coords;
+            let Some(dir) = dirs.next().map(Cell::from) else {
+                self.stack.pop();
+                continue;
+            };
 
-    for dir in dirs {
-        let next = match grid.get_next_cell_coords(coords, dir) {
-            Ok(next) => next,
-            Err(_) => continue,
-        };
+            let next = match self.grid.get_next_cell_coords(coords, dir) {
+                Ok(next) => next,
+                Err(_) => continue,
+            };
 
-        if grid.is_cell_visited(next) {
-            continue;
+            if self.grid.is_cell_visited(next) {
+                continue;
+            }
+
+            if let Ok(next) = self.grid.carve_passage(coords, dir) {
+                self.stack.push((next, (self.directions)(next).into_iter()));
+                return Some([coords, next]);
+            }
         }
 
-        if let Ok(next) = grid.carve_passage(coords, dir) {
-            carve_passages_from(next, grid);
+        None
+    }
+
+    /// Returns the coords of the cell the generation is at, or `None` once it's done
+    pub fn current(&self) -> Option<Coords> {
+        self.stack.last().map(|(coords, _)| *coords)
+    }
+
+    /// Returns the grid as generated so far
+    pub const fn grid(&self) -> &Grid {
+        self.grid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn stepper_matches_generate() {
+        for (seed, start_coords) in [(1, None), (2, Some((3, 2))), (3, Some((6, 4)))] {
+            let mut generated = Grid::new(7, 5);
+            let mut rng = StdRng::seed_from_u64(seed);
+            RecursiveBacktracking.generate(&mut generated, start_coords, &mut rng);
+
+            let mut stepped = Grid::new(7, 5);
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut stepper = RecursiveBacktracking::stepper(&mut stepped, start_coords, &mut rng);
+            assert_eq!(stepper.current(), Some(start_coords.unwrap_or((0, 0))));
+
+            let mut steps = 0;
+            while let Some([from, to]) = stepper.step() {
+                assert_eq!(stepper.current(), Some(to));
+                assert!(stepper.grid().shared_wall(from, to).is_some());
+                steps += 1;
+            }
+            assert_eq!(steps, 7 * 5 - 1);
+            assert_eq!(stepper.current(), None);
+            assert_eq!(stepper.step(), None);
+
+            assert_eq!(stepper.grid(), &generated);
         }
     }
+
+    #[test]
+    fn from_order_rejects_non_permutations() {
+        let valid = [Pole::E, Pole::S, Pole::W, Pole::N];
+
+        assert!(RecursiveBacktracking::from_order(&[]).is_ok());
+        assert!(RecursiveBacktracking::from_order(&[valid, Pole::all()]).is_ok());
+        assert_eq!(
+            RecursiveBacktracking::from_order(&[valid, [Pole::N, Pole::N, Pole::S, Pole::W]])
+                .err()
+                .unwrap()
+                .reason,
+            "The order at index 1 must list each of `N`, `S`, `W` and `E` exactly once"
+        );
+    }
 }
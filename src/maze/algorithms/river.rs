@@ -0,0 +1,158 @@
+use super::Algorithm;
+use crate::maze::grid::{Grid, cell::Cell, pole::Pole};
+use crate::utils::types::Coords;
+use rand::prelude::*;
+
+/// The "River" algorithm for generating mazes
+///
+/// This algorithm first lays a long meandering corridor, "a river", between an entrance and an
+/// exit, and then grows the rest of the maze off the river as many short dead-end branches. So
+/// unlike the even texture of the recursive backtracker, the maze has a clearly dominant main route
+/// that the solution follows from the entrance to the exit.
+///
+/// The entrance is at the start coords, or the top left cell without them, and the exit is at the
+/// cell mirroring the entrance through the center of the maze unless set otherwise, e.g. the
+/// bottom right cell for the top left entrance.
+///
+/// # Example
+/// ```
+/// use bevy_knossos::maze::*;
+///
+/// let maze = OrthogonalMazeBuilder::new()
+///     .width(20)
+///     .height(20)
+///     .algorithm(Box::new(River::new().exit((19, 0))))
+///     .build()
+///     .unwrap();
+///
+/// assert!(maze.is_valid());
+/// assert!(maze.solve((0, 0), (19, 0)).unwrap().length() > 20);
+/// ```
+pub struct River {
+    exit: Option<Coords>,
+}
+
+impl River {
+    /// Create a new instance of the algorithm with the exit mirroring the entrance
+    pub const fn new() -> River {
+        River { exit: None }
+    }
+
+    /// Sets coords of the exit the river leads to and returns itself
+    ///
+    /// Coords out of the maze are clamped to its bounds.
+    pub const fn exit(mut self, coords: Coords) -> Self {
+        self.exit = Some(coords);
+        self
+    }
+}
+
+impl Default for River {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An implementation of the "River" algorithm for generating mazes
+///
+/// Here is how it works:
+///
+/// 1. Walks randomly from the entrance to unvisited adjacent cells, backing up from the cells
+///    without them, until the walk reaches the exit. The walk doesn't carve anything yet.
+///
+/// 2. Carves the river along the cells the walk went through from the entrance to the exit,
+///    leaving out the cells it backed up from.
+///
+/// 3. Chooses a random cell of the maze with unvisited adjacent cells and carves a passage to one
+///    of them, then repeats until every cell is visited. Choosing from all the cells at random
+///    grows many short branches rather than long corridors competing with the river.
+impl Algorithm for River {
+    fn generate(&mut self, grid: &mut Grid, start_coords: Option<Coords>, rng: &mut dyn RngCore) {
+        let (width, height) = (grid.width(), grid.height());
+        let entrance = start_coords.unwrap_or((0, 0));
+        let exit = self.exit.map_or(
+            (width - 1 - entrance.0, height - 1 - entrance.1),
+            |(x, y)| (x.min(width - 1), y.min(height - 1)),
+        );
+
+        let river = winding_path(grid, entrance, exit, rng);
+        let mut in_maze = vec![false; width * height];
+        for (i, &coords) in river.iter().enumerate() {
+            in_maze[grid.index(coords).unwrap()] = true;
+            if let Some(&next) = river.get(i + 1) {
+                let pole = grid
+                    .shared_wall(coords, next)
+                    .expect("Cells of a walk are adjacent");
+                grid.carve_passage(coords, Cell::from(pole)).unwrap();
+            }
+        }
+
+        let mut active = river;
+        while !active.is_empty() {
+            let i = rng.random_range(0..active.len());
+            let coords = active[i];
+            let next = Pole::shuffled(rng)
+                .map(Cell::from)
+                .into_iter()
+                .find_map(|dir| {
+                    grid.get_next_cell_coords(coords, dir)
+                        .ok()
+                        .filter(|next| !in_maze[grid.index(*next).unwrap()])
+                        .map(|next| (dir, next))
+                });
+
+            match next {
+                Some((dir, next)) => {
+                    grid.carve_passage(coords, dir).unwrap();
+                    in_maze[grid.index(next).unwrap()] = true;
+                    active.push(next);
+                }
+                None => {
+                    active.swap_remove(i);
+                }
+            }
+        }
+    }
+
+    fn has_start_coords(&self) -> bool {
+        true
+    }
+
+    fn name(&self) -> &'static str {
+        "River"
+    }
+}
+
+/// Returns cells of a random self-avoiding walk from one cell to another, which backs up from the
+/// cells without unvisited neighbours
+fn winding_path(grid: &Grid, from: Coords, to: Coords, rng: &mut dyn RngCore) -> Vec<Coords> {
+    let mut seen = vec![false; grid.width() * grid.height()];
+    seen[grid.index(from).unwrap()] = true;
+    let mut path = vec![from];
+
+    while let Some(&coords) = path.last() {
+        if coords == to {
+            break;
+        }
+
+        let next = Pole::shuffled(rng)
+            .map(Cell::from)
+            .into_iter()
+            .find_map(|dir| {
+                grid.get_next_cell_coords(coords, dir)
+                    .ok()
+                    .filter(|next| !seen[grid.index(*next).unwrap()])
+            });
+        match next {
+            Some(next) => {
+                seen[grid.index(next).unwrap()] = true;
+                path.push(next);
+            }
+            None => {
+                path.pop();
+            }
+        }
+    }
+
+    path
+}
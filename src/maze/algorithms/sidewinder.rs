@@ -36,21 +36,20 @@ pub struct Sidewinder;
 ///
 /// The `generate` function will warn in case a start_coords is passed.
 impl Algorithm for Sidewinder {
-    fn generate(&mut self, grid: &mut Grid, _c: Option<Coords>) {
+    fn generate(&mut self, grid: &mut Grid, _c: Option<Coords>, rng: &mut dyn RngCore) {
         if _c.is_some() {
             eprintln!("Algorithm `{}` doesn't suppoer `start_coords`", self.name())
         }
-        let mut rng = rand::rng();
-
         for y in 0..grid.height() {
             let mut run_start = 0;
 
             for x in 0..grid.width() {
                 let carve_east: bool = rng.random();
 
-                if y == 0 || (carve_east && x + 1 < grid.width()) {
+                // The edges of a torus have neighbours, so the runs must not go off them
+                if x + 1 < grid.width() && (y == 0 || carve_east) {
                     grid.carve_passage((x, y), Cell::EAST).ok();
-                } else {
+                } else if y > 0 {
                     let rand_x = rng.random_range(run_start..=x);
                     grid.carve_passage((rand_x, y), Cell::NORTH).ok();
                     run_start = x + 1;
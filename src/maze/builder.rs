@@ -1,5 +1,8 @@
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+
 use crate::maze::algorithms::{Algorithm, RecursiveBacktracking};
-use crate::maze::OrthogonalMaze;
+use crate::maze::maze::Generator;
+use crate::maze::{GenerationObserver, Grid, Mask, OrthogonalMaze, PostProcess, Symmetry};
 use crate::utils::types::Coords;
 
 use super::errors::BuildError;
@@ -10,6 +13,18 @@ pub struct OrthogonalMazeBuilder {
     height: usize,
     algorithm: Box<dyn Algorithm>,
     start_coords: Option<Coords>,
+    min_corridor: usize,
+    seed: Option<u64>,
+    rng: Option<Box<dyn RngCore + Send>>,
+    openings_seed: Option<u64>,
+    observer: Option<Box<dyn GenerationObserver>>,
+    symmetry: Option<Symmetry>,
+    goal_room: Option<usize>,
+    mask: Option<Mask>,
+    sparsity: Option<f64>,
+    torus: bool,
+    partial: Option<Grid>,
+    post_processes: Vec<PostProcess>,
 }
 
 impl OrthogonalMazeBuilder {
@@ -20,6 +35,58 @@ impl OrthogonalMazeBuilder {
             height: 10,
             algorithm: Box::new(RecursiveBacktracking),
             start_coords: None,
+            min_corridor: 0,
+            seed: None,
+            rng: None,
+            openings_seed: None,
+            observer: None,
+            symmetry: None,
+            goal_room: None,
+            mask: None,
+            sparsity: None,
+            torus: false,
+            partial: None,
+            post_processes: vec![],
+        }
+    }
+
+    /// Returns a new instance of a builder completing a partially carved grid, e.g. with a few
+    /// corridors carved by hand, with the default algorithm
+    ///
+    /// The maze takes the dimensions and the [wrapping](Grid::new_torus) of the grid. Every cell
+    /// with a passage or an opening in the grid keeps them, while the algorithm generates the rest
+    /// of the maze around the carved cells and connects it to them through randomly chosen
+    /// walls. A carved part without loops thus becomes a part of a perfect maze. The walls between
+    /// two carved cells are only broken when there is no other way to connect the carved parts,
+    /// e.g. when they cover the whole grid. The partial grid can't be combined with a
+    /// [symmetry](OrthogonalMazeBuilder::symmetry), and a
+    /// [goal room](OrthogonalMazeBuilder::goal_room) or a [mask](OrthogonalMazeBuilder::mask) may
+    /// still change the carved cells they cover.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let mut grid = Grid::new(10, 10);
+    /// for x in 0..9 {
+    ///     grid.carve_passage((x, 0), Cell::EAST).unwrap();
+    /// }
+    ///
+    /// let maze = OrthogonalMazeBuilder::from_grid(grid)
+    ///     .algorithm(Box::new(Kruskal::new()))
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert!(maze.is_valid());
+    /// assert!((0..9).all(|x| maze[(x, 0)].contains(Cell::EAST)));
+    /// ```
+    pub fn from_grid(grid: Grid) -> Self {
+        OrthogonalMazeBuilder {
+            width: grid.width(),
+            height: grid.height(),
+            torus: grid.is_torus(),
+            partial: Some(grid),
+            ..Self::new()
         }
     }
 
@@ -47,15 +114,425 @@ impl OrthogonalMazeBuilder {
         self
     }
 
+    /// Sets a hint for a minimum number of cells straight corridors should span and returns itself
+    ///
+    /// After generating a maze, a post-processing pass swaps passages to extend straight runs
+    /// shorter than `min_corridor` cells where possible, so there are fewer single-cell zigzags.
+    /// This is approximate and doesn't guarantee every corridor meets the minimum. Every swap keeps
+    /// the number of passages and the connectivity of cells, so a perfect maze remains perfect.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let maze = OrthogonalMazeBuilder::new()
+    ///     .algorithm(Box::new(Prim::new()))
+    ///     .min_corridor(4)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert!(maze.is_valid());
+    /// ```
+    pub const fn min_corridor(mut self, cells: usize) -> Self {
+        self.min_corridor = cells;
+        self
+    }
+
+    /// Sets a seed for the random number generator and returns itself
+    ///
+    /// The same seed, dimensions and algorithm always build the same maze. Without a seed, the
+    /// generator is seeded from the OS entropy source. The seed is used for both the
+    /// [structure](OrthogonalMazeBuilder::structure_seed) and the
+    /// [openings](OrthogonalMazeBuilder::openings_seed) of the maze, overriding any of them set
+    /// before.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let maze = OrthogonalMazeBuilder::new().seed(42).build().unwrap();
+    /// let same_maze = OrthogonalMazeBuilder::new().seed(42).build().unwrap();
+    ///
+    /// assert_eq!(maze, same_maze);
+    /// ```
+    pub const fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self.openings_seed = Some(seed);
+        self
+    }
+
+    /// Sets a seed for generating the walls of the maze only and returns itself
+    ///
+    /// Along with an [openings seed](OrthogonalMazeBuilder::openings_seed), the layout of a maze
+    /// and its openings can be reproduced independently, e.g. to compare the difficulty of
+    /// different entrances to a fixed layout.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let build = |openings_seed| {
+    ///     let mut maze = OrthogonalMazeBuilder::new()
+    ///         .structure_seed(1)
+    ///         .openings_seed(openings_seed)
+    ///         .build()
+    ///         .unwrap();
+    ///     let entrances = maze.add_random_entrances(2).unwrap();
+    ///     (maze, entrances)
+    /// };
+    ///
+    /// let (maze, entrances) = build(2);
+    /// assert_eq!(build(2), (maze.clone(), entrances.clone()));
+    ///
+    /// // The same layout with different entrances
+    /// let (other_maze, other_entrances) = build(3);
+    /// assert_ne!(other_entrances, entrances);
+    /// assert_eq!(other_maze.solve((0, 0), (9, 9)), maze.solve((0, 0), (9, 9)));
+    /// ```
+    pub const fn structure_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Sets a custom random number generator for generating the walls of the maze and returns
+    /// itself
+    ///
+    /// Any generator implementing [RngCore] can be plugged in, e.g. a counter-based generator for
+    /// reproducing a maze across platforms, or a stub generator in tests. The generator takes
+    /// precedence over the [structure seed](OrthogonalMazeBuilder::structure_seed), while openings
+    /// are still placed from the [openings seed](OrthogonalMazeBuilder::openings_seed). Randomized
+    /// post-processing draws from a generator seeded from the custom one after the generation, so
+    /// a deterministic generator makes the whole maze deterministic. The generator has to be
+    /// [Send], so that the builder can be moved to another thread, e.g. a background task.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    /// use rand::RngCore;
+    ///
+    /// /// A counter-based generator mixing a counter with SplitMix64
+    /// struct Counter(u64);
+    ///
+    /// impl RngCore for Counter {
+    ///     fn next_u32(&mut self) -> u32 {
+    ///         (self.next_u64() >> 32) as u32
+    ///     }
+    ///
+    ///     fn next_u64(&mut self) -> u64 {
+    ///         self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    ///         let z = (self.0 ^ (self.0 >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    ///         let z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    ///         z ^ (z >> 31)
+    ///     }
+    ///
+    ///     fn fill_bytes(&mut self, dst: &mut [u8]) {
+    ///         for chunk in dst.chunks_mut(8) {
+    ///             chunk.copy_from_slice(&self.next_u64().to_le_bytes()[..chunk.len()]);
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let build = || OrthogonalMazeBuilder::new().rng(Counter(7)).build().unwrap();
+    ///
+    /// assert!(build().is_valid());
+    /// assert_eq!(build(), build());
+    /// ```
+    pub fn rng(mut self, rng: impl RngCore + Send + 'static) -> Self {
+        self.rng = Some(Box::new(rng));
+        self
+    }
+
+    /// Sets a seed for placing random openings of the maze, e.g. with
+    /// [add_random_entrances](OrthogonalMaze::add_random_entrances), and returns itself
+    ///
+    /// The same openings seed places the same openings on a maze of the same dimensions regardless
+    /// of its walls. Without an openings seed, openings are placed with the random number generator
+    /// the maze was generated with. See [structure_seed](OrthogonalMazeBuilder::structure_seed)
+    /// for an example.
+    pub const fn openings_seed(mut self, seed: u64) -> Self {
+        self.openings_seed = Some(seed);
+        self
+    }
+
+    /// Sets an observer notified of generation events, e.g. carved passages, and returns itself
+    ///
+    /// See [GenerationObserver] for an example.
+    pub fn observer(mut self, observer: Box<dyn GenerationObserver>) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    /// Sets a symmetry the maze is generated with and returns itself
+    ///
+    /// The algorithm generates a half or a quadrant of the maze, which is then mirrored, so the
+    /// maze has to be about twice as large as the algorithm requires along the mirrored dimensions.
+    /// See [Symmetry] for how the mirrored parts are stitched.
+    pub const fn symmetry(mut self, symmetry: Symmetry) -> Self {
+        self.symmetry = Some(symmetry);
+        self
+    }
+
+    /// Reserves a goal room of `size`x`size` cells at the deepest point of the maze and returns
+    /// itself
+    ///
+    /// The deepest point is the cell with the longest path from the start coords, or from the top
+    /// left cell without them. After generating the maze, all the inner walls of the room around
+    /// that cell are removed, and exactly one passage connects the room to the rest of the maze,
+    /// e.g. for a boss room at the hardest location of a dungeon. The room has to be smaller than
+    /// the maze in both dimensions.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let maze = OrthogonalMazeBuilder::new()
+    ///     .width(12)
+    ///     .height(12)
+    ///     .goal_room(3)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// // The middle cell of the room has no walls
+    /// assert!(maze.is_valid());
+    /// assert!(maze.iter().any(|(_, cell)| *cell == Cell::all()));
+    /// ```
+    pub const fn goal_room(mut self, size: usize) -> Self {
+        self.goal_room = Some(size);
+        self
+    }
+
+    /// Sets a mask of solid areas the maze is generated around and returns itself
+    ///
+    /// The solid cells keep all of their walls, and the rest of the maze stays connected around
+    /// them, so the cells out of the solid areas can't be split into disconnected parts. A mask
+    /// can't be combined with a [goal room](OrthogonalMazeBuilder::goal_room), which could overlap
+    /// the solid areas. See [Mask] for an example.
+    pub fn mask(mut self, mask: Mask) -> Self {
+        self.mask = Some(mask);
+        self
+    }
+
+    /// Sets a fraction of dead ends to wall off after generating the maze and returns itself
+    ///
+    /// Every walled off dead end loses its only passage and becomes an isolated cell, which leaves
+    /// an imperfect maze with a sparse look, e.g. for decorative or abstract output. Such a maze
+    /// isn't [valid](OrthogonalMaze::is_valid) anymore, and its
+    /// [component count](OrthogonalMaze::component_count) tells how fragmented it became. The
+    /// fraction has to be between 0 and 1, and the dead ends are chosen with the random number
+    /// generator the maze is generated with.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let maze = OrthogonalMazeBuilder::new()
+    ///     .width(12)
+    ///     .height(12)
+    ///     .sparsity(0.5)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert!(!maze.is_valid());
+    /// assert!(maze.component_count() > 1);
+    /// ```
+    pub const fn sparsity(mut self, fraction: f64) -> Self {
+        self.sparsity = Some(fraction);
+        self
+    }
+
+    /// Sets whether the maze wraps around both axes like a [torus](Grid::new_torus) and returns
+    /// itself
+    ///
+    /// Algorithms finding the neighbours of cells with
+    /// [get_next_cell_coords](Grid::get_next_cell_coords), e.g. [RecursiveBacktracking] or
+    /// [Kruskal](crate::maze::Kruskal), carve passages across the edges too, while the rest of them
+    /// generate a maze within the edges, which is still valid on a torus. A torus can't be combined
+    /// with a [symmetry](OrthogonalMazeBuilder::symmetry), a [mask](OrthogonalMazeBuilder::mask) or
+    /// a [goal room](OrthogonalMazeBuilder::goal_room), which are laid out within the edges.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let maze = OrthogonalMazeBuilder::new()
+    ///     .width(12)
+    ///     .height(8)
+    ///     .torus(true)
+    ///     .seed(1)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// // Some passages lead off the western edge to the eastern one
+    /// assert!(maze.is_valid());
+    /// assert!((0..8).any(|y| maze[(0, y)].contains(Cell::WEST) && maze[(11, y)].contains(Cell::EAST)));
+    /// ```
+    pub const fn torus(mut self, torus: bool) -> Self {
+        self.torus = torus;
+        self
+    }
+
+    /// Adds a post-processing step applied after the maze is generated and returns itself
+    ///
+    /// Steps are applied in the order they are added, each to the maze left by the previous one.
+    /// See [PostProcess] for an example.
+    pub fn post_process(mut self, step: PostProcess) -> Self {
+        self.post_processes.push(step);
+        self
+    }
+
     /// Builds a maze and returns a resulting object of the generated orthogonal maze
-    pub fn build(mut self) -> Result<OrthogonalMaze, BuildError> {
-        let mut maze = OrthogonalMaze::new(self.width, self.height);
-        if self.start_coords.is_some() && !self.algorithm.has_start_coords() {
-            Err(BuildError::reason(self.algorithm.name()))
-        } else {
-            self.algorithm
-                .generate(maze.get_grid_mut(), self.start_coords);
-            Ok(maze)
+    ///
+    /// Returns a [BuildError] if the dimensions are smaller than the algorithm supports, or start
+    /// coords are set but not supported by the algorithm or out of the maze bounds, or the goal
+    /// room doesn't fit into the maze, or the mask splits the maze, covers the start coords or is
+    /// set along with a goal room, or the [partial grid](OrthogonalMazeBuilder::from_grid)
+    /// doesn't match the dimensions or is set along with a symmetry, or the sparsity is out of the
+    /// `0..=1` range, or a post-processing step cannot be applied, e.g. there are fewer boundary
+    /// cells than entrances to add.
+    pub fn build(self) -> Result<OrthogonalMaze, BuildError> {
+        self.validate()?;
+
+        let generator = Generator::new(self.algorithm, self.start_coords, self.min_corridor)
+            .observer(self.observer)
+            .symmetry(self.symmetry)
+            .goal_room(self.goal_room)
+            .mask(self.mask)
+            .sparsity(self.sparsity)
+            .partial(self.partial);
+        let mut maze = OrthogonalMaze::with_generator(self.width, self.height, generator);
+        if self.torus {
+            *maze.get_grid_mut() = Grid::new_torus(self.width, self.height);
+        }
+        match self.rng {
+            Some(mut rng) => maze.generate_with(rng.as_mut()),
+            None => {
+                maze.generate(
+                    self.seed
+                        .map_or_else(StdRng::from_os_rng, StdRng::seed_from_u64),
+                );
+                maze.set_seed(self.seed);
+            }
+        }
+        maze.set_openings_seed(self.openings_seed);
+
+        for step in self.post_processes {
+            step.apply(&mut maze)
+                .map_err(|error| BuildError::reason(error.reason))?;
+        }
+
+        Ok(maze)
+    }
+
+    fn validate(&self) -> Result<(), BuildError> {
+        let name = self.algorithm.name();
+        let (min_width, min_height) = self.algorithm.min_size();
+        let min_size = (min_width.max(1), min_height.max(1));
+        let (min_width, min_height) = self
+            .symmetry
+            .map_or(min_size, |symmetry| symmetry.min_size(min_size));
+
+        if self.width < min_width || self.height < min_height {
+            return Err(BuildError::reason(format!(
+                "Algorithm `{}` requires at least a {}x{} maze, but the maze is {}x{}",
+                name, min_width, min_height, self.width, self.height
+            )));
+        }
+
+        if let Some(partial) = &self.partial {
+            if (partial.width(), partial.height()) != (self.width, self.height) {
+                return Err(BuildError::reason(format!(
+                    "The partial grid is {}x{}, but the maze is {}x{}",
+                    partial.width(),
+                    partial.height(),
+                    self.width,
+                    self.height
+                )));
+            }
+            if self.symmetry.is_some() {
+                return Err(BuildError::reason(
+                    "A partial grid cannot be combined with a symmetry".to_string(),
+                ));
+            }
+        }
+
+        if let Some(mask) = &self.mask {
+            if !mask.is_connected(self.width, self.height) {
+                return Err(BuildError::reason(format!(
+                    "The mask doesn't leave a connected part of the {}x{} maze",
+                    self.width, self.height
+                )));
+            }
+            if let Some((x, y)) = self.start_coords.filter(|coords| mask.is_solid(*coords)) {
+                return Err(BuildError::reason(format!(
+                    "Start coords ({}, {}) are in a solid area of the mask",
+                    x, y
+                )));
+            }
+            if self.goal_room.is_some() {
+                return Err(BuildError::reason(
+                    "A goal room cannot be combined with a mask".to_string(),
+                ));
+            }
+        }
+
+        if self.torus {
+            let other = if self.symmetry.is_some() {
+                Some("a symmetry")
+            } else if self.mask.is_some() {
+                Some("a mask")
+            } else if self.goal_room.is_some() {
+                Some("a goal room")
+            } else {
+                None
+            };
+            if let Some(other) = other {
+                return Err(BuildError::reason(format!(
+                    "A torus cannot be combined with {}",
+                    other
+                )));
+            }
+        }
+
+        if let Some(fraction) = self
+            .sparsity
+            .filter(|fraction| !(0.0..=1.0).contains(fraction))
+        {
+            return Err(BuildError::reason(format!(
+                "Sparsity must be between 0 and 1, but it is {}",
+                fraction
+            )));
+        }
+
+        match self.goal_room {
+            Some(0) => {
+                return Err(BuildError::reason(
+                    "A goal room requires at least a single cell".to_string(),
+                ))
+            }
+            Some(size) if size >= self.width || size >= self.height => {
+                return Err(BuildError::reason(format!(
+                    "A {0}x{0} goal room requires at least a {1}x{1} maze, but the maze is {2}x{3}",
+                    size,
+                    size + 1,
+                    self.width,
+                    self.height
+                )))
+            }
+            _ => {}
+        }
+
+        match self.start_coords {
+            Some(_) if !self.algorithm.has_start_coords() => Err(BuildError::reason(format!(
+                "Algorithm `{}` doesn't support `start_coords`",
+                name
+            ))),
+            Some((x, y)) if x >= self.width || y >= self.height => {
+                Err(BuildError::reason(format!(
+                    "Start coords ({}, {}) are out of the {}x{} maze",
+                    x, y, self.width, self.height
+                )))
+            }
+            _ => Ok(()),
         }
     }
 }
@@ -68,7 +545,12 @@ impl Default for OrthogonalMazeBuilder {
 
 #[cfg(test)]
 mod tests {
-    use crate::maze::RecursiveDivision;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    };
+
+    use crate::maze::{Cell, Eller, Grid, Kruskal, Pole, RecursiveDivision};
 
     use super::*;
 
@@ -78,6 +560,123 @@ mod tests {
         assert!(maze.is_valid());
     }
 
+    #[test]
+    fn seeded_build_is_reproducible() {
+        let build = |seed| {
+            OrthogonalMazeBuilder::new()
+                .width(15)
+                .height(15)
+                .seed(seed)
+                .build()
+                .unwrap()
+        };
+
+        assert_eq!(build(7), build(7));
+        assert_ne!(build(7), build(8));
+    }
+
+    /// A linear congruential generator, counting the numbers it returns
+    struct Lcg(u64, Arc<AtomicUsize>);
+
+    impl RngCore for Lcg {
+        fn next_u32(&mut self) -> u32 {
+            (self.next_u64() >> 32) as u32
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            self.1.fetch_add(1, Ordering::Relaxed);
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            self.0
+        }
+
+        fn fill_bytes(&mut self, dst: &mut [u8]) {
+            for chunk in dst.chunks_mut(8) {
+                chunk.copy_from_slice(&self.next_u64().to_le_bytes()[..chunk.len()]);
+            }
+        }
+    }
+
+    #[test]
+    fn builder_is_send() {
+        fn assert_send<T: Send>(_: T) {}
+
+        assert_send(
+            OrthogonalMazeBuilder::new()
+                .rng(Lcg(5, Arc::new(AtomicUsize::new(0))))
+                .post_process(PostProcess::Custom(Box::new(|_| {}))),
+        );
+    }
+
+    #[test]
+    fn custom_rng() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let build = |seed| {
+            OrthogonalMazeBuilder::new()
+                .width(8)
+                .height(6)
+                .algorithm(Box::new(Kruskal::new()))
+                .seed(seed)
+                .rng(Lcg(5, Arc::clone(&calls)))
+                .build()
+                .unwrap()
+        };
+
+        let maze = build(1);
+        assert!(maze.is_valid());
+        assert!(calls.load(Ordering::Relaxed) > 0);
+
+        // The custom generator takes precedence over the seed
+        assert_eq!(build(2), maze);
+    }
+
+    #[test]
+    fn structure_and_openings_seeds() {
+        let build = |structure_seed, openings_seed| {
+            let mut maze = OrthogonalMazeBuilder::new()
+                .width(8)
+                .height(8)
+                .structure_seed(structure_seed)
+                .openings_seed(openings_seed)
+                .build()
+                .unwrap();
+            let layout = maze.clone();
+            let entrances = maze.add_random_entrances(3).unwrap();
+            (layout, entrances)
+        };
+
+        let (layout, entrances) = build(1, 2);
+        assert_eq!(build(1, 3).0, layout);
+        assert_ne!(build(1, 3).1, entrances);
+        assert_ne!(build(4, 2).0, layout);
+        assert_eq!(build(4, 2).1, entrances);
+
+        // A single seed is used for both the structure and the openings
+        let mut maze = OrthogonalMazeBuilder::new()
+            .width(8)
+            .height(8)
+            .seed(1)
+            .build()
+            .unwrap();
+        assert_eq!(maze, layout);
+        assert_eq!(maze.add_random_entrances(3).unwrap(), build(5, 1).1);
+    }
+
+    #[test]
+    fn regenerate_resets_openings() {
+        let mut maze = OrthogonalMazeBuilder::new()
+            .openings_seed(7)
+            .build()
+            .unwrap();
+        let entrances = maze.add_random_entrances(2).unwrap();
+        assert_ne!(maze.add_random_entrances(2).unwrap(), entrances);
+
+        maze.regenerate(3);
+        assert_eq!(maze.add_random_entrances(2).unwrap(), entrances);
+    }
+
     #[test]
     fn no_start_coord_support() {
         let maze_err = OrthogonalMazeBuilder::default()
@@ -87,4 +686,391 @@ mod tests {
             .unwrap_err();
         assert_eq!(maze_err.to_string(), "Cannot build maze. Reason: Algorithm `RecursiveDivision` doesn't support `start_coords`");
     }
+
+    #[test]
+    fn empty_dimensions() {
+        for (width, height) in [(0, 5), (5, 0), (0, 0)] {
+            let maze_err = OrthogonalMazeBuilder::new()
+                .width(width)
+                .height(height)
+                .build()
+                .unwrap_err();
+
+            assert_eq!(
+                maze_err.reason,
+                format!(
+                    "Algorithm `RecursiveBacktracking` requires at least a 1x1 maze, but the maze is {}x{}",
+                    width, height
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn algorithm_min_size() {
+        struct Wide;
+
+        impl Algorithm for Wide {
+            fn generate(&mut self, _: &mut Grid, _: Option<Coords>, _: &mut dyn RngCore) {}
+
+            fn has_start_coords(&self) -> bool {
+                false
+            }
+
+            fn min_size(&self) -> (usize, usize) {
+                (3, 2)
+            }
+
+            fn name(&self) -> &'static str {
+                "Wide"
+            }
+        }
+
+        let build = |width, height| {
+            OrthogonalMazeBuilder::new()
+                .width(width)
+                .height(height)
+                .algorithm(Box::new(Wide))
+                .build()
+        };
+
+        assert_eq!(
+            build(2, 5).unwrap_err().reason,
+            "Algorithm `Wide` requires at least a 3x2 maze, but the maze is 2x5"
+        );
+        assert!(build(3, 2).is_ok());
+
+        // A symmetric maze mirrors the part the algorithm generates
+        let build = |width, height| {
+            OrthogonalMazeBuilder::new()
+                .width(width)
+                .height(height)
+                .algorithm(Box::new(Wide))
+                .symmetry(Symmetry::Quad)
+                .build()
+        };
+
+        assert_eq!(
+            build(4, 3).unwrap_err().reason,
+            "Algorithm `Wide` requires at least a 5x3 maze, but the maze is 4x3"
+        );
+        assert!(build(5, 3).is_ok());
+    }
+
+    #[test]
+    fn single_row_and_column() {
+        for (width, height) in [(1, 1), (1, 7), (7, 1)] {
+            let maze = OrthogonalMazeBuilder::new()
+                .width(width)
+                .height(height)
+                .algorithm(Box::new(Eller))
+                .build()
+                .unwrap();
+
+            assert!(maze.is_valid());
+        }
+    }
+
+    #[test]
+    fn goal_room() {
+        let build = |seed| {
+            OrthogonalMazeBuilder::new()
+                .width(8)
+                .height(6)
+                .start_coords((7, 5))
+                .goal_room(2)
+                .seed(seed)
+                .build()
+                .unwrap()
+        };
+        let maze = build(4);
+
+        assert!(maze.is_valid());
+        assert_eq!(maze, build(4));
+        // A room of 2x2 cells makes a single loop
+        let passages: usize = maze.iter().map(|(_, cell)| cell.passage_count()).sum();
+        assert_eq!(passages / 2, 8 * 6);
+    }
+
+    #[test]
+    fn goal_room_out_of_bounds() {
+        let build = |size| {
+            OrthogonalMazeBuilder::new()
+                .width(5)
+                .height(3)
+                .goal_room(size)
+                .build()
+        };
+
+        assert_eq!(
+            build(3).unwrap_err().reason,
+            "A 3x3 goal room requires at least a 4x4 maze, but the maze is 5x3"
+        );
+        assert_eq!(
+            build(0).unwrap_err().reason,
+            "A goal room requires at least a single cell"
+        );
+        assert!(build(2).is_ok());
+    }
+
+    #[test]
+    fn sparsity() {
+        let build = |sparsity: Option<f64>| {
+            let mut builder = OrthogonalMazeBuilder::new().width(10).height(8).seed(3);
+            if let Some(fraction) = sparsity {
+                builder = builder.sparsity(fraction);
+            }
+            builder.build().unwrap()
+        };
+        let perfect = build(None);
+        let dead_ends = perfect.dead_ends().len();
+
+        assert_eq!(build(Some(0.0)), perfect);
+        // Dead ends of a perfect maze are never adjacent, so each of them becomes a part of its own
+        let sparse = build(Some(0.5));
+        assert!(!sparse.is_valid());
+        assert_eq!(sparse, build(Some(0.5)));
+        assert_eq!(
+            sparse.component_count(),
+            1 + (dead_ends as f64 * 0.5).round() as usize
+        );
+        assert_eq!(build(Some(1.0)).component_count(), 1 + dead_ends);
+    }
+
+    #[test]
+    fn invalid_sparsity() {
+        for fraction in [-0.1, 1.5, f64::NAN] {
+            let error = OrthogonalMazeBuilder::new()
+                .sparsity(fraction)
+                .build()
+                .unwrap_err();
+            assert_eq!(
+                error.reason,
+                format!("Sparsity must be between 0 and 1, but it is {}", fraction)
+            );
+        }
+    }
+
+    #[test]
+    fn mask() {
+        let maze = OrthogonalMazeBuilder::new()
+            .width(9)
+            .height(7)
+            .algorithm(Box::new(Eller))
+            .mask(Mask::new().solid_rect((2, 2), 5, 3))
+            .seed(5)
+            .build()
+            .unwrap();
+
+        assert!(maze.is_valid());
+        assert_eq!(maze[(4, 3)], Cell::empty());
+        assert!(!maze[(1, 3)].contains(Cell::EAST));
+        let solution = maze.solve((0, 3), (8, 3)).unwrap();
+        assert!(solution
+            .path
+            .iter()
+            .all(|(x, y)| !(2..7).contains(x) || !(2..5).contains(y)));
+
+        // The solid cells are unreachable in a maze without a mask
+        let mut other = OrthogonalMaze::new(9, 7);
+        *other.get_grid_mut() = maze.grid().clone();
+        assert!(!other.is_valid());
+    }
+
+    #[test]
+    fn post_processes_in_order() {
+        let steps = Arc::new(Mutex::new(vec![]));
+        let step = |name| {
+            let steps = Arc::clone(&steps);
+            PostProcess::Custom(Box::new(move |maze| {
+                steps.lock().unwrap().push((name, maze.dead_ends().len()));
+            }))
+        };
+
+        let maze = OrthogonalMazeBuilder::new()
+            .width(12)
+            .height(12)
+            .seed(3)
+            .post_process(step("generated"))
+            .post_process(PostProcess::AdjustDeadEnds(4))
+            .post_process(step("adjusted"))
+            .post_process(PostProcess::AddRandomEntrances(2))
+            .build()
+            .unwrap();
+
+        let steps = steps.lock().unwrap();
+        assert_eq!(steps.len(), 2);
+        assert_eq!(steps[0].0, "generated");
+        assert!(steps[0].1 > 4);
+        assert_eq!(steps[1], ("adjusted", 4));
+        // Openings of the entrances lead out of the maze
+        let openings = maze
+            .iter()
+            .flat_map(|(coords, cell)| Pole::all().map(move |pole| (coords, *cell, pole)))
+            .filter(|(coords, cell, pole)| {
+                cell.contains(Cell::from(*pole)) && maze.move_from(*coords, *pole).is_none()
+            })
+            .count();
+        assert_eq!(openings, 2);
+    }
+
+    #[test]
+    fn seeded_post_processes_are_reproducible() {
+        let build = |seed| {
+            OrthogonalMazeBuilder::new()
+                .width(15)
+                .height(15)
+                .seed(seed)
+                .sparsity(0.5)
+                .post_process(PostProcess::ConnectComponents)
+                .post_process(PostProcess::CullDeadEnds(0.5))
+                .post_process(PostProcess::AddRandomEntrances(3))
+                .build()
+                .unwrap()
+        };
+
+        assert_eq!(build(7), build(7));
+        assert_ne!(build(7), build(8));
+    }
+
+    #[test]
+    fn failing_post_process() {
+        let result = OrthogonalMazeBuilder::new()
+            .width(2)
+            .height(2)
+            .post_process(PostProcess::AddRandomEntrances(5))
+            .build();
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn invalid_masks() {
+        let build = |mask: Mask| {
+            OrthogonalMazeBuilder::new()
+                .width(5)
+                .height(4)
+                .start_coords((1, 1))
+                .mask(mask)
+        };
+
+        assert_eq!(
+            build(Mask::new().solid_rect((2, 0), 1, 4))
+                .build()
+                .unwrap_err()
+                .reason,
+            "The mask doesn't leave a connected part of the 5x4 maze"
+        );
+        assert_eq!(
+            build(Mask::new().solid_rect((0, 0), 5, 4))
+                .build()
+                .unwrap_err()
+                .reason,
+            "The mask doesn't leave a connected part of the 5x4 maze"
+        );
+        assert_eq!(
+            build(Mask::new().solid_rect((1, 1), 2, 2))
+                .build()
+                .unwrap_err()
+                .reason,
+            "Start coords (1, 1) are in a solid area of the mask"
+        );
+        assert_eq!(
+            build(Mask::new().solid_rect((3, 1), 1, 1))
+                .goal_room(2)
+                .build()
+                .unwrap_err()
+                .reason,
+            "A goal room cannot be combined with a mask"
+        );
+    }
+
+    #[test]
+    fn from_grid() {
+        let mut grid = Grid::new_torus(8, 6);
+        for y in 0..5 {
+            grid.carve_passage((3, y), Cell::SOUTH).unwrap();
+        }
+        let partial = grid.clone();
+
+        let mut maze = OrthogonalMazeBuilder::from_grid(grid)
+            .algorithm(Box::new(Kruskal::new()))
+            .seed(3)
+            .build()
+            .unwrap();
+
+        for seed in [3, 4] {
+            maze.regenerate(seed);
+            assert!(maze.grid().is_torus());
+            assert!(maze.is_valid());
+            assert_eq!(maze.component_count(), 1);
+            assert!((0..6).all(|y| maze[(3, y)].contains(partial[(3, y)])));
+        }
+    }
+
+    #[test]
+    fn invalid_partial_grids() {
+        assert_eq!(
+            OrthogonalMazeBuilder::from_grid(Grid::new(4, 3))
+                .width(5)
+                .build()
+                .unwrap_err()
+                .reason,
+            "The partial grid is 4x3, but the maze is 5x3"
+        );
+        assert_eq!(
+            OrthogonalMazeBuilder::from_grid(Grid::new(4, 4))
+                .symmetry(Symmetry::Quad)
+                .build()
+                .unwrap_err()
+                .reason,
+            "A partial grid cannot be combined with a symmetry"
+        );
+    }
+
+    #[test]
+    fn invalid_torus() {
+        let build = || OrthogonalMazeBuilder::new().width(6).height(6).torus(true);
+
+        assert_eq!(
+            build().symmetry(Symmetry::Quad).build().unwrap_err().reason,
+            "A torus cannot be combined with a symmetry"
+        );
+        assert_eq!(
+            build()
+                .mask(Mask::new().solid_rect((2, 2), 2, 2))
+                .build()
+                .unwrap_err()
+                .reason,
+            "A torus cannot be combined with a mask"
+        );
+        assert_eq!(
+            build().goal_room(2).build().unwrap_err().reason,
+            "A torus cannot be combined with a goal room"
+        );
+        assert_eq!(
+            OrthogonalMazeBuilder::from_grid(Grid::new_torus(6, 6))
+                .goal_room(2)
+                .build()
+                .unwrap_err()
+                .reason,
+            "A torus cannot be combined with a goal room"
+        );
+        assert!(build().build().is_ok());
+    }
+
+    #[test]
+    fn start_coords_out_of_bounds() {
+        let maze_err = OrthogonalMazeBuilder::new()
+            .width(4)
+            .height(3)
+            .start_coords((1, 3))
+            .build()
+            .unwrap_err();
+
+        assert_eq!(
+            maze_err.reason,
+            "Start coords (1, 3) are out of the 4x3 maze"
+        );
+    }
 }
@@ -0,0 +1,570 @@
+use std::collections::VecDeque;
+
+use super::grid::{cell::Cell, pole::Pole, Grid};
+use crate::utils::types::Coords;
+
+/// The maximum number of passes over the whole grid
+const MAX_PASSES: usize = 4;
+
+/// A passage between a cell and its eastern or southern neighbour
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Edge {
+    coords: Coords,
+    direction: Cell,
+}
+
+/// A row (for horizontal passages) or a column (for vertical passages) of the grid
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Line {
+    Row(usize),
+    Column(usize),
+}
+
+impl Edge {
+    /// Returns the carved edge between two adjacent cells regardless of their order, including an
+    /// edge wrapping around a torus
+    fn carved(grid: &Grid, a: Coords, b: Coords) -> Edge {
+        [(a, b), (b, a)]
+            .into_iter()
+            .flat_map(|(from, to)| [Cell::EAST, Cell::SOUTH].map(|direction| (from, to, direction)))
+            .find(|&(from, to, direction)| {
+                grid.is_carved(from, direction)
+                    && grid.get_next_cell_coords(from, direction).ok() == Some(to)
+            })
+            .map(|(coords, _, direction)| Edge { coords, direction })
+            .expect("Cells of a tree are connected by a passage")
+    }
+
+    /// Returns coords of the cell on the other side of the edge
+    fn next(&self, grid: &Grid) -> Coords {
+        grid.get_next_cell_coords(self.coords, self.direction)
+            .expect("An edge always connects two cells of the grid")
+    }
+
+    fn line(&self) -> Line {
+        if self.direction == Cell::EAST {
+            Line::Row(self.coords.1)
+        } else {
+            Line::Column(self.coords.0)
+        }
+    }
+}
+
+/// Straightens corridors of a grid, so that straight runs of passages span at least
+/// `min_corridor` cells where possible
+///
+/// Every change adds a passage that extends a short straight run and removes another passage on
+/// the loop it would otherwise create. The number of passages and the connectivity of cells stay
+/// the same, so a perfect maze remains a perfect maze.
+pub fn straighten_corridors(grid: &mut Grid, min_corridor: usize) {
+    // A single passage already connects two cells in a straight line
+    if min_corridor <= 2 {
+        return;
+    }
+
+    for _ in 0..MAX_PASSES {
+        if !straighten_pass(grid, min_corridor) {
+            break;
+        }
+    }
+}
+
+fn straighten_pass(grid: &mut Grid, min_corridor: usize) -> bool {
+    let mut improved = false;
+    let mut forest = Forest::new(grid);
+
+    for y in 0..grid.height() {
+        for x in 0..grid.width() {
+            for direction in [Cell::EAST, Cell::SOUTH] {
+                let Ok(next) = grid.get_next_cell_coords((x, y), direction) else {
+                    continue;
+                };
+                if grid.is_carved((x, y), direction) {
+                    continue;
+                }
+
+                let added = Edge {
+                    coords: (x, y),
+                    direction,
+                };
+                if !extends_short_run(grid, added, next, min_corridor) {
+                    continue;
+                }
+
+                // Any passage on the path between the two cells can be swapped for the new one
+                let Some(path) = forest.path(grid, (x, y), next) else {
+                    continue;
+                };
+
+                let best = path
+                    .into_iter()
+                    .map(|removed| (removed, swap_delta(grid, added, removed, min_corridor)))
+                    .filter(|(_, delta)| *delta < 0)
+                    .min_by_key(|(_, delta)| *delta);
+
+                if let Some((removed, _)) = best {
+                    set_passage(grid, added, true);
+                    set_passage(grid, removed, false);
+                    forest.swap(grid, added, removed);
+                    improved = true;
+                }
+            }
+        }
+    }
+
+    improved
+}
+
+/// Indicates if adding a given passage would join straight runs into a longer one that reduces
+/// the shortfall of its line
+fn extends_short_run(grid: &Grid, added: Edge, next: Coords, min_corridor: usize) -> bool {
+    let before = run_length(grid, added.coords, opposite(added.direction));
+    let after = run_length(grid, next, added.direction);
+
+    shortfall(before, min_corridor) + shortfall(after, min_corridor)
+        > shortfall(before + after + 1, min_corridor)
+}
+
+/// Returns the number of collinear passages going from a given cell in a given direction
+fn run_length(grid: &Grid, coords: Coords, direction: Cell) -> usize {
+    let mut length = 0;
+    let mut current = coords;
+
+    while grid.is_carved(current, direction) {
+        let Ok(next) = grid.get_next_cell_coords(current, direction) else {
+            break;
+        };
+        length += 1;
+        current = next;
+    }
+
+    length
+}
+
+/// Returns the change of shortfall after adding one passage and removing another
+fn swap_delta(grid: &mut Grid, added: Edge, removed: Edge, min_corridor: usize) -> isize {
+    // Passages of different lines only change the runs right next to them
+    if added.line() != removed.line() {
+        return join_delta(grid, added, min_corridor) - join_delta(grid, removed, min_corridor);
+    }
+
+    let line = added.line();
+    let before = line_shortfall(grid, line, min_corridor) as isize;
+    set_passage(grid, added, true);
+    set_passage(grid, removed, false);
+    let after = line_shortfall(grid, line, min_corridor) as isize;
+    set_passage(grid, removed, true);
+    set_passage(grid, added, false);
+
+    after - before
+}
+
+/// Returns the change of shortfall of the line of a given edge after joining the runs on both
+/// sides of the edge with a passage through it
+fn join_delta(grid: &Grid, edge: Edge, min_corridor: usize) -> isize {
+    let (position, len) = match edge.line() {
+        Line::Row(_) => (edge.coords.0, grid.width()),
+        Line::Column(_) => (edge.coords.1, grid.height()),
+    };
+    // A line only spans `len - 1` passages, without the one wrapping around a torus
+    if position + 1 >= len {
+        return 0;
+    }
+
+    let carved = |position: usize| {
+        let coords = match edge.line() {
+            Line::Row(y) => (position, y),
+            Line::Column(x) => (x, position),
+        };
+        grid.is_carved(coords, edge.direction)
+    };
+    let before = (0..position).rev().take_while(|&p| carved(p)).count();
+    let after = (position + 1..len - 1).take_while(|&p| carved(p)).count();
+
+    shortfall(before + after + 1, min_corridor) as isize
+        - shortfall(before, min_corridor) as isize
+        - shortfall(after, min_corridor) as isize
+}
+
+fn line_shortfall(grid: &Grid, line: Line, min_corridor: usize) -> usize {
+    let (start, direction, len) = match line {
+        Line::Row(y) => ((0, y), Cell::EAST, grid.width()),
+        Line::Column(x) => ((x, 0), Cell::SOUTH, grid.height()),
+    };
+
+    let mut total = 0;
+    let mut run = 0;
+    let mut current = start;
+
+    for _ in 1..len {
+        let next = grid
+            .get_next_cell_coords(current, direction)
+            .expect("A line never leaves the grid");
+        if grid.is_carved(current, direction) {
+            run += 1;
+        } else {
+            total += shortfall(run, min_corridor);
+            run = 0;
+        }
+        current = next;
+    }
+
+    total + shortfall(run, min_corridor)
+}
+
+/// Returns how many cells a straight run of a given number of passages misses to span
+/// `min_corridor` cells
+const fn shortfall(passages: usize, min_corridor: usize) -> usize {
+    if passages == 0 {
+        return 0;
+    }
+
+    min_corridor.saturating_sub(passages + 1)
+}
+
+/// A spanning forest of the passages of a grid, where every cell points to its parent towards the
+/// root of its tree
+///
+/// Paths between cells are followed up the parent pointers instead of searching the grid, and a
+/// swap of passages only updates the part of a tree that is moved.
+struct Forest {
+    width: usize,
+    parents: Vec<Option<Coords>>,
+    depths: Vec<usize>,
+}
+
+impl Forest {
+    fn new(grid: &Grid) -> Forest {
+        let width = grid.width();
+        let mut forest = Forest {
+            width,
+            parents: vec![None; width * grid.height()],
+            depths: vec![0; width * grid.height()],
+        };
+        let mut seen = vec![false; width * grid.height()];
+
+        for y in 0..grid.height() {
+            for x in 0..width {
+                if seen[forest.index((x, y))] {
+                    continue;
+                }
+                seen[forest.index((x, y))] = true;
+                let mut queue = VecDeque::from([(x, y)]);
+
+                while let Some(coords) = queue.pop_front() {
+                    for next in neighbours(grid, coords) {
+                        if !seen[forest.index(next)] {
+                            seen[forest.index(next)] = true;
+                            forest.hang(next, coords);
+                            queue.push_back(next);
+                        }
+                    }
+                }
+            }
+        }
+
+        forest
+    }
+
+    const fn index(&self, (x, y): Coords) -> usize {
+        y * self.width + x
+    }
+
+    fn parent(&self, coords: Coords) -> Option<Coords> {
+        self.parents[self.index(coords)]
+    }
+
+    fn depth(&self, coords: Coords) -> usize {
+        self.depths[self.index(coords)]
+    }
+
+    fn hang(&mut self, coords: Coords, parent: Coords) {
+        let index = self.index(coords);
+        self.parents[index] = Some(parent);
+        self.depths[index] = self.depth(parent) + 1;
+    }
+
+    /// Returns passages on the path between two cells from the last one to the first one, or
+    /// `None` if the cells are not connected
+    fn path(&self, grid: &Grid, from: Coords, to: Coords) -> Option<Vec<Edge>> {
+        let (mut from, mut to) = (from, to);
+        let (mut from_side, mut to_side) = (vec![], vec![]);
+
+        while from != to {
+            let (coords, side) = if self.depth(from) > self.depth(to) {
+                (&mut from, &mut from_side)
+            } else {
+                (&mut to, &mut to_side)
+            };
+            let parent = self.parent(*coords)?;
+            side.push(Edge::carved(grid, parent, *coords));
+            *coords = parent;
+        }
+
+        to_side.extend(from_side.into_iter().rev());
+        Some(to_side)
+    }
+
+    /// Updates the forest after a given passage has been added to the grid and another one on the
+    /// path between the cells of the added one has been removed
+    ///
+    /// The cells under the removed passage are hung under the added one instead, reversing the
+    /// parent pointers from the cell of the added passage up to the removed one.
+    fn swap(&mut self, grid: &Grid, added: Edge, removed: Edge) {
+        let mut bottom = removed.next(grid);
+        if self.parent(bottom) != Some(removed.coords) {
+            bottom = removed.coords;
+        }
+        let (mut parent, mut current) = (added.coords, added.next(grid));
+        if !self.is_under(current, bottom) {
+            (parent, current) = (current, parent);
+        }
+
+        let (anchor, root) = (parent, current);
+        loop {
+            let next = self.parent(current);
+            let index = self.index(current);
+            self.parents[index] = Some(parent);
+            if current == bottom {
+                break;
+            }
+            parent = current;
+            current = next.expect("The removed passage is above the cell of the added one");
+        }
+
+        // Only depths of the moved cells change
+        self.hang(root, anchor);
+        let mut queue = VecDeque::from([root]);
+        while let Some(coords) = queue.pop_front() {
+            for next in neighbours(grid, coords) {
+                if self.parent(next) == Some(coords) {
+                    self.hang(next, coords);
+                    queue.push_back(next);
+                }
+            }
+        }
+    }
+
+    /// Indicates if a cell is a given one or one of the cells under it
+    fn is_under(&self, coords: Coords, ancestor: Coords) -> bool {
+        let mut current = coords;
+        while self.depth(current) > self.depth(ancestor) {
+            current = self
+                .parent(current)
+                .expect("A cell below a root has a parent");
+        }
+        current == ancestor
+    }
+}
+
+/// Returns coords of the cells connected to a given cell by passages
+fn neighbours(grid: &Grid, coords: Coords) -> impl Iterator<Item = Coords> + '_ {
+    Pole::all().into_iter().filter_map(move |pole| {
+        let direction = Cell::from(pole);
+        if !grid.is_carved(coords, direction) {
+            return None;
+        }
+        grid.get_next_cell_coords(coords, direction).ok()
+    })
+}
+
+fn set_passage(grid: &mut Grid, edge: Edge, carved: bool) {
+    if carved {
+        grid.carve_passage(edge.coords, edge.direction)
+            .expect("An edge always connects two cells of the grid");
+    } else {
+        grid.remove_passage(edge.coords, edge.direction)
+            .expect("An edge always connects two cells of the grid");
+    }
+}
+
+fn opposite(direction: Cell) -> Cell {
+    Pole::from_cell(direction).map_or(direction, |pole| Cell::from(pole.opposite()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze::{algorithms::Algorithm, validate::validate, Prim, RecursiveBacktracking};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn straighten_zigzag() {
+        // A staircase of single passages leads from the top left corner to the bottom right one
+        let mut grid = Grid::new(3, 3);
+        grid.carve_passage((0, 0), Cell::EAST).unwrap();
+        grid.carve_passage((1, 0), Cell::SOUTH).unwrap();
+        grid.carve_passage((1, 1), Cell::EAST).unwrap();
+        grid.carve_passage((2, 1), Cell::SOUTH).unwrap();
+        grid.carve_passage((0, 0), Cell::SOUTH).unwrap();
+        grid.carve_passage((0, 1), Cell::SOUTH).unwrap();
+        grid.carve_passage((0, 2), Cell::EAST).unwrap();
+        grid.carve_passage((1, 0), Cell::EAST).unwrap();
+
+        let before = corridor_shortfall(&grid, 3);
+        straighten_corridors(&mut grid, 3);
+
+        assert!(corridor_shortfall(&grid, 3) < before);
+        assert!(validate(&grid));
+        assert_eq!(passage_count(&grid), 8);
+    }
+
+    #[test]
+    fn straighten_generated_mazes() {
+        for seed in 0..10 {
+            let mut grid = Grid::new(12, 12);
+            RecursiveBacktracking.generate(&mut grid, None, &mut StdRng::seed_from_u64(seed));
+
+            let before = corridor_shortfall(&grid, 4);
+            straighten_corridors(&mut grid, 4);
+
+            assert!(corridor_shortfall(&grid, 4) <= before);
+            assert!(validate(&grid));
+            assert_eq!(passage_count(&grid), 12 * 12 - 1);
+        }
+    }
+
+    #[test]
+    fn straighten_increases_average_run_length() {
+        let (mut before, mut after) = (0.0, 0.0);
+
+        for seed in 0..5 {
+            let mut grid = Grid::new(12, 12);
+            Prim::new().generate(&mut grid, None, &mut StdRng::seed_from_u64(seed));
+
+            before += average_run_length(&grid);
+            straighten_corridors(&mut grid, 4);
+            after += average_run_length(&grid);
+        }
+
+        assert!(after > before);
+    }
+
+    #[test]
+    fn straighten_torus() {
+        for seed in 0..5 {
+            let mut grid = Grid::new_torus(8, 6);
+            RecursiveBacktracking.generate(&mut grid, None, &mut StdRng::seed_from_u64(seed));
+
+            straighten_corridors(&mut grid, 4);
+
+            assert!(validate(&grid));
+            assert_eq!(passage_count(&grid), 8 * 6 - 1);
+        }
+    }
+
+    #[test]
+    fn forest_follows_swaps() {
+        let mut grid = Grid::new(8, 8);
+        RecursiveBacktracking.generate(&mut grid, None, &mut StdRng::seed_from_u64(3));
+        let mut forest = Forest::new(&grid);
+
+        for y in 0..8 {
+            for x in 0..7 {
+                if grid.is_carved((x, y), Cell::EAST) {
+                    continue;
+                }
+                let added = Edge {
+                    coords: (x, y),
+                    direction: Cell::EAST,
+                };
+                let path = forest.path(&grid, (x, y), (x + 1, y)).unwrap();
+                let removed = path[path.len() / 2];
+
+                set_passage(&mut grid, added, true);
+                set_passage(&mut grid, removed, false);
+                forest.swap(&grid, added, removed);
+            }
+        }
+
+        // Paths through a tree are unique, so the updated forest finds the same ones as a new one
+        let fresh = Forest::new(&grid);
+        for (from, to) in [((0, 0), (7, 7)), ((3, 5), (6, 1)), ((7, 0), (0, 7))] {
+            assert_eq!(forest.path(&grid, from, to), fresh.path(&grid, from, to));
+        }
+        assert!(validate(&grid));
+    }
+
+    #[test]
+    fn join_delta_of_lines() {
+        let mut grid = Grid::new(7, 1);
+        grid.carve_passage((0, 0), Cell::EAST).unwrap();
+        grid.carve_passage((3, 0), Cell::EAST).unwrap();
+        grid.carve_passage((4, 0), Cell::EAST).unwrap();
+
+        // A join changes the shortfall of the line as much as toggling the passage does
+        for x in 0..6 {
+            let edge = Edge {
+                coords: (x, 0),
+                direction: Cell::EAST,
+            };
+            let carved = grid.is_carved((x, 0), Cell::EAST);
+            set_passage(&mut grid, edge, true);
+            let joined = line_shortfall(&grid, Line::Row(0), 5) as isize;
+            set_passage(&mut grid, edge, false);
+            let split = line_shortfall(&grid, Line::Row(0), 5) as isize;
+            set_passage(&mut grid, edge, carved);
+
+            assert_eq!(join_delta(&grid, edge, 5), joined - split, "{x}");
+        }
+    }
+
+    #[test]
+    fn short_min_corridor_keeps_grid() {
+        let mut grid = Grid::new(3, 1);
+        grid.carve_passage((0, 0), Cell::EAST).unwrap();
+        let expected = grid.clone();
+
+        straighten_corridors(&mut grid, 2);
+
+        assert_eq!(grid, expected);
+    }
+
+    #[test]
+    fn shortfall_of_runs() {
+        assert_eq!(shortfall(0, 4), 0);
+        assert_eq!(shortfall(1, 4), 2);
+        assert_eq!(shortfall(3, 4), 0);
+    }
+
+    fn average_run_length(grid: &Grid) -> f64 {
+        let (mut passages, mut runs) = (0, 0);
+        for y in 0..grid.height() {
+            for x in 0..grid.width() {
+                for direction in [Cell::EAST, Cell::SOUTH] {
+                    if grid.get_next_cell_coords((x, y), direction).is_err()
+                        || !grid.is_carved((x, y), direction)
+                    {
+                        continue;
+                    }
+                    passages += 1;
+                    // A run starts at a cell without a collinear passage behind it
+                    if !grid.is_carved((x, y), opposite(direction)) {
+                        runs += 1;
+                    }
+                }
+            }
+        }
+        passages as f64 / runs as f64
+    }
+
+    /// Returns the total shortfall of straight runs of a grid, i.e. how many cells are missing in all
+    /// the runs shorter than `min_corridor` cells
+    fn corridor_shortfall(grid: &Grid, min_corridor: usize) -> usize {
+        let rows = (0..grid.height()).map(|y| line_shortfall(grid, Line::Row(y), min_corridor));
+        let columns =
+            (0..grid.width()).map(|x| line_shortfall(grid, Line::Column(x), min_corridor));
+
+        rows.chain(columns).sum()
+    }
+
+    fn passage_count(grid: &Grid) -> usize {
+        let mut count = 0;
+        for y in 0..grid.height() {
+            for x in 0..grid.width() {
+                count += grid[(x, y)].passage_count();
+            }
+        }
+        count / 2
+    }
+}
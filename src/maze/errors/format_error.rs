@@ -0,0 +1,44 @@
+use std::fmt;
+
+#[derive(Debug, Clone)]
+/// A maze format error
+///
+/// Represents a custom error when a maze cannot be converted into another format, e.g. when the
+/// output would be too large
+pub struct FormatError {
+    /// A reason why a maze cannot be formatted
+    pub reason: String,
+}
+
+impl FormatError {
+    /// Defines reason why was not able to format maze
+    pub fn reason(reason: impl Into<String>) -> Self {
+        Self {
+            reason: reason.into(),
+        }
+    }
+}
+
+/// An implementation of [fmt::Display](fmt::Display) trait
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Cannot format maze. Reason: {}", self.reason)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        let error = FormatError {
+            reason: String::from("It's a fake reason"),
+        };
+
+        assert_eq!(
+            error.to_string(),
+            "Cannot format maze. Reason: It's a fake reason"
+        )
+    }
+}
@@ -0,0 +1,43 @@
+use std::fmt;
+
+#[derive(Debug, Clone)]
+/// A maze error
+///
+/// Represents a custom error when an operation cannot be applied to a maze
+pub struct MazeError {
+    /// A reason why an operation cannot be applied to a maze
+    pub reason: String,
+}
+
+impl MazeError {
+    /// Defines reason why an operation cannot be applied to a maze
+    pub fn reason(reason: impl Into<String>) -> Self {
+        Self {
+            reason: reason.into(),
+        }
+    }
+}
+
+/// An implementation of [fmt::Display](fmt::Display) trait
+impl fmt::Display for MazeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Cannot modify maze. Reason: {}", self.reason)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        let error = MazeError {
+            reason: String::from("It's a fake reason"),
+        };
+
+        assert_eq!(
+            error.to_string(),
+            "Cannot modify maze. Reason: It's a fake reason"
+        )
+    }
+}
@@ -1,7 +1,13 @@
 mod save_error;
 mod transit_error;
 mod builder_error;
+mod maze_error;
+mod parse_error;
+mod format_error;
 
 pub use save_error::MazeSaveError;
 pub use transit_error::TransitError;
 pub use builder_error::BuildError;
+pub use maze_error::MazeError;
+pub use parse_error::ParseError;
+pub use format_error::FormatError;
@@ -0,0 +1,44 @@
+use std::fmt;
+
+#[derive(Debug, Clone)]
+/// A maze parse error
+///
+/// Represents a custom error when a maze, or a part of it such as a [Pole](crate::maze::Pole),
+/// cannot be reconstructed from another format
+pub struct ParseError {
+    /// A reason why a maze cannot be parsed
+    pub reason: String,
+}
+
+impl ParseError {
+    /// Defines reason why was not able to parse maze
+    pub fn reason(reason: impl Into<String>) -> Self {
+        Self {
+            reason: reason.into(),
+        }
+    }
+}
+
+/// An implementation of [fmt::Display](fmt::Display) trait
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Cannot parse maze. Reason: {}", self.reason)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        let error = ParseError {
+            reason: String::from("It's a fake reason"),
+        };
+
+        assert_eq!(
+            error.to_string(),
+            "Cannot parse maze. Reason: It's a fake reason"
+        )
+    }
+}
@@ -2,12 +2,18 @@ use crate::utils::types::Coords;
 use std::fmt;
 
 #[derive(Debug, Clone)]
+/// A transit error
+///
+/// Represents a custom error when it is impossible to move from a grid cell in some direction
 pub struct TransitError {
+    /// Coords of a cell the transit was made from
     pub coords: Coords,
+    /// A reason why the transit is impossible
     pub reason: String,
 }
 
 impl TransitError {
+    /// Defines reason why it is impossible to move from a cell at given coords
     pub fn reason(reason: impl Into<String>, coords: Coords) -> Self {
         Self {
             reason: reason.into(),
@@ -16,6 +22,7 @@ impl TransitError {
     }
 }
 
+/// An implementation of [fmt::Display](fmt::Display) trait
 impl fmt::Display for TransitError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let (x, y) = self.coords;
@@ -1,6 +1,8 @@
 use crate::maze::grid::cell::Cell;
-use crate::maze::{formatters::Formatter, grid::Grid};
-use std::fmt::Write;
+use crate::maze::{formatters::Formatter, grid::Grid, Solution};
+use crate::utils::types::Coords;
+use std::fmt::{self, Write};
+use std::io;
 
 use super::StringWrapper;
 
@@ -34,83 +36,345 @@ pub struct AsciiNarrow;
 /// ```
 pub struct AsciiBroad;
 
-/// An implementation of a narrow ASCII formatter
-impl Formatter<StringWrapper> for AsciiNarrow {
-    /// Converts a given grid into ASCII characters and returns an [StringWrapper] over that image
-    fn format(&self, grid: &Grid) -> StringWrapper {
-        let mut result = String::new();
+/// A configurable ASCII formatter
+///
+/// Formats a maze the same way as [AsciiNarrow] or [AsciiBroad], but allows to repeat the passage
+/// characters horizontally. Since terminal characters are taller than wide, a square maze may look
+/// stretched in a terminal, and a horizontal repeat factor of `2` makes it look square instead.
+///
+/// # Example:
+///
+/// ```rust,no_run
+/// use bevy_knossos::maze::*;
+///
+/// let maze = OrthogonalMazeBuilder::new().build().unwrap();
+///
+/// maze.save("output/maze.txt", Ascii::narrow().horizontal_repeat(2)).unwrap();
+/// ```
+///
+/// A narrow maze with the horizontal repeat factor set to `2`:
+/// ```no_test
+///  ___________
+/// |  |_____   |
+/// |__    __|  |
+/// |   ________|
+/// |___________|
+/// ```
+///
+/// A [solution](Ascii::solution) can be overlaid, e.g. for terminal-based solving demos:
+/// ```no_test
+/// +---+---+---+---+
+/// | S |           |
+/// +   +---+---+   +
+/// | *   *   G |   |
+/// +---+   +---+   +
+/// ```
+pub struct Ascii {
+    broad: bool,
+    horizontal_repeat: usize,
+    solution: Option<Solution>,
+}
+
+impl Ascii {
+    /// Returns a new instance of an [Ascii] formatter with narrow passages, same as [AsciiNarrow]
+    pub const fn narrow() -> Ascii {
+        Ascii {
+            broad: false,
+            horizontal_repeat: 1,
+            solution: None,
+        }
+    }
+
+    /// Returns a new instance of an [Ascii] formatter with broad passages, same as [AsciiBroad]
+    pub const fn broad() -> Ascii {
+        Ascii {
+            broad: true,
+            horizontal_repeat: 1,
+            solution: None,
+        }
+    }
+
+    /// Sets how many times passage characters repeat horizontally and returns itself
+    ///
+    /// Defaults to `1`, which preserves the [AsciiNarrow] and [AsciiBroad] output. A factor of `0`
+    /// is treated as `1`.
+    pub const fn horizontal_repeat(mut self, factor: usize) -> Self {
+        self.horizontal_repeat = factor;
+        self
+    }
+
+    /// Sets a solution to overlay and returns itself
+    ///
+    /// The start cell of the solution is marked with `S`, the goal cell with `G` and the other
+    /// cells of the path with `*`. A marker replaces the middle character of a cell, so in narrow
+    /// mode it hides the southern wall of the cell.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let maze = OrthogonalMazeBuilder::new().width(5).height(5).build().unwrap();
+    /// let solution = maze.solve((0, 0), (4, 4)).unwrap();
+    ///
+    /// let ascii = maze.format(Ascii::broad().solution(solution)).0;
+    /// assert_eq!(ascii.matches('S').count(), 1);
+    /// assert_eq!(ascii.matches('G').count(), 1);
+    /// ```
+    pub fn solution(mut self, solution: Solution) -> Self {
+        self.solution = Some(solution);
+        self
+    }
+
+    /// Returns a character marking a given cell of the overlaid solution, if any
+    fn marker(&self, coords: Coords) -> Option<char> {
+        let solution = self.solution.as_ref()?;
+        if solution.start() == Some(coords) {
+            Some('S')
+        } else if solution.goal() == Some(coords) {
+            Some('G')
+        } else if solution.contains(coords) {
+            Some('*')
+        } else {
+            None
+        }
+    }
+
+    const fn repeat(&self) -> usize {
+        if self.horizontal_repeat == 0 {
+            1
+        } else {
+            self.horizontal_repeat
+        }
+    }
+
+    /// Writes a given grid as ASCII characters into a given writer
+    ///
+    /// Unlike [format](Formatter::format), no intermediate string is allocated, so it can be used
+    /// to write large mazes directly into a [fmt::Formatter], e.g. from a `Display` impl.
+    pub(crate) fn write_grid<W: Write>(&self, grid: &Grid, out: &mut W) -> fmt::Result {
+        if self.broad {
+            self.write_broad(grid, out)
+        } else {
+            self.write_narrow(grid, out)
+        }
+    }
+
+    /// Writes a given grid as ASCII characters into a given [io::Write] sink row by row
+    ///
+    /// Only a single row is buffered at a time instead of the whole maze, so very large mazes can
+    /// be piped into a file without building a giant string. The output is identical to the one
+    /// of [format](Formatter::format).
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::{formatters::Formatter, *};
+    ///
+    /// let mut grid = Grid::new(5, 5);
+    /// grid.carve_passage((0, 0), Cell::EAST).unwrap();
+    ///
+    /// let mut out: Vec<u8> = Vec::new();
+    /// Ascii::broad().write_to(&grid, &mut out).unwrap();
+    ///
+    /// assert_eq!(String::from_utf8(out).unwrap(), AsciiBroad.format(&grid).0);
+    /// ```
+    pub fn write_to<W: io::Write>(&self, grid: &Grid, writer: W) -> io::Result<()> {
+        let mut rows = RowWriter {
+            inner: writer,
+            row: String::new(),
+            error: None,
+        };
+
+        let result = self
+            .write_grid(grid, &mut rows)
+            .and_then(|_| rows.flush_row());
+        match (result, rows.error) {
+            (Ok(_), _) => Ok(()),
+            (Err(_), Some(error)) => Err(error),
+            (Err(_), None) => Err(io::Error::other("Couldn't format a maze")),
+        }
+    }
+
+    fn write_passage<W: Write>(&self, out: &mut W, passage: &str) -> fmt::Result {
+        for _ in 0..self.repeat() {
+            write!(out, "{}", passage)?;
+        }
+        Ok(())
+    }
+
+    /// Writes a passage of a given cell, with a marker of the overlaid solution in the middle
+    fn write_cell<W: Write>(&self, out: &mut W, coords: Coords, passage: &str) -> fmt::Result {
+        let Some(marker) = self.marker(coords) else {
+            return self.write_passage(out, passage);
+        };
 
-        let top_border = "_".repeat(grid.width() * 2 - 1);
+        let cell = passage.repeat(self.repeat());
+        let middle = cell.len() / 2;
+        write!(out, "{}{}{}", &cell[..middle], marker, &cell[middle + 1..])
+    }
 
-        writeln!(result, " {} ", top_border).unwrap();
+    fn write_narrow<W: Write>(&self, grid: &Grid, out: &mut W) -> fmt::Result {
+        write!(out, " ")?;
+        for x in 0..grid.width() {
+            if x > 0 {
+                write!(out, "_")?;
+            }
+            if grid.is_carved((x, 0), Cell::NORTH) {
+                self.write_passage(out, " ")?;
+            } else {
+                self.write_passage(out, "_")?;
+            }
+        }
+        writeln!(out, " ")?; // display top border
 
         for y in 0..grid.height() {
-            write!(result, "|").unwrap();
+            // display left border
+            if grid.is_carved((0, y), Cell::WEST) {
+                write!(out, " ")?;
+            } else {
+                write!(out, "|")?;
+            }
 
             for x in 0..grid.width() {
                 if grid.is_carved((x, y), Cell::SOUTH) {
-                    write!(result, " ").unwrap();
+                    self.write_cell(out, (x, y), " ")?;
                 } else {
-                    write!(result, "_").unwrap();
+                    self.write_cell(out, (x, y), "_")?;
                 }
 
                 if grid.is_carved((x, y), Cell::EAST) {
                     if grid.is_carved((x, y), Cell::SOUTH)
-                        || grid.is_carved((x + 1, y), Cell::SOUTH)
+                        || (x + 1 < grid.width() && grid.is_carved((x + 1, y), Cell::SOUTH))
                     {
-                        write!(result, " ").unwrap();
+                        write!(out, " ")?;
                     } else {
-                        write!(result, "_").unwrap();
+                        write!(out, "_")?;
                     }
                 } else {
-                    write!(result, "|").unwrap();
+                    write!(out, "|")?;
                 }
             }
 
-            writeln!(result).unwrap();
+            writeln!(out)?; // goto next line
         }
 
-        StringWrapper(result)
+        Ok(())
     }
-}
 
-/// An implementation of an broad ASCII formatter
-impl Formatter<StringWrapper> for AsciiBroad {
-    /// Converts a given grid into ASCII characters and returns an [StringWrapper] over that image
-    fn format(&self, grid: &Grid) -> StringWrapper {
-        let mut output = format!("+{}\n", "---+".to_string().repeat(grid.width()));
+    fn write_broad<W: Write>(&self, grid: &Grid, out: &mut W) -> fmt::Result {
+        write!(out, "+")?;
+        for x in 0..grid.width() {
+            if grid.is_carved((x, 0), Cell::NORTH) {
+                self.write_passage(out, "   ")?;
+            } else {
+                self.write_passage(out, "---")?;
+            }
+            write!(out, "+")?;
+        }
+        writeln!(out)?; // display top border
 
         for y in 0..grid.height() {
-            let mut top_line = "|".to_string();
-            let mut bottom_line = "+".to_string();
+            // display left border and cells with their eastern walls
+            if grid.is_carved((0, y), Cell::WEST) {
+                write!(out, " ")?;
+            } else {
+                write!(out, "|")?;
+            }
 
             for x in 0..grid.width() {
-                top_line.push_str("   ");
-                let east_boundary = if grid.is_carved((x, y), Cell::EAST) {
-                    " "
+                self.write_cell(out, (x, y), "   ")?;
+                if grid.is_carved((x, y), Cell::EAST) {
+                    write!(out, " ")?;
                 } else {
-                    "|"
-                };
-                top_line.push_str(east_boundary);
+                    write!(out, "|")?;
+                }
+            }
+            writeln!(out)?;
 
-                let south_boundary = if grid.is_carved((x, y), Cell::SOUTH) {
-                    "   "
+            // display southern walls
+            write!(out, "+")?;
+            for x in 0..grid.width() {
+                if grid.is_carved((x, y), Cell::SOUTH) {
+                    self.write_passage(out, "   ")?;
                 } else {
-                    "---"
-                };
-                bottom_line.push_str(south_boundary);
-                bottom_line.push('+');
-
-                if x == grid.width() - 1 {
-                    output.push_str(&top_line);
-                    output.push('\n');
-                    output.push_str(&bottom_line);
-                    output.push('\n');
+                    self.write_passage(out, "---")?;
                 }
+                write!(out, "+")?;
             }
+            writeln!(out)?;
         }
 
-        StringWrapper(output)
+        Ok(())
+    }
+
+    /// Returns an approximate length of the formatted grid used to preallocate the output
+    const fn capacity(&self, grid: &Grid) -> usize {
+        let cell_width = if self.broad { 3 } else { 1 } * self.repeat() + 1;
+        let rows = if self.broad { 2 } else { 1 } * grid.height() + 1;
+
+        // Each row has additionally the left border and a line break
+        (grid.width() * cell_width + 2) * rows
+    }
+}
+
+/// An adapter writing characters into an [io::Write] sink a single row at a time
+struct RowWriter<W: io::Write> {
+    inner: W,
+    row: String,
+    error: Option<io::Error>,
+}
+
+impl<W: io::Write> RowWriter<W> {
+    fn flush_row(&mut self) -> fmt::Result {
+        if let Err(error) = self.inner.write_all(self.row.as_bytes()) {
+            self.error = Some(error);
+            return Err(fmt::Error);
+        }
+        self.row.clear();
+        Ok(())
+    }
+}
+
+impl<W: io::Write> Write for RowWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.row.push_str(s);
+        if s.contains('\n') {
+            self.flush_row()?;
+        }
+        Ok(())
+    }
+}
+
+/// An implementation of a configurable ASCII formatter
+impl Formatter<StringWrapper> for Ascii {
+    /// Converts a given grid into ASCII characters and returns an [StringWrapper] over that image
+    fn format(&self, grid: &Grid) -> StringWrapper {
+        let mut result = String::with_capacity(self.capacity(grid));
+        self.write_grid(grid, &mut result).unwrap();
+
+        StringWrapper(result)
+    }
+}
+
+impl AsciiNarrow {
+    /// Writes a given grid as narrow ASCII characters into a given writer
+    pub(crate) fn write_grid<W: Write>(&self, grid: &Grid, out: &mut W) -> fmt::Result {
+        Ascii::narrow().write_grid(grid, out)
+    }
+}
+
+/// An implementation of a narrow ASCII formatter
+impl Formatter<StringWrapper> for AsciiNarrow {
+    /// Converts a given grid into ASCII characters and returns an [StringWrapper] over that image
+    fn format(&self, grid: &Grid) -> StringWrapper {
+        Ascii::narrow().format(grid)
+    }
+}
+
+/// An implementation of an broad ASCII formatter
+impl Formatter<StringWrapper> for AsciiBroad {
+    /// Converts a given grid into ASCII characters and returns an [StringWrapper] over that image
+    fn format(&self, grid: &Grid) -> StringWrapper {
+        Ascii::broad().format(grid)
     }
 }
 
@@ -154,6 +418,193 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn format_narrow_with_openings() {
+        let mut expected = String::new();
+        expected.push_str("  ______ \n");
+        expected.push_str("| |___  |\n");
+        expected.push_str("|_   _|  \n");
+        expected.push_str("   _____|\n");
+        expected.push_str("|_____  |\n");
+
+        let mut grid = generate_maze();
+        grid.carve_opening((0, 0), Cell::NORTH).unwrap();
+        grid.carve_opening((3, 1), Cell::EAST).unwrap();
+        grid.carve_opening((0, 2), Cell::WEST).unwrap();
+        grid.carve_opening((3, 3), Cell::SOUTH).unwrap();
+        let actual = AsciiNarrow.format(&grid).0;
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn format_broad_with_openings() {
+        let mut expected = String::new();
+        expected.push_str("+   +---+---+---+\n");
+        expected.push_str("|   |           |\n");
+        expected.push_str("+   +---+---+   +\n");
+        expected.push_str("|           |    \n");
+        expected.push_str("+---+   +---+   +\n");
+        expected.push_str("                |\n");
+        expected.push_str("+   +---+---+---+\n");
+        expected.push_str("|               |\n");
+        expected.push_str("+---+---+---+   +\n");
+
+        let mut grid = generate_maze();
+        grid.carve_opening((0, 0), Cell::NORTH).unwrap();
+        grid.carve_opening((3, 1), Cell::EAST).unwrap();
+        grid.carve_opening((0, 2), Cell::WEST).unwrap();
+        grid.carve_opening((3, 3), Cell::SOUTH).unwrap();
+        let actual = AsciiBroad.format(&grid).0;
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn format_default_repeat_matches_unit_formatters() {
+        let grid = generate_maze();
+
+        assert_eq!(Ascii::narrow().format(&grid).0, AsciiNarrow.format(&grid).0);
+        assert_eq!(Ascii::broad().format(&grid).0, AsciiBroad.format(&grid).0);
+        assert_eq!(
+            Ascii::narrow().horizontal_repeat(0).format(&grid).0,
+            AsciiNarrow.format(&grid).0
+        );
+    }
+
+    #[test]
+    fn format_narrow_with_horizontal_repeat() {
+        let mut expected = String::new();
+        expected.push_str(" ___________ \n");
+        expected.push_str("|  |_____   |\n");
+        expected.push_str("|__    __|  |\n");
+        expected.push_str("|   ________|\n");
+        expected.push_str("|___________|\n");
+
+        let formatter = Ascii::narrow().horizontal_repeat(2);
+        let actual = formatter.format(&generate_maze()).0;
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn format_broad_with_horizontal_repeat() {
+        let mut expected = String::new();
+        expected.push_str("+------+------+------+------+\n");
+        expected.push_str("|      |                    |\n");
+        expected.push_str("+      +------+------+      +\n");
+        expected.push_str("|                    |      |\n");
+        expected.push_str("+------+      +------+      +\n");
+        expected.push_str("|                           |\n");
+        expected.push_str("+      +------+------+------+\n");
+        expected.push_str("|                           |\n");
+        expected.push_str("+------+------+------+------+\n");
+
+        let formatter = Ascii::broad().horizontal_repeat(2);
+        let actual = formatter.format(&generate_maze()).0;
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn format_narrow_with_solution() {
+        let mut expected = String::new();
+        expected.push_str(" _______ \n");
+        expected.push_str("|S|___  |\n");
+        expected.push_str("|* * G| |\n");
+        expected.push_str("|  _____|\n");
+        expected.push_str("|_______|\n");
+
+        let formatter = Ascii::narrow().solution(generate_solution());
+        let actual = formatter.format(&generate_maze()).0;
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn format_broad_with_solution() {
+        let mut expected = String::new();
+        expected.push_str("+------+------+------+------+\n");
+        expected.push_str("|   S  |                    |\n");
+        expected.push_str("+      +------+------+      +\n");
+        expected.push_str("|   *      *      G  |      |\n");
+
+        let formatter = Ascii::broad()
+            .horizontal_repeat(2)
+            .solution(generate_solution());
+        let actual = formatter.format(&generate_maze()).0;
+
+        assert!(actual.starts_with(&expected));
+        assert_eq!(actual.matches(['S', 'G', '*']).count(), 4);
+    }
+
+    #[test]
+    fn write_to_matches_format() {
+        let grid = generate_maze();
+
+        for formatter in [
+            Ascii::narrow(),
+            Ascii::broad(),
+            Ascii::narrow().horizontal_repeat(3),
+            Ascii::broad().horizontal_repeat(2),
+        ] {
+            let mut out = Vec::new();
+            formatter.write_to(&grid, &mut out).unwrap();
+
+            assert_eq!(String::from_utf8(out).unwrap(), formatter.format(&grid).0);
+        }
+    }
+
+    #[test]
+    fn write_to_writes_row_by_row() {
+        struct Rows(Vec<String>);
+
+        impl io::Write for Rows {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.0.push(String::from_utf8(buf.to_vec()).unwrap());
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut rows = Rows(vec![]);
+        Ascii::broad()
+            .write_to(&generate_maze(), &mut rows)
+            .unwrap();
+
+        assert_eq!(rows.0.len(), 9);
+        assert_eq!(rows.0[0], "+---+---+---+---+\n");
+        assert!(rows.0.iter().all(|row| row.matches('\n').count() == 1));
+    }
+
+    #[test]
+    fn write_to_returns_io_error() {
+        struct Failing;
+
+        impl io::Write for Failing {
+            fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+                Err(io::Error::new(io::ErrorKind::BrokenPipe, "closed"))
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let err = Ascii::narrow()
+            .write_to(&generate_maze(), Failing)
+            .unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::BrokenPipe);
+    }
+
+    fn generate_solution() -> Solution {
+        Solution::new(vec![(0, 0), (0, 1), (1, 1), (2, 1)])
+    }
+
     fn generate_maze() -> Grid {
         let mut grid = Grid::new(4, 4);
 
@@ -68,6 +68,39 @@ struct GameMapState {
     passage: char,
 }
 
+impl GameMapState {
+    /// Pushes the north wall of a map, leaving a passage where a cell has an opening to the north
+    fn push_north_wall(&self, map: &mut Vec<char>, grid: &Grid, map_cols: usize) {
+        let span = self.span + 1;
+
+        map.push(self.wall);
+        for x in 0..map_cols - 1 {
+            let cx = x / span;
+            let is_last_col = (x + 1).is_multiple_of(span);
+
+            if !is_last_col && grid.is_carved((cx, 0), Cell::NORTH) {
+                map.push(self.passage);
+            } else {
+                map.push(self.wall);
+            }
+        }
+    }
+
+    /// Returns a west wall character of a map row, which is a passage where a cell has an opening
+    /// to the west
+    fn west_wall(&self, grid: &Grid, y: usize) -> char {
+        let span = self.span + 1;
+        let cy = y / span;
+        let is_last_row = (y + 1).is_multiple_of(span);
+
+        if !is_last_row && grid.is_carved((0, cy), Cell::WEST) {
+            self.passage
+        } else {
+            self.wall
+        }
+    }
+}
+
 /// An implementation of a formatter without predefined start and exit points
 impl GameMap<NoStartGoal> {
     /// Returns a new instance of a [GameMap] formatter with a default settings
@@ -205,13 +238,11 @@ impl Formatter<StringWrapper> for GameMap<NoStartGoal> {
         let map_cols = grid.width() * span + 1;
 
         // Add the north wall
-        for _ in 0..map_cols {
-            map.push(self.state.wall);
-        }
+        self.state.push_north_wall(&mut map, grid, map_cols);
 
         for y in 0..map_rows - 1 {
             // Add the west wall
-            map.push(self.state.wall);
+            map.push(self.state.west_wall(grid, y));
 
             for x in 0..map_cols - 1 {
                 // X coordinate of a cell in the grid
@@ -274,13 +305,11 @@ impl Formatter<StringWrapper> for GameMap<WithStartGoal> {
         let map_cols = grid.width() * span + 1;
 
         // Add the north wall
-        for _ in 0..map_cols {
-            map.push(self.state.wall);
-        }
+        self.state.push_north_wall(&mut map, grid, map_cols);
 
         for y in 0..map_rows - 1 {
             // Add the west wall
-            map.push(self.state.wall);
+            map.push(self.state.west_wall(grid, y));
 
             for x in 0..map_cols - 1 {
                 // X coordinate of a cell in the grid
@@ -516,6 +545,29 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn format_with_openings() {
+        let mut expected = String::new();
+        expected.push_str("#.#######\n");
+        expected.push_str("#.#.....#\n");
+        expected.push_str("#.#####.#\n");
+        expected.push_str("#.....#.#\n");
+        expected.push_str("###.###.#\n");
+        expected.push_str("........#\n");
+        expected.push_str("#.#######\n");
+        expected.push_str("#........\n");
+        expected.push_str("#########\n");
+
+        let formatter = GameMap::new().span(1);
+        let mut grid = generate_maze();
+        grid.carve_opening((0, 0), Cell::NORTH).unwrap();
+        grid.carve_opening((0, 2), Cell::WEST).unwrap();
+        grid.carve_opening((3, 3), Cell::EAST).unwrap();
+        let actual = formatter.format(&grid).0;
+
+        assert_eq!(actual, expected);
+    }
+
     fn generate_maze() -> Grid {
         let mut grid = Grid::new(4, 4);
 
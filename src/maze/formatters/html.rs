@@ -0,0 +1,173 @@
+use crate::maze::{
+    formatters::Formatter,
+    grid::{cell::Cell, Grid},
+};
+use crate::utils::color::Color;
+use std::fmt::Write;
+
+use super::StringWrapper;
+
+/// An HTML formatter for a generated maze, e.g. for embedding a maze into docs or web tutorials
+///
+/// A maze is rendered as a `<table>` with a `<td>` per cell, and every wall of a cell is a border
+/// of its `<td>`. All the styles are inline, so the table renders in any browser on its own, without
+/// images or stylesheets.
+///
+/// # Example
+/// ```
+/// use bevy_knossos::maze::*;
+///
+/// let maze = OrthogonalMazeBuilder::new().width(5).height(5).build().unwrap();
+/// let html = maze.format(Html::new().wall(1).passage(16)).0;
+///
+/// assert!(html.starts_with("<table"));
+/// assert_eq!(html.matches("<td").count(), 25);
+/// ```
+pub struct Html {
+    wall_width: usize,
+    passage_width: usize,
+    background_color: Color,
+    foreground_color: Color,
+}
+
+impl Html {
+    /// Returns a new instance of an [Html] formatter with a default settings
+    pub const fn new() -> Html {
+        Html {
+            wall_width: 2,
+            passage_width: 20,
+            background_color: Color::RGB(250, 250, 250),
+            foreground_color: Color::RGB(0, 0, 0),
+        }
+    }
+
+    /// Sets a wall width in pixels and returns itself
+    pub const fn wall(mut self, width: usize) -> Self {
+        self.wall_width = width;
+        self
+    }
+
+    /// Sets a passage width in pixels and returns itself
+    pub const fn passage(mut self, width: usize) -> Self {
+        self.passage_width = width;
+        self
+    }
+
+    /// Sets a background color and returns itself
+    pub const fn background(mut self, color: Color) -> Self {
+        self.background_color = color;
+        self
+    }
+
+    /// Sets a maze (foreground) color and returns itself
+    pub const fn foreground(mut self, color: Color) -> Self {
+        self.foreground_color = color;
+        self
+    }
+}
+
+impl Default for Html {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An implementation of a formatter
+impl Formatter<StringWrapper> for Html {
+    /// Converts a given grid into an HTML table and returns a [StringWrapper] over that table
+    fn format(&self, grid: &Grid) -> StringWrapper {
+        let mut html = String::new();
+
+        writeln!(
+            html,
+            r#"<table style="border-collapse: collapse; background-color: {};">"#,
+            self.background_color
+        )
+        .unwrap();
+
+        for y in 0..grid.height() {
+            writeln!(html, "<tr>").unwrap();
+            for x in 0..grid.width() {
+                // Borders go clockwise from the top one, like in CSS
+                let style = |direction| {
+                    if grid.is_carved((x, y), direction) {
+                        "none"
+                    } else {
+                        "solid"
+                    }
+                };
+                writeln!(
+                    html,
+                    r#"<td style="width: {1}px; height: {1}px; padding: 0; border: {0}px solid {2}; border-style: {3} {4} {5} {6};"></td>"#,
+                    self.wall_width,
+                    self.passage_width,
+                    self.foreground_color,
+                    style(Cell::NORTH),
+                    style(Cell::EAST),
+                    style(Cell::SOUTH),
+                    style(Cell::WEST),
+                )
+                .unwrap();
+            }
+            writeln!(html, "</tr>").unwrap();
+        }
+
+        writeln!(html, "</table>").unwrap();
+
+        StringWrapper(html)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_call_default_params() {
+        let html = Html::new();
+        assert_eq!(2, html.wall_width);
+        assert_eq!(20, html.passage_width);
+        assert_eq!(Color::RGB(250, 250, 250), html.background_color);
+        assert_eq!(Color::RGB(0, 0, 0), html.foreground_color);
+    }
+
+    #[test]
+    fn params_change() {
+        let html = Html::new()
+            .wall(1)
+            .passage(10)
+            .background(Color::RGB(1, 1, 1))
+            .foreground(Color::RGB(100, 100, 100));
+
+        assert_eq!(1, html.wall_width);
+        assert_eq!(10, html.passage_width);
+        assert_eq!(Color::RGB(1, 1, 1), html.background_color);
+        assert_eq!(Color::RGB(100, 100, 100), html.foreground_color);
+    }
+
+    #[test]
+    fn format() {
+        let mut grid = Grid::new(2, 1);
+        grid.carve_passage((0, 0), Cell::EAST).unwrap();
+        grid.carve_opening((1, 0), Cell::SOUTH).unwrap();
+
+        let cell = |borders: &str| {
+            format!(
+                r#"<td style="width: 10px; height: 10px; padding: 0; border: 1px solid rgb(0, 0, 0); border-style: {borders};"></td>"#
+            )
+        };
+        let mut expected = String::new();
+        expected.push_str(
+            r#"<table style="border-collapse: collapse; background-color: rgb(250, 250, 250);">"#,
+        );
+        expected.push_str("\n<tr>\n");
+        expected.push_str(&cell("solid none solid solid"));
+        expected.push('\n');
+        expected.push_str(&cell("solid solid none none"));
+        expected.push_str("\n</tr>\n</table>\n");
+
+        let actual = Html::new().wall(1).passage(10).format(&grid).0;
+
+        assert_eq!(actual, expected);
+    }
+}
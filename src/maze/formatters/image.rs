@@ -1,18 +1,120 @@
-use crate::maze::grid::cell::Cell;
-use crate::maze::{formatters::Formatter, grid::Grid};
+use crate::maze::errors::{FormatError, ParseError};
+use crate::maze::grid::{cell::Cell, pole::Pole};
+use crate::maze::{formatters::Formatter, grid::Grid, Solution};
 use crate::utils::color::Color;
 use crate::utils::types::Coords;
-use image::{ImageBuffer, RgbImage};
+use image::{imageops, ImageBuffer, Rgb, RgbImage, Rgba, RgbaImage};
+use std::collections::{HashMap, HashSet};
 
+use super::walls::{has_horizontal_wall, has_vertical_wall};
 use super::ImageWrapper;
 
+/// The number of samples per pixel along each axis when rendering an anti-aliased image
+const SUPERSAMPLING: usize = 4;
+
+/// The default maximum number of pixels of an image, i.e. 300 MB of RGB data
+const DEFAULT_MAX_PIXELS: usize = 100_000_000;
+
+/// The default color of the fog over the cells out of the visible ones
+const DEFAULT_FOG_COLOR: Color = Color::RGB(0, 0, 0);
+
+/// The default colors of the lowest and the highest cell values
+const DEFAULT_GRADIENT: (Color, Color) = (Color::RGB(49, 54, 149), Color::RGB(215, 48, 39));
+
+/// Rows of the 3x5 px glyphs of legend labels, where the highest of the 3 bits is the leftmost
+/// pixel of a row
+const GLYPHS: [(char, [u8; 5]); 12] = [
+    ('0', [0b111, 0b101, 0b101, 0b101, 0b111]),
+    ('1', [0b010, 0b110, 0b010, 0b010, 0b111]),
+    ('2', [0b111, 0b001, 0b111, 0b100, 0b111]),
+    ('3', [0b111, 0b001, 0b111, 0b001, 0b111]),
+    ('4', [0b101, 0b101, 0b111, 0b001, 0b001]),
+    ('5', [0b111, 0b100, 0b111, 0b001, 0b111]),
+    ('6', [0b111, 0b100, 0b111, 0b101, 0b111]),
+    ('7', [0b111, 0b001, 0b001, 0b001, 0b001]),
+    ('8', [0b111, 0b101, 0b111, 0b101, 0b111]),
+    ('9', [0b111, 0b101, 0b111, 0b001, 0b111]),
+    ('-', [0b000, 0b000, 0b111, 0b000, 0b000]),
+    ('.', [0b000, 0b000, 0b000, 0b000, 0b010]),
+];
+
+/// The width of the color scale of a legend in glyph pixels
+const LEGEND_SCALE_WIDTH: usize = 20;
+
+/// A horizontal band of rows of an image
+///
+/// Cells only ever fill their wall pixels with the foreground color, so the cells overlapping a
+/// band can be drawn independently of the other bands, and in any order.
+struct Band<'a> {
+    /// RGB pixels of the rows of the band
+    pixels: &'a mut [u8],
+    /// The width of the image
+    width: usize,
+    /// The first row of the band in the image
+    top: usize,
+}
+
+impl Band<'_> {
+    const fn rows(&self) -> usize {
+        self.pixels.len() / (self.width * 3)
+    }
+
+    fn put_pixel(&mut self, x: usize, y: usize, Color::RGB(r, g, b): Color) {
+        let idx = ((y - self.top) * self.width + x) * 3;
+        self.pixels[idx..idx + 3].copy_from_slice(&[r, g, b]);
+    }
+}
+
+/// A style of wall joints where two walls meet at a right angle
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum CornerStyle {
+    /// The outer corner of a joint is square, as if the walls overlapped
+    #[default]
+    Square,
+    /// The outer corner of a joint is cut off diagonally
+    Bevel,
+}
+
+/// A corner of an image
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Corner {
+    /// The top left corner
+    TopLeft,
+    /// The top right corner
+    TopRight,
+    /// The bottom left corner
+    BottomLeft,
+    /// The bottom right corner
+    BottomRight,
+}
+
 /// An Image formatter for a generated maze
+#[derive(Clone)]
 pub struct Image {
     wall_width: usize,
+    wall_widths: Option<HashMap<Coords, usize>>,
+    thickest_wall: usize,
     passage_width: usize,
     margin: usize,
+    legend: bool,
     background_color: Color,
     foreground_color: Color,
+    wall_colors: Option<(Color, Color)>,
+    wall_gradient: Option<(Color, Color)>,
+    passage_color: Option<Color>,
+    cell_values: Option<HashMap<Coords, f64>>,
+    gradient: (Color, Color),
+    corner_style: CornerStyle,
+    dead_end_radius: usize,
+    passage_lines: Option<(usize, Color)>,
+    hide_walls: bool,
+    shadow: Option<((i32, i32), Color, f64)>,
+    visible_cells: Option<HashSet<Coords>>,
+    fog_color: Color,
+    antialias: bool,
+    thumbnail: Option<u32>,
+    watermark: Option<(RgbaImage, Corner, usize, f64)>,
+    max_pixels: usize,
 }
 
 impl Image {
@@ -20,10 +122,29 @@ impl Image {
     pub const fn new() -> Image {
         Image {
             wall_width: 40,
+            wall_widths: None,
+            thickest_wall: 0,
             passage_width: 40,
+            legend: false,
             background_color: Color::RGB(250, 250, 250),
             foreground_color: Color::RGB(0, 0, 0),
+            wall_colors: None,
+            wall_gradient: None,
+            passage_color: None,
+            cell_values: None,
+            gradient: DEFAULT_GRADIENT,
+            corner_style: CornerStyle::Square,
+            dead_end_radius: 0,
+            passage_lines: None,
+            hide_walls: false,
+            shadow: None,
+            visible_cells: None,
+            fog_color: DEFAULT_FOG_COLOR,
             margin: 50,
+            antialias: false,
+            thumbnail: None,
+            watermark: None,
+            max_pixels: DEFAULT_MAX_PIXELS,
         }
     }
 
@@ -33,6 +154,35 @@ impl Image {
         self
     }
 
+    /// Sets widths of the walls of given cells in pixels and returns itself, e.g. to emphasize a
+    /// locked area with bolder walls
+    ///
+    /// Walls of the other cells keep the regular [width](Image::wall). A thicker wall grows from
+    /// its regular width to both sides, into the passages next to it, and the margin grows by
+    /// the half of the thickest wall out of the regular width, so that the outer walls aren't
+    /// clipped. Widths under the regular one make no difference, as the adjacent cells draw their
+    /// shared walls in full, and a [thumbnail](Image::thumbnail) ignores the widths altogether.
+    ///
+    /// # Example
+    /// ```
+    /// use std::collections::HashMap;
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let maze = OrthogonalMazeBuilder::new().width(3).height(3).build().unwrap();
+    /// let regular = maze.format(Image::new()).0;
+    /// let bold = maze
+    ///     .format(Image::new().wall_widths(HashMap::from([((1, 1), 60)])))
+    ///     .0;
+    ///
+    /// // The margin grows by 10 px on each side
+    /// assert_eq!(bold.width(), regular.width() + 20);
+    /// ```
+    pub fn wall_widths(mut self, widths: HashMap<Coords, usize>) -> Self {
+        self.thickest_wall = widths.values().copied().max().unwrap_or(0);
+        self.wall_widths = Some(widths);
+        self
+    }
+
     /// Sets a passage width and returns itself
     pub const fn passage(mut self, width: usize) -> Self {
         self.passage_width = width;
@@ -51,12 +201,801 @@ impl Image {
         self
     }
 
+    /// Sets distances of cells, e.g. the numbers of steps from a start cell, and returns itself
+    ///
+    /// Passages of cells are tinted as a heatmap, i.e. the distances are taken as [cell
+    /// values](Image::cell_values), colored from blue for the shortest distance to red for the
+    /// longest one with the default [gradient](Image::gradient). Only the passage inside a cell is
+    /// tinted, while cells without a distance keep the [passage color](Image::passage_color).
+    ///
+    /// # Example
+    /// ```
+    /// use std::collections::HashMap;
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let maze = OrthogonalMazeBuilder::new().width(3).height(1).build().unwrap();
+    /// let distances = HashMap::from([((0, 0), 0), ((2, 0), 2)]);
+    /// let image = maze.format(Image::new().heatmap(distances)).0;
+    ///
+    /// assert_eq!(image.get_pixel(110, 110).0, [49, 54, 149]);
+    /// assert_eq!(image.get_pixel(190, 110).0, [250, 250, 250]);
+    /// assert_eq!(image.get_pixel(270, 110).0, [215, 48, 39]);
+    /// ```
+    pub fn heatmap(mut self, distances: HashMap<Coords, usize>) -> Self {
+        let values = distances.into_iter().map(|(coords, d)| (coords, d as f64));
+        self.cell_values = Some(values.collect());
+        self
+    }
+
+    /// Sets whether to draw a legend of the [cell values](Image::cell_values) and returns itself
+    ///
+    /// The legend is a color scale of the [gradient](Image::gradient) between labels of the lowest
+    /// and the highest values, rounded to two decimals, drawn in the foreground color into the
+    /// margin under the bottom left corner of the maze. Its size follows the margin, and it is
+    /// skipped when there are no finite cell values, or when it doesn't fit into the margin
+    /// without overlapping the maze, e.g. with a margin under 10 px. Thumbnails never have a
+    /// legend. Disabled by default.
+    ///
+    /// # Example
+    /// ```
+    /// use std::collections::HashMap;
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let maze = OrthogonalMazeBuilder::new().width(3).height(1).build().unwrap();
+    /// let distances = HashMap::from([((0, 0), 0), ((2, 0), 2)]);
+    /// let formatter = Image::new().heatmap(distances);
+    ///
+    /// let plain = maze.format(formatter.clone()).0;
+    /// let image = maze.format(formatter.legend(true)).0;
+    ///
+    /// // The color scale starts right after the `0` label in the bottom margin
+    /// assert_eq!(image.dimensions(), plain.dimensions());
+    /// assert_eq!(image.get_pixel(75, 190).0, [49, 54, 149]);
+    /// assert_eq!(plain.get_pixel(75, 190).0, [250, 250, 250]);
+    /// ```
+    pub const fn legend(mut self, legend: bool) -> Self {
+        self.legend = legend;
+        self
+    }
+
+    /// Sets colors of horizontal walls, i.e. the northern and the southern ones, and of vertical
+    /// walls, i.e. the eastern and the western ones, and returns itself
+    ///
+    /// Different colors give the maze a woven look. The joints of walls take the horizontal color,
+    /// so horizontal walls run over the vertical ones. Defaults to the foreground color for both.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::{maze::{formatters::Formatter, *}, Color};
+    ///
+    /// let formatter = Image::new()
+    ///     .margin(0)
+    ///     .wall_colors(Color::RGB(200, 0, 0), Color::RGB(0, 0, 200));
+    /// let image = formatter.format(&Grid::new(1, 1)).0;
+    ///
+    /// assert_eq!(image.get_pixel(60, 10).0, [200, 0, 0]);
+    /// assert_eq!(image.get_pixel(10, 60).0, [0, 0, 200]);
+    /// assert_eq!(image.get_pixel(10, 10).0, [200, 0, 0]);
+    /// ```
+    pub const fn wall_colors(mut self, horizontal: Color, vertical: Color) -> Self {
+        self.wall_colors = Some((horizontal, vertical));
+        self
+    }
+
+    /// Sets colors walls fade between across their thickness and returns itself
+    ///
+    /// Horizontal walls, along with the joints, fade from the top color on their upper edge to the
+    /// bottom color on their lower edge, and vertical walls fade the same way from their western
+    /// edge to their eastern one, which gives walls a beveled look. The gradient takes precedence
+    /// over the [wall colors](Image::wall_colors), while thumbnails keep their walls flat. Defaults
+    /// to flat walls of the foreground color.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::{maze::{formatters::Formatter, *}, Color};
+    ///
+    /// let formatter = Image::new()
+    ///     .wall(11)
+    ///     .margin(0)
+    ///     .wall_gradient(Color::RGB(200, 200, 200), Color::RGB(0, 0, 0));
+    /// let image = formatter.format(&Grid::new(1, 1)).0;
+    ///
+    /// assert_eq!(image.get_pixel(20, 0).0, [200, 200, 200]);
+    /// assert_eq!(image.get_pixel(20, 5).0, [100, 100, 100]);
+    /// assert_eq!(image.get_pixel(20, 10).0, [0, 0, 0]);
+    /// ```
+    pub const fn wall_gradient(mut self, top: Color, bottom: Color) -> Self {
+        self.wall_gradient = Some((top, bottom));
+        self
+    }
+
+    /// Sets a passage (floor) color and returns itself
+    ///
+    /// Passages are colored inside the maze only, while the margin keeps the background color.
+    /// Defaults to the background color.
+    pub const fn passage_color(mut self, color: Color) -> Self {
+        self.passage_color = Some(color);
+        self
+    }
+
+    /// Sets values of cells tinting their passages and returns itself
+    ///
+    /// Values are normalized between the lowest and the highest finite values, which are colored
+    /// with the ends of the [gradient](Image::gradient), e.g. to visualize enemy density over a
+    /// maze. Only the passage inside a cell is tinted, while cells without a finite value keep the
+    /// passage color.
+    ///
+    /// # Example
+    /// ```
+    /// use std::collections::HashMap;
+    /// use bevy_knossos::{maze::*, Color};
+    ///
+    /// let maze = OrthogonalMazeBuilder::new().width(3).height(1).build().unwrap();
+    /// let values = HashMap::from([((0, 0), 1.0), ((2, 0), 5.0)]);
+    /// let image = maze
+    ///     .format(Image::new().margin(0).cell_values(values).gradient(
+    ///         Color::RGB(0, 0, 255),
+    ///         Color::RGB(255, 0, 0),
+    ///     ))
+    ///     .0;
+    ///
+    /// assert_eq!(image.get_pixel(60, 60).0, [0, 0, 255]);
+    /// assert_eq!(image.get_pixel(140, 60).0, [250, 250, 250]);
+    /// assert_eq!(image.get_pixel(220, 60).0, [255, 0, 0]);
+    /// ```
+    pub fn cell_values(mut self, values: HashMap<Coords, f64>) -> Self {
+        self.cell_values = Some(values);
+        self
+    }
+
+    /// Sets colors of the lowest and the highest [cell values](Image::cell_values) and returns
+    /// itself
+    ///
+    /// Values in between are colored by interpolating the two colors.
+    pub const fn gradient(mut self, low: Color, high: Color) -> Self {
+        self.gradient = (low, high);
+        self
+    }
+
+    /// Sets a style of corners where two walls meet at a right angle and returns itself
+    ///
+    /// Only corners of exactly two perpendicular walls are affected, while ends of walls and
+    /// joints of three or four walls keep their square shape. Defaults to
+    /// [Square](CornerStyle::Square).
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::{formatters::Formatter, *};
+    ///
+    /// let formatter = Image::new().wall(10).margin(0).corner_style(CornerStyle::Bevel);
+    /// let image = formatter.format(&Grid::new(1, 1)).0;
+    ///
+    /// // The outer corners of the maze are cut off
+    /// assert_eq!(image.get_pixel(0, 0).0, [250, 250, 250]);
+    /// assert_eq!(image.get_pixel(10, 0).0, [0, 0, 0]);
+    /// ```
+    pub const fn corner_style(mut self, style: CornerStyle) -> Self {
+        self.corner_style = style;
+        self
+    }
+
+    /// Sets a radius in pixels of the rounded ends of dead ends and returns itself
+    ///
+    /// Only the two corners of the passage of a dead end at its closed end are rounded, so the
+    /// corridors leading into dead ends and the rest of the cells stay square. A radius of half the
+    /// passage width makes a semicircular end, and larger ones are clamped to it. Defaults to `0`,
+    /// i.e. square dead ends.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::{formatters::Formatter, *};
+    ///
+    /// let mut grid = Grid::new(2, 1);
+    /// grid.carve_passage((0, 0), Cell::EAST).unwrap();
+    /// let formatter = Image::new().wall(10).passage(20).margin(0).round_dead_ends(10);
+    /// let image = formatter.format(&grid).0;
+    ///
+    /// // The western end of the first cell is rounded, while its eastern side stays square
+    /// assert_eq!(image.get_pixel(10, 10).0, [0, 0, 0]);
+    /// assert_eq!(image.get_pixel(29, 10).0, [250, 250, 250]);
+    /// ```
+    pub const fn round_dead_ends(mut self, radius: usize) -> Self {
+        self.dead_end_radius = radius;
+        self
+    }
+
+    /// Sets a thickness and a color of lines drawn over the maze between the centers of cells
+    /// connected with passages and returns itself
+    ///
+    /// The lines show the graph of passages, i.e. the spanning tree of a perfect maze, like a
+    /// subway map. A line goes from a cell through an opening in the outer wall up to the edge of
+    /// the maze. Along with [hidden walls](Image::hide_walls), only the graph is drawn.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::{maze::*, Color};
+    ///
+    /// let maze = OrthogonalMazeBuilder::new().width(3).height(3).build().unwrap();
+    /// let image = maze
+    ///     .format(
+    ///         Image::new()
+    ///             .passage_lines(4, Color::RGB(200, 0, 0))
+    ///             .hide_walls(true)
+    ///             .margin(0),
+    ///     )
+    ///     .0;
+    ///
+    /// // Every cell is connected, so there is a line across the center of every cell
+    /// assert_eq!(image.get_pixel(60, 60).0, [200, 0, 0]);
+    /// assert_eq!(image.get_pixel(0, 0).0, [250, 250, 250]);
+    /// ```
+    pub const fn passage_lines(mut self, thickness: usize, color: Color) -> Self {
+        self.passage_lines = Some((thickness, color));
+        self
+    }
+
+    /// Sets whether walls are hidden and returns itself
+    ///
+    /// Hidden walls leave only the floor, the [cell values](Image::cell_values) and the [passage
+    /// lines](Image::passage_lines) in the image. Disabled by default.
+    pub const fn hide_walls(mut self, hide: bool) -> Self {
+        self.hide_walls = hide;
+        self
+    }
+
+    /// Sets an offset in pixels, a color and an opacity of a shadow cast by walls and returns
+    /// itself
+    ///
+    /// A shadow is a translucent copy of the walls shifted by the offset, e.g. `(4, 4)` to cast it
+    /// towards the bottom right corner, which is blended over the floor under the walls before the
+    /// walls themselves are drawn. An opacity of 0 leaves no shadow and an opacity of 1 makes it
+    /// solid, while values out of that range are clamped. Hidden walls cast no shadow. Disabled by
+    /// default.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::{maze::*, Color};
+    ///
+    /// let maze = OrthogonalMazeBuilder::new().width(3).height(1).build().unwrap();
+    /// let image = maze
+    ///     .format(Image::new().shadow((10, 10), Color::RGB(0, 0, 250), 0.4))
+    ///     .0;
+    ///
+    /// // The margin takes the shadow of the outer walls
+    /// assert_eq!(image.get_pixel(50, 50).0, [0, 0, 0]);
+    /// assert_eq!(image.get_pixel(65, 175).0, [150, 150, 250]);
+    /// assert_eq!(image.get_pixel(45, 175).0, [250, 250, 250]);
+    /// ```
+    pub const fn shadow(mut self, offset: (i32, i32), color: Color, opacity: f64) -> Self {
+        self.shadow = Some((offset, color, opacity));
+        self
+    }
+
+    /// Sets a logo stamped onto a corner of the final image, a padding between the logo and the
+    /// edges of the image in pixels, and an opacity of the logo, and returns itself
+    ///
+    /// The logo is alpha-blended over everything else in the image, thumbnails included, with its
+    /// own alpha channel multiplied by the opacity. An opacity of 0 leaves no logo and an opacity
+    /// of 1 keeps the logo as it is, while values out of that range are clamped. A logo larger
+    /// than the image without the padding is clipped by the opposite edges. Disabled by default.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    /// use image::{Rgba, RgbaImage};
+    ///
+    /// let logo = RgbaImage::from_pixel(8, 4, Rgba([250, 0, 0, 255]));
+    /// let maze = OrthogonalMazeBuilder::new().width(3).height(3).build().unwrap();
+    /// let image = maze
+    ///     .format(Image::new().watermark(logo, Corner::BottomRight, 10, 0.6))
+    ///     .0;
+    ///
+    /// let (width, height) = image.dimensions();
+    /// assert_eq!(image.get_pixel(width - 11, height - 11).0, [250, 100, 100]);
+    /// assert_eq!(image.get_pixel(width - 10, height - 10).0, [250, 250, 250]);
+    /// ```
+    pub fn watermark(
+        mut self,
+        logo: RgbaImage,
+        corner: Corner,
+        padding: usize,
+        opacity: f64,
+    ) -> Self {
+        self.watermark = Some((logo, corner, padding, opacity));
+        self
+    }
+
+    /// Sets the only cells visible through a fog and returns itself, e.g. the explored part of a
+    /// maze in a game
+    ///
+    /// Visible cells are rendered as usual along with all of their walls, so the walls between the
+    /// visible cells and the fog stay in the image. Everything else inside the maze, including the
+    /// walls of the other cells and the [passage lines](Image::passage_lines) through them, is
+    /// filled with the [fog color](Image::fog_color), while the margin keeps the background color.
+    ///
+    /// # Example
+    /// ```
+    /// use std::collections::HashSet;
+    /// use bevy_knossos::{maze::*, Color};
+    ///
+    /// let maze = OrthogonalMazeBuilder::new().width(3).height(1).build().unwrap();
+    /// let explored = HashSet::from([(0, 0)]);
+    /// let image = maze
+    ///     .format(
+    ///         Image::new()
+    ///             .margin(0)
+    ///             .visible_cells(explored)
+    ///             .fog_color(Color::RGB(30, 30, 30)),
+    ///     )
+    ///     .0;
+    ///
+    /// assert_eq!(image.get_pixel(60, 60).0, [250, 250, 250]);
+    /// assert_eq!(image.get_pixel(140, 60).0, [30, 30, 30]);
+    /// ```
+    pub fn visible_cells(mut self, cells: HashSet<Coords>) -> Self {
+        self.visible_cells = Some(cells);
+        self
+    }
+
+    /// Sets a color of the fog over the cells out of the [visible cells](Image::visible_cells)
+    /// and returns itself
+    ///
+    /// Defaults to black.
+    pub const fn fog_color(mut self, color: Color) -> Self {
+        self.fog_color = color;
+        self
+    }
+
     /// Sets a margin (a distance between a maze and the image borders) and returns itself
+    ///
+    /// The outer walls on the far sides overlap the margin by a pixel, so an image without a margin
+    /// grows by that pixel instead.
     pub const fn margin(mut self, value: usize) -> Self {
         self.margin = value;
         self
     }
 
+    /// Sets whether edges of walls are anti-aliased and returns itself
+    ///
+    /// An anti-aliased image is rendered at 4x the size and then downscaled, so that pixels on
+    /// the edges of walls blend the foreground and background colors. Rendering then takes about
+    /// 16 times as many pixels to fill plus the downscale, which is noticeably slower for large
+    /// mazes. Disabled by default to keep the exact pixel output.
+    pub const fn antialias(mut self, antialias: bool) -> Self {
+        self.antialias = antialias;
+        self
+    }
+
+    /// Sets a size of passages of cells in pixels for rendering a small thumbnail of a maze instead
+    /// of a full image and returns itself, e.g. for previews or minimaps
+    ///
+    /// A thumbnail skips the regular wall and passage geometry, and fills every cell with a block
+    /// of `cell_px` by `cell_px` pixels of the passage color, bordered by 1 px walls of the
+    /// foreground color shared with the neighbouring cells. Thus, a `width`x`height` maze takes
+    /// `width * (cell_px + 1) + 1` by `height * (cell_px + 1) + 1` pixels, and a single pixel per
+    /// passage keeps even a very large maze small. The walls and passages widths, the margin and
+    /// the other decorations are ignored, and a zero size counts as a single pixel.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let maze = OrthogonalMazeBuilder::new().width(100).height(50).build().unwrap();
+    /// let thumbnail = maze.format(Image::new().thumbnail(1)).0;
+    ///
+    /// assert_eq!(thumbnail.dimensions(), (201, 101));
+    /// assert_eq!(thumbnail.get_pixel(0, 0).0, [0, 0, 0]);
+    /// assert_eq!(thumbnail.get_pixel(1, 1).0, [250, 250, 250]);
+    /// ```
+    pub const fn thumbnail(mut self, cell_px: u32) -> Self {
+        self.thumbnail = Some(cell_px);
+        self
+    }
+
+    /// Sets a maximum number of pixels of an image and returns itself
+    ///
+    /// A huge grid with wide walls may require a multi-gigabyte image, so the size of the image is
+    /// checked before allocating it. An anti-aliased image is checked at the supersampled size.
+    /// Defaults to 100 million pixels.
+    pub const fn max_pixels(mut self, pixels: usize) -> Self {
+        self.max_pixels = pixels;
+        self
+    }
+
+    /// Converts a given grid into an image the same way as [format](Formatter::format), but returns
+    /// a [FormatError] instead of allocating an image larger than the [maximum number of
+    /// pixels](Image::max_pixels)
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// // Each side of the image would take 1000 * 80 + 140 px
+    /// assert!(Image::new().try_format(&Grid::new(1000, 1000)).is_err());
+    /// assert!(Image::new().try_format(&Grid::new(10, 10)).is_ok());
+    /// ```
+    pub fn try_format(&self, grid: &Grid) -> Result<ImageWrapper, FormatError> {
+        self.check_size(grid)?;
+        Ok(self.render(grid))
+    }
+
+    /// Renders the path of a given solution through a given grid as a transparent image of the same
+    /// dimensions as the image of the grid, e.g. for overlaying the solution on the maze or toggling
+    /// it in a UI
+    ///
+    /// The path is a line between the centers of the consecutive cells of the solution, drawn with
+    /// the thickness and the color of [passage lines](Image::passage_lines) if set, or with a third
+    /// of the passage width and the foreground color otherwise. Everything else is left fully
+    /// transparent. The line follows the layout of a [thumbnail](Image::thumbnail) as well, where
+    /// it is at most as thick as a cell, and is anti-aliased along with the image.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let mut maze = OrthogonalMazeBuilder::new().seed(1).build().unwrap();
+    /// let solution = maze.solve((0, 0), (9, 9)).unwrap();
+    /// let formatter = Image::new();
+    ///
+    /// let maze_image = maze.format(formatter.clone()).0;
+    /// let solution_image = formatter.render_solution_layer(maze.get_grid_mut(), &solution);
+    ///
+    /// assert_eq!(solution_image.dimensions(), maze_image.dimensions());
+    /// assert_eq!(solution_image.get_pixel(0, 0).0[3], 0);
+    /// ```
+    pub fn render_solution_layer(&self, grid: &Grid, solution: &Solution) -> RgbaImage {
+        let (thickness, color) = self
+            .passage_lines
+            .unwrap_or_else(|| ((self.passage_width / 3).max(1), self.foreground_color));
+        let Color::RGB(r, g, b) = color;
+
+        if self.antialias && self.thumbnail.is_none() {
+            let (width, height) = self.sizes(grid);
+            let supersampled = Image {
+                wall_width: self.wall_width * SUPERSAMPLING,
+                wall_widths: self.supersampled_wall_widths(),
+                thickest_wall: self.thickest_wall * SUPERSAMPLING,
+                passage_width: self.passage_width * SUPERSAMPLING,
+                margin: self.margin * SUPERSAMPLING,
+                passage_lines: Some((thickness * SUPERSAMPLING, color)),
+                antialias: false,
+                watermark: None,
+                ..self.clone()
+            }
+            .render_solution_layer(grid, solution);
+
+            return imageops::resize(
+                &supersampled,
+                width as u32,
+                height as u32,
+                imageops::FilterType::Triangle,
+            );
+        }
+
+        // The first pixel inside the first cell, the size of the inside of a cell, the distance
+        // between two adjacent cells, and the size of the image
+        let (inner, passage, step, (width, height)) = self.thumbnail_pitch().map_or_else(
+            || {
+                (
+                    self.inset() + self.wall_width,
+                    self.passage_width,
+                    self.wall_width + self.passage_width,
+                    self.sizes(grid),
+                )
+            },
+            |pitch| {
+                (
+                    1,
+                    pitch - 1,
+                    pitch,
+                    (grid.width() * pitch + 1, grid.height() * pitch + 1),
+                )
+            },
+        );
+        // Lines through openings on a torus end at the edges of the maze, out of the margin
+        let edge = inner - self.thumbnail.map_or(self.wall_width, |_| 1);
+        let thickness = thickness.min(passage).max(1);
+        let start = |line: usize| (inner + line * step + passage / 2).saturating_sub(thickness / 2);
+
+        // Transparent pixels keep the color of the line, so anti-aliased edges don't darken
+        let mut image = RgbaImage::from_pixel(width as u32, height as u32, Rgba([r, g, b, 0]));
+        let mut fill = |x0: usize, y0: usize, x1: usize, y1: usize| {
+            for y in y0..y1.min(height) {
+                for x in x0..x1.min(width) {
+                    image.put_pixel(x as u32, y as u32, Rgba([r, g, b, 255]));
+                }
+            }
+        };
+
+        for &(x, y) in &solution.path {
+            fill(
+                start(x),
+                start(y),
+                start(x) + thickness,
+                start(y) + thickness,
+            );
+        }
+        for pair in solution.path.windows(2) {
+            let (from, to) = (pair[0], pair[1]);
+            let Some(pole) = grid.shared_wall(from, to) else {
+                continue;
+            };
+
+            if from.0.abs_diff(to.0) + from.1.abs_diff(to.1) == 1 {
+                let (x0, y0) = (start(from.0.min(to.0)), start(from.1.min(to.1)));
+                let (x1, y1) = (start(from.0.max(to.0)), start(from.1.max(to.1)));
+                fill(x0, y0, x1 + thickness, y1 + thickness);
+                continue;
+            }
+
+            // Cells adjacent around a torus are joined through the edges of the maze
+            for ((x, y), pole) in [(from, pole), (to, pole.opposite())] {
+                let (sx, sy) = (start(x), start(y));
+                match pole {
+                    Pole::N => fill(sx, edge, sx + thickness, sy + thickness),
+                    Pole::S => fill(sx, sy, sx + thickness, height - edge),
+                    Pole::W => fill(edge, sy, sx + thickness, sy + thickness),
+                    Pole::E => fill(sx, sy, width - edge, sy + thickness),
+                }
+            }
+        }
+
+        image
+    }
+
+    /// Returns a [FormatError] if rendering a given grid would allocate more than the maximum
+    /// number of pixels
+    fn check_size(&self, grid: &Grid) -> Result<(), FormatError> {
+        let (step, edges, scale) = self.thumbnail_pitch().map_or_else(
+            || {
+                let scale = if self.antialias { SUPERSAMPLING } else { 1 };
+                (
+                    self.wall_width + self.passage_width,
+                    self.wall_width + self.inset() * 2 + self.far_overlap(),
+                    scale,
+                )
+            },
+            |pitch| (pitch, 1, 1),
+        );
+        let side = |cells: usize| {
+            step.checked_mul(cells)?
+                .checked_add(edges)?
+                .checked_mul(scale)
+        };
+        let pixels = side(grid.width())
+            .zip(side(grid.height()))
+            .and_then(|(width, height)| {
+                // Image dimensions are limited to `u32`
+                u32::try_from(width.max(height)).ok()?;
+                width.checked_mul(height)
+            });
+
+        match pixels {
+            Some(pixels) if pixels <= self.max_pixels => Ok(()),
+            _ => Err(FormatError::reason(format!(
+                "A {}x{} maze exceeds the maximum of {} pixels",
+                grid.width(),
+                grid.height(),
+                self.max_pixels
+            ))),
+        }
+    }
+
+    /// Reconstructs a grid from an image rendered by an [Image] formatter with the same settings
+    ///
+    /// The grid size is implied by the wall width, the passage width and the margin, and the
+    /// presence of every wall, including openings in the outer walls, is read by sampling a pixel in
+    /// the middle of it. A pixel closer to the foreground color than to the passage one is a wall.
+    /// This is inherently fragile, e.g. a lossy compressed image may not be parsed correctly.
+    ///
+    /// Returns a [ParseError] if walls are narrower than 1 px or passages are narrower than 2 px,
+    /// or the image dimensions don't match any grid size.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::{formatters::Formatter, *};
+    ///
+    /// let maze = OrthogonalMazeBuilder::new().width(6).height(4).build().unwrap();
+    /// let formatter = || Image::new().wall(5).passage(10).margin(3);
+    /// let image = maze.format(formatter()).0;
+    ///
+    /// let grid = formatter().parse(&image).unwrap();
+    ///
+    /// assert_eq!((grid.width(), grid.height()), (6, 4));
+    /// assert_eq!(formatter().format(&grid).0, image);
+    /// ```
+    pub fn parse(&self, image: &RgbImage) -> Result<Grid, ParseError> {
+        // Wall joints overlap neighbouring walls by a pixel, so a narrower passage can't be read
+        if self.wall_width == 0 || self.passage_width < 2 {
+            return Err(ParseError::reason(
+                "Wall width must be at least 1 px and passage width at least 2 px",
+            ));
+        }
+
+        let (image_width, image_height) = (image.width() as usize, image.height() as usize);
+        let (Some(width), Some(height)) = (
+            self.cells_along(image_width),
+            self.cells_along(image_height),
+        ) else {
+            return Err(ParseError::reason(format!(
+                "Image dimensions {}x{} don't match a grid with {} px walls, {} px passages and a {} px margin",
+                image_width, image_height, self.wall_width, self.passage_width, self.margin
+            )));
+        };
+
+        let mut grid = Grid::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                if !self.has_horizontal_wall(image, (x, y), height) {
+                    self.open(&mut grid, (x, y), Cell::NORTH);
+                }
+                if !self.has_vertical_wall(image, (x, y), width) {
+                    self.open(&mut grid, (x, y), Cell::WEST);
+                }
+                if y + 1 == height && !self.has_horizontal_wall(image, (x, height), height) {
+                    self.open(&mut grid, (x, y), Cell::SOUTH);
+                }
+                if x + 1 == width && !self.has_vertical_wall(image, (width, y), width) {
+                    self.open(&mut grid, (x, y), Cell::EAST);
+                }
+            }
+        }
+
+        Ok(grid)
+    }
+
+    /// Returns the number of cells fitting exactly into a given number of pixels, or `None` if
+    /// the pixels don't match any number of cells
+    const fn cells_along(&self, pixels: usize) -> Option<usize> {
+        let step = self.wall_width + self.passage_width;
+        match pixels.checked_sub(self.inset() * 2 + self.wall_width + self.far_overlap()) {
+            Some(maze) if maze > 0 && maze % step == 0 => Some(maze / step),
+            _ => None,
+        }
+    }
+
+    /// Indicates if there is a wall along the top side of a cell `(x, y)`, or along the bottom
+    /// side of the last row when `y` equals the grid height
+    fn has_horizontal_wall(&self, image: &RgbImage, (x, y): Coords, height: usize) -> bool {
+        let px = self.passage_center(x);
+        let py = self.wall_center(y, height);
+        self.is_wall_pixel(image.get_pixel(px as u32, py as u32))
+    }
+
+    /// Indicates if there is a wall along the left side of a cell `(x, y)`, or along the right side
+    /// of the last column when `x` equals the grid width
+    fn has_vertical_wall(&self, image: &RgbImage, (x, y): Coords, width: usize) -> bool {
+        let px = self.wall_center(x, width);
+        let py = self.passage_center(y);
+        self.is_wall_pixel(image.get_pixel(px as u32, py as u32))
+    }
+
+    /// Returns the pixel in the middle of a wall before a given column or row of cells
+    const fn wall_center(&self, line: usize, lines: usize) -> usize {
+        // The outer walls on the far sides are drawn a pixel further than the other walls
+        let offset = if line == lines {
+            self.wall_width.div_ceil(2)
+        } else {
+            self.wall_width / 2
+        };
+        self.inset() + line * (self.wall_width + self.passage_width) + offset
+    }
+
+    /// Returns the pixel in the middle of the passage of a given column or row of cells
+    const fn passage_center(&self, line: usize) -> usize {
+        self.inset()
+            + line * (self.wall_width + self.passage_width)
+            + self.wall_width
+            + self.passage_width / 2
+    }
+
+    fn is_wall_pixel(&self, pixel: &Rgb<u8>) -> bool {
+        let distance = |color: Color| {
+            let Color::RGB(r, g, b) = color;
+            [r, g, b]
+                .iter()
+                .zip(pixel.0)
+                .map(|(c, p)| (*c as i32 - p as i32).pow(2))
+                .sum::<i32>()
+        };
+        distance(self.foreground_color) < distance(self.floor_color())
+    }
+
+    /// Returns the distance between the first pixels of two adjacent cells of a thumbnail, or
+    /// `None` if a full image is rendered
+    fn thumbnail_pitch(&self) -> Option<usize> {
+        self.thumbnail.map(|cell_px| cell_px.max(1) as usize + 1)
+    }
+
+    /// Renders a thumbnail of a given grid, where walls are 1 px lines between blocks of cells
+    fn render_thumbnail(&self, grid: &Grid, pitch: usize) -> ImageWrapper {
+        let (width, height) = (grid.width() * pitch + 1, grid.height() * pitch + 1);
+        let Color::RGB(r, g, b) = self.floor_color();
+        let mut image = RgbImage::from_pixel(width as u32, height as u32, Rgb([r, g, b]));
+
+        // Every wall covers the posts at both of its ends, so posts without walls stay floor, and
+        // horizontal walls are drawn over the vertical ones
+        let Color::RGB(r, g, b) = self.wall_color(false);
+        for j in 0..grid.height() {
+            for i in 0..=grid.width() {
+                if has_vertical_wall(grid, i, j) {
+                    for y in j * pitch..=(j + 1) * pitch {
+                        image.put_pixel((i * pitch) as u32, y as u32, Rgb([r, g, b]));
+                    }
+                }
+            }
+        }
+        let Color::RGB(r, g, b) = self.wall_color(true);
+        for j in 0..=grid.height() {
+            for i in 0..grid.width() {
+                if has_horizontal_wall(grid, i, j) {
+                    for x in i * pitch..=(i + 1) * pitch {
+                        image.put_pixel(x as u32, (j * pitch) as u32, Rgb([r, g, b]));
+                    }
+                }
+            }
+        }
+
+        ImageWrapper(image)
+    }
+
+    /// Returns the color of horizontal or vertical walls, which is the foreground color unless set
+    /// otherwise
+    const fn wall_color(&self, horizontal: bool) -> Color {
+        match self.wall_colors {
+            Some((color, _)) if horizontal => color,
+            Some((_, color)) => color,
+            None => self.foreground_color,
+        }
+    }
+
+    /// Returns the color of passages, which is the background color unless set otherwise
+    fn floor_color(&self) -> Color {
+        self.passage_color.unwrap_or(self.background_color)
+    }
+
+    fn open(&self, grid: &mut Grid, coords: Coords, direction: Cell) {
+        if grid.get_next_cell_coords(coords, direction).is_ok() {
+            grid.carve_passage(coords, direction).unwrap();
+        } else {
+            grid.carve_opening(coords, direction).unwrap();
+        }
+    }
+
+    /// Returns widths of the walls of cells scaled up for a supersampled image
+    fn supersampled_wall_widths(&self) -> Option<HashMap<Coords, usize>> {
+        self.wall_widths.as_ref().map(|widths| {
+            widths
+                .iter()
+                .map(|(coords, width)| (*coords, width * SUPERSAMPLING))
+                .collect()
+        })
+    }
+
+    /// Returns the distance between the edges of the image and the outer walls, i.e. the margin
+    /// along with the part of the thickest wall growing out of the regular wall width
+    const fn inset(&self) -> usize {
+        self.margin + self.thickest_wall.saturating_sub(self.wall_width) / 2
+    }
+
+    /// Returns how far the walls of a given cell grow out of and into the regular wall width
+    fn wall_growth(&self, coords: Coords) -> (usize, usize) {
+        let width = self
+            .wall_widths
+            .as_ref()
+            .and_then(|widths| widths.get(&coords))
+            .map_or(self.wall_width, |width| (*width).max(self.wall_width));
+        let extra = width - self.wall_width;
+        (extra / 2, extra - extra / 2)
+    }
+
+    /// Returns the number of pixels the image grows by to fit the outer walls on the far sides,
+    /// which overlap the margin by a pixel like cells overlap the next wall, so they are only out of
+    /// the image without a margin
+    const fn far_overlap(&self) -> usize {
+        (self.margin == 0) as usize
+    }
+
     const fn cell_width(&self) -> usize {
         self.wall_width * 2 + self.passage_width
     }
@@ -69,8 +1008,8 @@ impl Image {
         let maze_width = self.cell_width() * grid.width() - (grid.width() - 1) * self.wall_width;
         let maze_height = self.cell_width() * grid.height() - (grid.height() - 1) * self.wall_width;
 
-        let image_width = maze_width + self.margin * 2;
-        let image_height = maze_height + self.margin * 2;
+        let image_width = maze_width + self.inset() * 2 + self.far_overlap();
+        let image_height = maze_height + self.inset() * 2 + self.far_overlap();
 
         (image_width, image_height)
     }
@@ -83,32 +1022,510 @@ impl Image {
         }
     }
 
+    fn tint_cells(&self, image: &mut RgbImage, grid: &Grid) {
+        let Some((min, max)) = self.value_range(grid) else {
+            return;
+        };
+        let range = max - min;
+
+        let step = self.cell_width() - self.wall_width;
+        let (width, height) = (image.width() as usize, image.height() as usize);
+        for (&(x, y), &value) in self.finite_values(grid) {
+            let t = if range > 0.0 {
+                (value - min) / range
+            } else {
+                0.0
+            };
+            let Color::RGB(r, g, b) = self.gradient.0.lerp(self.gradient.1, t);
+
+            // Walls are drawn over the edges of passages afterwards
+            let start_x = x * step + self.inset() + self.wall_width;
+            let start_y = y * step + self.inset() + self.wall_width;
+            for py in start_y..=(start_y + self.passage_width).min(height - 1) {
+                for px in start_x..=(start_x + self.passage_width).min(width - 1) {
+                    *image.get_pixel_mut(px as u32, py as u32) = image::Rgb([r, g, b]);
+                }
+            }
+        }
+    }
+
+    /// Returns the finite cell values of the cells within a given grid
+    fn finite_values<'a>(&'a self, grid: &Grid) -> impl Iterator<Item = (&'a Coords, &'a f64)> {
+        let (width, height) = (grid.width(), grid.height());
+        self.cell_values
+            .iter()
+            .flatten()
+            .filter(move |(&(x, y), value)| x < width && y < height && value.is_finite())
+    }
+
+    /// Returns the lowest and the highest finite cell values of the cells within a given grid, if
+    /// there are any
+    fn value_range(&self, grid: &Grid) -> Option<(f64, f64)> {
+        self.finite_values(grid)
+            .fold(None, |range, (_, &value)| match range {
+                Some((min, max)) => Some((value.min(min), value.max(max))),
+                None => Some((value, value)),
+            })
+    }
+
+    /// Draws a legend of the cell values into the bottom margin, unless it doesn't fit there
+    fn draw_legend(&self, image: &mut RgbImage, grid: &Grid) {
+        if !self.legend || self.thumbnail.is_some() {
+            return;
+        }
+        let Some((min, max)) = self.value_range(grid) else {
+            return;
+        };
+
+        // A glyph pixel takes a tenth of the margin, which leaves a glyph pixel and a half above
+        // and under the 5 glyph pixels tall legend
+        let inset = self.inset();
+        let scale = inset / 10;
+        let (low, high) = (legend_label(min), legend_label(max));
+        let label_width = |label: &str| (label.len() * 4 - 1) * scale;
+        let scale_width = LEGEND_SCALE_WIDTH * scale;
+        let legend_width = label_width(&low) + scale_width + label_width(&high) + 4 * scale;
+        let maze_width = image.width() as usize - inset * 2;
+        if scale == 0 || legend_width > maze_width {
+            return;
+        }
+
+        let top = image.height() as usize - inset + (inset - 5 * scale) / 2;
+        let mut left = inset;
+        left = self.draw_label(image, &low, (left, top), scale) + 2 * scale;
+        for x in 0..scale_width {
+            let t = x as f64 / (scale_width - 1) as f64;
+            let Color::RGB(r, g, b) = self.gradient.0.lerp(self.gradient.1, t);
+            for y in top..top + 5 * scale {
+                *image.get_pixel_mut((left + x) as u32, y as u32) = image::Rgb([r, g, b]);
+            }
+        }
+        left += scale_width + 2 * scale;
+        self.draw_label(image, &high, (left, top), scale);
+    }
+
+    /// Draws a label with its top left corner at given coords in glyph pixels of a given size, and
+    /// returns the x coordinate right after the label
+    fn draw_label(
+        &self,
+        image: &mut RgbImage,
+        label: &str,
+        (left, top): Coords,
+        scale: usize,
+    ) -> usize {
+        let Color::RGB(r, g, b) = self.foreground_color;
+        let mut x0 = left;
+        for c in label.chars() {
+            let rows = GLYPHS
+                .iter()
+                .find(|(glyph, _)| *glyph == c)
+                .map_or([0; 5], |(_, rows)| *rows);
+            for (row, bits) in rows.iter().enumerate() {
+                for column in (0..3).filter(|column| bits & (0b100 >> column) != 0) {
+                    for y in top + row * scale..top + (row + 1) * scale {
+                        for x in x0 + column * scale..x0 + (column + 1) * scale {
+                            *image.get_pixel_mut(x as u32, y as u32) = image::Rgb([r, g, b]);
+                        }
+                    }
+                }
+            }
+            x0 += 4 * scale;
+        }
+        x0 - scale
+    }
+
+    fn fill_passages(&self, image: &mut RgbImage) {
+        let Some(Color::RGB(r, g, b)) = self.passage_color else {
+            return;
+        };
+
+        // Walls are drawn over the floor afterwards, so the whole maze is filled at once
+        let (width, height) = (image.width() as usize, image.height() as usize);
+        for y in self.inset()..height - self.inset() {
+            for x in self.inset()..width - self.inset() {
+                *image.get_pixel_mut(x as u32, y as u32) = image::Rgb([r, g, b]);
+            }
+        }
+    }
+
+    /// Cuts off the outer corners of joints of two perpendicular walls, restoring the pixels
+    /// rendered under the walls
+    fn bevel_corners(&self, image: &mut RgbImage, floor: &RgbImage, grid: &Grid) {
+        let step = self.wall_width + self.passage_width;
+        let (width, height) = (image.width() as usize, image.height() as usize);
+
+        for j in 0..=grid.height() {
+            for i in 0..=grid.width() {
+                let north = j > 0 && has_vertical_wall(grid, i, j - 1);
+                let south = j < grid.height() && has_vertical_wall(grid, i, j);
+                let west = i > 0 && has_horizontal_wall(grid, i - 1, j);
+                let east = i < grid.width() && has_horizontal_wall(grid, i, j);
+                if north == south || west == east {
+                    continue;
+                }
+
+                // Offsets of pixels are measured from the corner with no walls attached
+                let (x0, y0) = (self.inset() + i * step, self.inset() + j * step);
+                for dy in 0..=self.wall_width {
+                    for dx in 0..self.wall_width - dy {
+                        let x = if west {
+                            x0 + self.wall_width - dx
+                        } else {
+                            x0 + dx
+                        };
+                        let y = if north {
+                            y0 + self.wall_width - dy
+                        } else {
+                            y0 + dy
+                        };
+                        if x < width && y < height {
+                            image.put_pixel(
+                                x as u32,
+                                y as u32,
+                                *floor.get_pixel(x as u32, y as u32),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Rounds the two corners of the passage of every dead end at its closed end, filling the
+    /// pixels out of the rounded corners like the wall closing the dead end
+    fn round_dead_end_caps(&self, image: &mut RgbImage, grid: &Grid) {
+        if self.dead_end_radius == 0 || self.wall_width == 0 {
+            return;
+        }
+
+        let step = self.wall_width + self.passage_width;
+        for y in 0..grid.height() {
+            for x in 0..grid.width() {
+                let cell = grid[(x, y)];
+                if cell.passage_count() != 1 {
+                    continue;
+                }
+                let Some(cap) = Pole::all()
+                    .into_iter()
+                    .find(|pole| cell.contains(Cell::from(pole.opposite())))
+                else {
+                    continue;
+                };
+
+                // The passage of a cell, where the inner walls before it are a pixel wider, and the
+                // outer walls on the far sides are drawn a pixel further
+                let (_, inward) = self.wall_growth((x, y));
+                let left = x * step + self.inset() + self.wall_width + inward + (x > 0) as usize;
+                let top = y * step + self.inset() + self.wall_width + inward + (y > 0) as usize;
+                let right = (x * step + self.inset() + step - 1 + (x + 1 == grid.width()) as usize)
+                    .saturating_sub(inward);
+                let bottom = (y * step + self.inset() + step - 1
+                    + (y + 1 == grid.height()) as usize)
+                    .saturating_sub(inward);
+                if right < left || bottom < top {
+                    continue;
+                }
+                let (columns, rows) = (right - left + 1, bottom - top + 1);
+                let radius = self.dead_end_radius.min(columns / 2).min(rows / 2);
+
+                // Corners touching the closed end as pairs of whether they are on the western and
+                // the northern side
+                let corners = match cap {
+                    Pole::N => [(true, true), (false, true)],
+                    Pole::S => [(true, false), (false, false)],
+                    Pole::W => [(true, true), (true, false)],
+                    Pole::E => [(false, true), (false, false)],
+                };
+                for (west, north) in corners {
+                    for dy in 0..radius {
+                        for dx in 0..radius {
+                            let (cx, cy) = (
+                                radius as f64 - dx as f64 - 0.5,
+                                radius as f64 - dy as f64 - 0.5,
+                            );
+                            if cx * cx + cy * cy <= (radius * radius) as f64 {
+                                continue;
+                            }
+
+                            let px = if west { left + dx } else { right - dx };
+                            let py = if north { top + dy } else { bottom - dy };
+                            // The pixel of the closing wall right next to the passage
+                            let (wx, wy) = match cap {
+                                Pole::N => (px, top - 1),
+                                Pole::S => (px, bottom + 1),
+                                Pole::W => (left - 1, py),
+                                Pole::E => (right + 1, py),
+                            };
+                            let color = *image.get_pixel(wx as u32, wy as u32);
+                            image.put_pixel(px as u32, py as u32, color);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Draws a line between the centers of every two cells connected with a passage, and from the
+    /// center of a cell to the edge of the maze through every opening
+    fn draw_passage_lines(&self, image: &mut RgbImage, grid: &Grid) {
+        let Some((thickness, Color::RGB(r, g, b))) = self.passage_lines else {
+            return;
+        };
+
+        let step = self.wall_width + self.passage_width;
+        // The first pixel of a line going across the centers of cells in a given row or column
+        let start = |line: usize| {
+            (self.inset() + line * step + self.wall_width + self.passage_width / 2)
+                .saturating_sub(thickness / 2)
+        };
+        let right = self.inset() + grid.width() * step + self.wall_width;
+        let bottom = self.inset() + grid.height() * step + self.wall_width;
+        let (width, height) = (image.width() as usize, image.height() as usize);
+
+        for y in 0..grid.height() {
+            for x in 0..grid.width() {
+                let (sx, sy) = (start(x), start(y));
+                for direction in Pole::all().map(Cell::from) {
+                    if !grid.is_carved((x, y), direction) {
+                        continue;
+                    }
+
+                    // Lines between cells are drawn from the western or the northern cell only
+                    let (x0, y0, x1, y1) = match grid.get_next_cell_coords((x, y), direction) {
+                        Ok((nx, ny)) if direction == Cell::EAST || direction == Cell::SOUTH => {
+                            (sx, sy, start(nx) + thickness, start(ny) + thickness)
+                        }
+                        Ok(_) => continue,
+                        Err(_) => match direction {
+                            Cell::NORTH => (sx, self.inset(), sx + thickness, sy + thickness),
+                            Cell::SOUTH => (sx, sy, sx + thickness, bottom),
+                            Cell::WEST => (self.inset(), sy, sx + thickness, sy + thickness),
+                            _ => (sx, sy, right, sy + thickness),
+                        },
+                    };
+
+                    for py in y0..y1.min(height) {
+                        for px in x0..x1.min(width) {
+                            *image.get_pixel_mut(px as u32, py as u32) = image::Rgb([r, g, b]);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Fills everything inside the maze out of the visible cells and their walls with the fog color
+    fn draw_fog(&self, image: &mut RgbImage, grid: &Grid) {
+        let Some(visible_cells) = &self.visible_cells else {
+            return;
+        };
+
+        let (columns, rows) = (grid.width(), grid.height());
+        let mut visible = vec![false; columns * rows];
+        for &(x, y) in visible_cells {
+            if x < columns && y < rows {
+                visible[y * columns + x] = true;
+            }
+        }
+
+        // Cells with their walls covering a pixel at a given offset from the maze along a row or a
+        // column, since the walls between two cells belong to both of them
+        let step = self.wall_width + self.passage_width;
+        let cells = |offset: usize, count: usize| {
+            let next = offset / step;
+            let first = if offset % step <= self.wall_width {
+                next.saturating_sub(1)
+            } else {
+                next
+            };
+            first..=next.min(count - 1)
+        };
+
+        // Cells overlap the next wall by a pixel, like the walls are drawn
+        let (width, height) = (image.width() as usize, image.height() as usize);
+        let right = (self.inset() + columns * step + self.wall_width + 1).min(width);
+        let bottom = (self.inset() + rows * step + self.wall_width + 1).min(height);
+        let Color::RGB(r, g, b) = self.fog_color;
+
+        for py in self.inset()..bottom {
+            let ys = cells(py - self.inset(), rows);
+            for px in self.inset()..right {
+                let lit = cells(px - self.inset(), columns)
+                    .any(|x| ys.clone().any(|y| visible[y * columns + x]));
+                if !lit {
+                    *image.get_pixel_mut(px as u32, py as u32) = image::Rgb([r, g, b]);
+                }
+            }
+        }
+    }
+
+    /// Blends the shadow color over the pixels covered by the walls shifted by the shadow offset
+    fn draw_shadow(&self, image: &mut RgbImage, grid: &Grid) {
+        let Some(((dx, dy), color, opacity)) = self.shadow else {
+            return;
+        };
+        let opacity = if opacity.is_nan() {
+            0.0
+        } else {
+            opacity.clamp(0.0, 1.0)
+        };
+
+        // Walls are drawn in white over a black image, which keeps only the shape of the walls
+        let mut walls: RgbImage = ImageBuffer::new(image.width(), image.height());
+        let mask = Image {
+            foreground_color: Color::RGB(255, 255, 255),
+            wall_colors: None,
+            ..self.clone()
+        };
+        let floor = (self.corner_style == CornerStyle::Bevel).then(|| walls.clone());
+        mask.draw_maze(&mut walls, grid);
+        if let Some(floor) = floor {
+            mask.bevel_corners(&mut walls, &floor, grid);
+        }
+
+        let (width, height) = (image.width() as i64, image.height() as i64);
+        for (x, y, pixel) in walls.enumerate_pixels() {
+            let (sx, sy) = (x as i64 + dx as i64, y as i64 + dy as i64);
+            if pixel.0 == [0, 0, 0] || !(0..width).contains(&sx) || !(0..height).contains(&sy) {
+                continue;
+            }
+
+            let target = image.get_pixel_mut(sx as u32, sy as u32);
+            let [r, g, b] = target.0;
+            let Color::RGB(r, g, b) = Color::RGB(r, g, b).lerp(color, opacity);
+            *target = image::Rgb([r, g, b]);
+        }
+    }
+
+    /// Alpha-blends the watermark logo into its corner of the image, clipping the logo by the edges
+    fn stamp_watermark(&self, image: &mut RgbImage) {
+        let Some((logo, corner, padding, opacity)) = &self.watermark else {
+            return;
+        };
+        let opacity = if opacity.is_nan() {
+            0.0
+        } else {
+            opacity.clamp(0.0, 1.0)
+        };
+
+        // The top left pixel of the logo, which may be out of the image for the far corners
+        let (width, height) = (image.width() as i64, image.height() as i64);
+        let (padding, logo_width, logo_height) =
+            (*padding as i64, logo.width() as i64, logo.height() as i64);
+        let (left, top) = match corner {
+            Corner::TopLeft => (padding, padding),
+            Corner::TopRight => (width - padding - logo_width, padding),
+            Corner::BottomLeft => (padding, height - padding - logo_height),
+            Corner::BottomRight => (width - padding - logo_width, height - padding - logo_height),
+        };
+
+        for (x, y, pixel) in logo.enumerate_pixels() {
+            let (ix, iy) = (left + x as i64, top + y as i64);
+            let inside = (padding..width - padding).contains(&ix)
+                && (padding..height - padding).contains(&iy);
+            let [r, g, b, a] = pixel.0;
+            if !inside || a == 0 {
+                continue;
+            }
+
+            let target = image.get_pixel_mut(ix as u32, iy as u32);
+            let [tr, tg, tb] = target.0;
+            let alpha = a as f64 / 255.0 * opacity;
+            let Color::RGB(r, g, b) = Color::RGB(tr, tg, tb).lerp(Color::RGB(r, g, b), alpha);
+            *target = image::Rgb([r, g, b]);
+        }
+    }
+
+    /// Draws walls of a maze, splitting the image into a band per thread with the `rayon` feature
     fn draw_maze(&self, image: &mut RgbImage, grid: &Grid) {
+        #[cfg(feature = "rayon")]
+        let rows = (image.height() as usize).div_ceil(rayon::current_num_threads());
+        #[cfg(not(feature = "rayon"))]
+        let rows = image.height() as usize;
+
+        self.draw_bands(image, grid, rows);
+    }
+
+    /// Draws walls of a maze in bands of a given number of rows, in parallel with the `rayon`
+    /// feature
+    fn draw_bands(&self, image: &mut RgbImage, grid: &Grid, rows: usize) {
+        let width = image.width() as usize;
+        let chunk = (rows * width * 3).max(1);
+        let draw = |(i, pixels): (usize, &mut [u8])| {
+            let mut band = Band {
+                pixels,
+                width,
+                top: i * rows,
+            };
+            self.draw_band(&mut band, grid);
+        };
+
+        #[cfg(feature = "rayon")]
+        {
+            use rayon::prelude::*;
+            image.par_chunks_mut(chunk).enumerate().for_each(draw);
+        }
+        #[cfg(not(feature = "rayon"))]
+        image.chunks_mut(chunk).enumerate().for_each(draw);
+    }
+
+    /// Draws the cells overlapping a given band
+    fn draw_band(&self, band: &mut Band, grid: &Grid) {
+        let step = self.cell_width() - self.wall_width;
+        let bottom = band.top + band.rows();
+
         for y in 0..grid.height() {
+            // Thicker walls may reach out of the cell
+            let start_y = (y * step + self.inset()).saturating_sub(self.thickest_wall);
+            if start_y >= bottom || start_y + self.cell_width() + self.thickest_wall * 2 < band.top
+            {
+                continue;
+            }
             for x in 0..grid.width() {
-                self.draw_cell((x, y), grid, image);
+                self.draw_cell((x, y), grid, band);
             }
         }
     }
 
-    fn draw_cell(&self, coords: Coords, grid: &Grid, image: &mut RgbImage) {
+    fn draw_cell(&self, coords: Coords, grid: &Grid, band: &mut Band) {
         let (x, y) = coords;
         let cell_width_without_joint_wall = self.cell_width() - self.wall_width;
-        let start_x = x * cell_width_without_joint_wall + self.margin;
-        let start_y = y * cell_width_without_joint_wall + self.margin;
+        let start_x = x * cell_width_without_joint_wall + self.inset();
+        let start_y = y * cell_width_without_joint_wall + self.inset();
+
+        // Cells overlap the next wall by a pixel, and the outer walls on the far sides overlap the
+        // margin, which the image grows by without a margin
+        let east = start_x + cell_width_without_joint_wall;
+        let south = start_y + cell_width_without_joint_wall;
+
+        // Thicker walls grow out of the cell as well as into its passage
+        let (outward, inward) = self.wall_growth(coords);
+        let (west, north) = (
+            start_x.saturating_sub(outward),
+            start_y.saturating_sub(outward),
+        );
+        let inner_west = start_x + self.wall_width + inward;
+        let inner_north = start_y + self.wall_width + inward;
+        let (inner_east, inner_south) = (east.saturating_sub(inward), south.saturating_sub(inward));
+        let right = east + self.wall_width + outward;
+        let bottom = south + self.wall_width + outward;
 
-        for y in start_y..=start_y + self.cell_width() {
-            for x in start_x..=start_x + self.cell_width() {
+        // Only the rows of the band are drawn
+        let end_x = right.min(band.width - 1);
+        let end_y = bottom.min(band.top + band.rows() - 1);
+
+        for y in north.max(band.top)..=end_y {
+            for x in west..=end_x {
                 // A cell consists of two main zones: its walls and some empty space between them
                 // called "a passage". To draw a cell, the following code checks some particular
                 // zones and skips filling pixels with color in case a wall should not display or
                 // it's a cell passage. In all other cases, we fill pixels with a given color
 
                 // Top left corner must display only if either Northern or Western wall exists
-                if x >= start_x
-                    && x <= start_x + self.wall_width
-                    && y >= start_y
-                    && y <= start_y + self.wall_width
+                if x >= west
+                    && x <= inner_west
+                    && y >= north
+                    && y <= inner_north
                     && grid.is_carved(coords, Cell::NORTH)
                     && grid.is_carved(coords, Cell::WEST)
                 {
@@ -116,20 +1533,20 @@ impl Image {
                 }
 
                 // Northern wall must display only if there is no passage carved to North
-                if x >= start_x + self.wall_width
-                    && x <= start_x + cell_width_without_joint_wall
-                    && y >= start_y
-                    && y <= start_y + self.wall_width
+                if x >= inner_west
+                    && x <= inner_east
+                    && y >= north
+                    && y <= inner_north
                     && grid.is_carved(coords, Cell::NORTH)
                 {
                     continue;
                 }
 
                 // Top right corner must display only if either Northern or Eastern wall exists
-                if x >= start_x + cell_width_without_joint_wall
-                    && x <= start_x + self.cell_width()
-                    && y >= start_y
-                    && y <= start_y + self.wall_width
+                if x >= inner_east
+                    && x <= right
+                    && y >= north
+                    && y <= inner_north
                     && grid.is_carved(coords, Cell::NORTH)
                     && grid.is_carved(coords, Cell::EAST)
                 {
@@ -137,39 +1554,35 @@ impl Image {
                 }
 
                 // Western wall must display only if there is no passage carved to West
-                if x >= start_x
-                    && x <= start_x + self.wall_width
-                    && y >= start_y + self.wall_width
-                    && y <= start_y + cell_width_without_joint_wall
+                if x >= west
+                    && x <= inner_west
+                    && y >= inner_north
+                    && y <= inner_south
                     && grid.is_carved(coords, Cell::WEST)
                 {
                     continue;
                 }
 
-                // Cell's passage must not be colored, i.e. it remains same as an image background
-                if x >= start_x + self.wall_width
-                    && x <= start_x + cell_width_without_joint_wall
-                    && y >= start_y + self.wall_width
-                    && y <= start_y + cell_width_without_joint_wall
-                {
+                // Cell's passage must not be colored, i.e. it remains same as the passage color
+                if x >= inner_west && x <= inner_east && y >= inner_north && y <= inner_south {
                     continue;
                 }
 
                 // Eastern wall must display only if there is no passage carved to East
-                if x >= start_x + cell_width_without_joint_wall
-                    && x <= start_x + self.cell_width()
-                    && y >= start_y + self.wall_width
-                    && y <= start_y + cell_width_without_joint_wall
+                if x >= inner_east
+                    && x <= right
+                    && y >= inner_north
+                    && y <= inner_south
                     && grid.is_carved(coords, Cell::EAST)
                 {
                     continue;
                 }
 
                 // Bottom left corner must display only if either Southern or Western wall exists
-                if x >= start_x
-                    && x <= start_x + self.wall_width
-                    && y >= start_y + cell_width_without_joint_wall
-                    && y <= start_y + self.cell_width()
+                if x >= west
+                    && x <= inner_west
+                    && y >= inner_south
+                    && y <= bottom
                     && grid.is_carved(coords, Cell::SOUTH)
                     && grid.is_carved(coords, Cell::WEST)
                 {
@@ -177,30 +1590,50 @@ impl Image {
                 }
 
                 // Southern wall must display only if there is no passage carved to South
-                if x >= start_x + self.wall_width
-                    && x <= start_x + cell_width_without_joint_wall
-                    && y >= start_y + cell_width_without_joint_wall
-                    && y <= start_y + self.cell_width()
+                if x >= inner_west
+                    && x <= inner_east
+                    && y >= inner_south
+                    && y <= bottom
                     && grid.is_carved(coords, Cell::SOUTH)
                 {
                     continue;
                 }
 
                 // Bottom right corner must display only if either Southern or Eastern wall exists
-                if x >= start_x + cell_width_without_joint_wall
-                    && x <= start_x + self.cell_width()
-                    && y >= start_y + cell_width_without_joint_wall
-                    && y <= start_y + self.cell_width()
+                if x >= inner_east
+                    && x <= right
+                    && y >= inner_south
+                    && y <= bottom
                     && grid.is_carved(coords, Cell::SOUTH)
                     && grid.is_carved(coords, Cell::EAST)
                 {
                     continue;
                 }
 
-                // Fill the remaining pixels with a given color
-                *image.get_pixel_mut(x as u32, y as u32) = match self.foreground_color {
-                    Color::RGB(r, g, b) => image::Rgb([r, g, b]),
-                }
+                // Fill the remaining pixels with a given color, where the joints in the corners
+                // belong to the horizontal walls
+                let horizontal = y <= inner_north || y >= inner_south;
+                let color = match self.wall_gradient {
+                    Some((upper, lower)) => {
+                        // How far the pixel is across the thickness of its wall, where the walls
+                        // on the far sides of the cell are measured back from their outer edges
+                        let thickness = inner_north - north;
+                        let (at, start) = match (horizontal, y <= inner_north, x <= inner_west) {
+                            (true, true, _) => (y, north),
+                            (true, false, _) => (y, (bottom + 1).saturating_sub(thickness)),
+                            (false, _, true) => (x, west),
+                            (false, _, false) => (x, (right + 1).saturating_sub(thickness)),
+                        };
+                        let t = if thickness > 1 {
+                            (at.saturating_sub(start) as f64 / (thickness - 1) as f64).min(1.0)
+                        } else {
+                            0.0
+                        };
+                        upper.lerp(lower, t)
+                    }
+                    None => self.wall_color(horizontal),
+                };
+                band.put_pixel(x, y, color);
             }
         }
     }
@@ -215,17 +1648,97 @@ impl Default for Image {
 /// An implementation of a formatter
 impl Formatter<ImageWrapper> for Image {
     /// Converts a given grid into an image and returns an [ImageWrapper] over that image
+    ///
+    /// The image is rendered regardless of the [maximum number of pixels](Image::max_pixels), which
+    /// only [try_format](Image::try_format) checks.
     fn format(&self, grid: &Grid) -> ImageWrapper {
+        self.render(grid)
+    }
+
+    /// Converts a given grid into an image, or returns a [FormatError] if the image would be larger
+    /// than the [maximum number of pixels](Image::max_pixels), see [try_format](Image::try_format)
+    fn try_format(&self, grid: &Grid) -> Result<ImageWrapper, FormatError> {
+        Image::try_format(self, grid)
+    }
+}
+
+impl Image {
+    fn render(&self, grid: &Grid) -> ImageWrapper {
+        let mut image = self.render_layers(grid);
+        // The legend is drawn after downscaling an anti-aliased image, so that its glyphs stay
+        // sharp
+        self.draw_legend(&mut image.0, grid);
+        self.stamp_watermark(&mut image.0);
+        image
+    }
+
+    fn render_layers(&self, grid: &Grid) -> ImageWrapper {
+        if let Some(pitch) = self.thumbnail_pitch() {
+            return self.render_thumbnail(grid, pitch);
+        }
+
         let (width, height) = self.sizes(grid);
+
+        if self.antialias {
+            let supersampled = Image {
+                wall_width: self.wall_width * SUPERSAMPLING,
+                wall_widths: self.supersampled_wall_widths(),
+                thickest_wall: self.thickest_wall * SUPERSAMPLING,
+                passage_width: self.passage_width * SUPERSAMPLING,
+                margin: self.margin * SUPERSAMPLING,
+                dead_end_radius: self.dead_end_radius * SUPERSAMPLING,
+                passage_lines: self
+                    .passage_lines
+                    .map(|(thickness, color)| (thickness * SUPERSAMPLING, color)),
+                shadow: self.shadow.map(|((dx, dy), color, opacity)| {
+                    let scale = SUPERSAMPLING as i32;
+                    ((dx * scale, dy * scale), color, opacity)
+                }),
+                antialias: false,
+                watermark: None,
+                ..self.clone()
+            }
+            .render_layers(grid)
+            .0;
+
+            return ImageWrapper(imageops::resize(
+                &supersampled,
+                width as u32,
+                height as u32,
+                imageops::FilterType::Triangle,
+            ));
+        }
+
         let mut image: RgbImage = ImageBuffer::new(width as u32, height as u32);
 
         self.fill_background(&mut image);
-        self.draw_maze(&mut image, grid);
+        self.fill_passages(&mut image);
+        self.tint_cells(&mut image, grid);
+        if !self.hide_walls {
+            self.draw_shadow(&mut image, grid);
+            let floor = (self.corner_style == CornerStyle::Bevel).then(|| image.clone());
+            self.draw_maze(&mut image, grid);
+            if let Some(floor) = floor {
+                self.bevel_corners(&mut image, &floor, grid);
+            }
+            self.round_dead_end_caps(&mut image, grid);
+        }
+        self.draw_passage_lines(&mut image, grid);
+        self.draw_fog(&mut image, grid);
 
         ImageWrapper(image)
     }
 }
 
+/// Returns a label of a legend value rounded to two decimals, without trailing zeros
+fn legend_label(value: f64) -> String {
+    let label = format!("{value:.2}");
+    match label.trim_end_matches('0').trim_end_matches('.') {
+        "-0" => "0".to_string(),
+        label => label.to_string(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use image::EncodableLayout;
@@ -238,10 +1751,28 @@ mod tests {
     fn new_call_default_params() {
         let image = Image::new();
         assert_eq!(40, image.wall_width);
+        assert_eq!(None, image.wall_widths);
         assert_eq!(40, image.passage_width);
         assert_eq!(Color::RGB(250, 250, 250), image.background_color);
         assert_eq!(Color::RGB(0, 0, 0), image.foreground_color);
+        assert_eq!(None, image.wall_colors);
+        assert_eq!(None, image.wall_gradient);
         assert_eq!(50, image.margin);
+        assert!(!image.legend);
+        assert_eq!(None, image.passage_color);
+        assert_eq!(None, image.cell_values);
+        assert_eq!(DEFAULT_GRADIENT, image.gradient);
+        assert_eq!(CornerStyle::Square, image.corner_style);
+        assert_eq!(0, image.dead_end_radius);
+        assert_eq!(None, image.passage_lines);
+        assert!(!image.hide_walls);
+        assert_eq!(None, image.shadow);
+        assert_eq!(None, image.visible_cells);
+        assert_eq!(DEFAULT_FOG_COLOR, image.fog_color);
+        assert!(!image.antialias);
+        assert_eq!(None, image.thumbnail);
+        assert!(image.watermark.is_none());
+        assert_eq!(100_000_000, image.max_pixels);
     }
 
     #[test]
@@ -251,12 +1782,16 @@ mod tests {
             .passage(5)
             .background(Color::RGB(1, 1, 1))
             .foreground(Color::RGB(100, 100, 100))
-            .margin(20);
+            .passage_color(Color::RGB(50, 50, 50))
+            .margin(20)
+            .antialias(true);
 
+        assert!(image.antialias);
         assert_eq!(10, image.wall_width);
         assert_eq!(5, image.passage_width);
         assert_eq!(Color::RGB(1, 1, 1), image.background_color);
         assert_eq!(Color::RGB(100, 100, 100), image.foreground_color);
+        assert_eq!(Some(Color::RGB(50, 50, 50)), image.passage_color);
         assert_eq!(20, image.margin);
     }
 
@@ -271,6 +1806,834 @@ mod tests {
         assert_eq!(actual.as_bytes(), expected.as_bytes());
     }
 
+    #[test]
+    fn format_heatmap() {
+        let formatter = Image::new().wall(3).passage(9).margin(4);
+        let grid = generate_maze();
+        let distances = HashMap::from([((0, 0), 0), ((1, 0), 5), ((2, 0), 10), ((9, 9), 100)]);
+
+        let image = formatter.heatmap(distances).format(&grid).0;
+
+        // The middle of the `n`-th passage is at 4 + 3 + 12 * n + 4
+        assert_eq!(image.get_pixel(11, 11).0, [49, 54, 149]);
+        assert_eq!(image.get_pixel(23, 11).0, [132, 51, 94]);
+        assert_eq!(image.get_pixel(35, 11).0, [215, 48, 39]);
+        assert_eq!(image.get_pixel(47, 11).0, [250, 250, 250]);
+        // Walls are drawn over the tinted passages
+        assert_eq!(image.get_pixel(5, 5).0, [0, 0, 0]);
+    }
+
+    #[test]
+    fn format_legend() {
+        let formatter = || Image::new().wall(2).passage(22).margin(20).legend(true);
+        let grid = generate_maze();
+        let distances = HashMap::from([((0, 0), 0), ((1, 0), 3), ((2, 0), 10)]);
+
+        let plain = formatter().legend(false).heatmap(distances.clone());
+        let plain = plain.format(&grid).0;
+        let image = formatter().heatmap(distances).format(&grid).0;
+
+        // Glyph pixels are 2 px wide, and the legend is 10 px tall in the middle of the bottom
+        // margin, with the color scale between the `0` label and the `10` label at 30..70
+        let top = image.height() - 15;
+        let (low, high) = ([49, 54, 149], [215, 48, 39]);
+        for (x, y, color) in [
+            (20, top, [0, 0, 0]),
+            (22, top + 4, [250, 250, 250]),
+            (30, top + 2, low),
+            (69, top + 9, high),
+            (76, top, [0, 0, 0]),
+            (87, top + 9, [0, 0, 0]),
+        ] {
+            assert_eq!(image.get_pixel(x, y).0, color, "{x}, {y}");
+        }
+        for (x, y, pixel) in image.enumerate_pixels() {
+            if !(20..88).contains(&x) || !(top..top + 10).contains(&y) {
+                assert_eq!(pixel, plain.get_pixel(x, y), "{x}, {y}");
+            }
+        }
+    }
+
+    #[test]
+    fn skip_legend() {
+        let grid = generate_maze();
+        let distances = HashMap::from([((0, 0), 1), ((1, 0), 5)]);
+        let plain = Image::new().wall(2).passage(22).margin(20);
+
+        for formatter in [
+            plain.clone(),
+            // No distances within the grid
+            plain.clone().heatmap(HashMap::from([((9, 9), 1)])),
+            plain.clone().margin(9).heatmap(distances.clone()),
+            // The labels take 54 px and 6 px, which is too wide for 98 px along with the scale
+            plain
+                .clone()
+                .heatmap(HashMap::from([((0, 0), 1), ((1, 0), 1234567)])),
+            plain.clone().thumbnail(2).heatmap(distances.clone()),
+        ] {
+            assert_eq!(
+                formatter.clone().legend(true).format(&grid).0,
+                formatter.format(&grid).0
+            );
+        }
+
+        assert_ne!(
+            plain
+                .clone()
+                .heatmap(distances.clone())
+                .legend(true)
+                .format(&grid)
+                .0,
+            plain.heatmap(distances).format(&grid).0
+        );
+    }
+
+    #[test]
+    fn legend_labels() {
+        assert_eq!(legend_label(0.0), "0");
+        assert_eq!(legend_label(-0.001), "0");
+        assert_eq!(legend_label(10.0), "10");
+        assert_eq!(legend_label(-1.5), "-1.5");
+        assert_eq!(legend_label(2.0 / 3.0), "0.67");
+    }
+
+    #[test]
+    fn format_passage_color() {
+        let formatter = || Image::new().wall(3).passage(9).margin(4);
+        let grid = generate_maze();
+
+        let floored = formatter()
+            .passage_color(Color::RGB(200, 180, 120))
+            .format(&grid)
+            .0;
+
+        assert_eq!(floored.get_pixel(0, 0).0, [250, 250, 250]);
+        assert_eq!(floored.get_pixel(5, 5).0, [0, 0, 0]);
+        assert_eq!(floored.get_pixel(12, 12).0, [200, 180, 120]);
+        assert_eq!(
+            formatter()
+                .passage_color(Color::RGB(250, 250, 250))
+                .format(&grid)
+                .0,
+            formatter().format(&grid).0
+        );
+        assert_eq!(
+            formatter()
+                .passage_color(Color::RGB(200, 180, 120))
+                .parse(&floored)
+                .unwrap(),
+            grid
+        );
+    }
+
+    #[test]
+    fn format_cell_values() {
+        let formatter = || {
+            Image::new()
+                .wall(3)
+                .passage(9)
+                .margin(4)
+                .passage_color(Color::RGB(200, 180, 120))
+                .gradient(Color::RGB(0, 0, 0), Color::RGB(200, 100, 0))
+        };
+        let grid = generate_maze();
+        let values = HashMap::from([
+            ((0, 0), -1.0),
+            ((1, 0), 0.0),
+            ((2, 0), 1.0),
+            ((3, 0), f64::NAN),
+            ((9, 9), 100.0),
+        ]);
+
+        let image = formatter().cell_values(values).format(&grid).0;
+
+        // The middle of the `n`-th passage is at 4 + 3 + 12 * n + 4
+        assert_eq!(image.get_pixel(11, 11).0, [0, 0, 0]);
+        assert_eq!(image.get_pixel(23, 11).0, [100, 50, 0]);
+        assert_eq!(image.get_pixel(35, 11).0, [200, 100, 0]);
+        assert_eq!(image.get_pixel(47, 11).0, [200, 180, 120]);
+        assert_eq!(image.get_pixel(11, 23).0, [200, 180, 120]);
+        // Walls are drawn over the tinted passages
+        assert_eq!(image.get_pixel(5, 5).0, [0, 0, 0]);
+        assert_eq!(image.get_pixel(5, 11).0, [0, 0, 0]);
+
+        let single = formatter()
+            .cell_values(HashMap::from([((1, 1), 3.0)]))
+            .format(&grid)
+            .0;
+        assert_eq!(single.get_pixel(23, 23).0, [0, 0, 0]);
+    }
+
+    #[test]
+    fn format_bevel_corners() {
+        let formatter = || Image::new().wall(4).passage(6).margin(2);
+        let mut grid = Grid::new(2, 1);
+        grid.carve_passage((0, 0), Cell::EAST).unwrap();
+
+        let square = formatter().format(&grid).0;
+        let bevel = formatter().corner_style(CornerStyle::Bevel).format(&grid).0;
+
+        // The outer corner of the top left joint is cut along the diagonal
+        assert_eq!(square.get_pixel(2, 2).0, [0, 0, 0]);
+        assert_eq!(bevel.get_pixel(2, 2).0, [250, 250, 250]);
+        assert_eq!(bevel.get_pixel(5, 2).0, [250, 250, 250]);
+        assert_eq!(bevel.get_pixel(6, 2).0, [0, 0, 0]);
+        assert_eq!(bevel.get_pixel(4, 4).0, [0, 0, 0]);
+        // So is the bottom right one, while the middle joints end straight walls
+        assert_eq!(bevel.get_pixel(26, 16).0, [250, 250, 250]);
+        assert_eq!(bevel.get_pixel(26, 12).0, [0, 0, 0]);
+        assert_eq!(bevel.get_pixel(12, 2).0, [0, 0, 0]);
+        assert_eq!(bevel.get_pixel(12, 14).0, [0, 0, 0]);
+
+        let changed = square
+            .pixels()
+            .zip(bevel.pixels())
+            .filter(|(square, bevel)| square != bevel)
+            .count();
+        // Four corners of 4 + 3 + 2 + 1 pixels
+        assert_eq!(changed, 4 * 10);
+    }
+
+    #[test]
+    fn format_round_dead_ends() {
+        let mut grid = Grid::new(3, 1);
+        grid.carve_passage((0, 0), Cell::EAST).unwrap();
+        grid.carve_passage((1, 0), Cell::EAST).unwrap();
+        let formatter = || Image::new().wall(4).passage(8).margin(0);
+
+        let square = formatter().format(&grid).0;
+        let rounded = formatter().round_dead_ends(4).format(&grid).0;
+        let color = |x, y| rounded.get_pixel(x, y).0;
+        let (wall, floor) = ([0, 0, 0], [250, 250, 250]);
+
+        // The western end of the first cell
+        assert_eq!(color(4, 4), wall);
+        assert_eq!(color(5, 4), wall);
+        assert_eq!(color(6, 4), floor);
+        assert_eq!(color(4, 11), wall);
+        assert_eq!(color(7, 7), floor);
+        assert_eq!(color(11, 4), floor);
+        // The eastern end of the last cell
+        assert_eq!(color(36, 4), wall);
+        assert_eq!(color(36, 11), wall);
+        assert_eq!(color(30, 4), floor);
+        // The corridor in between stays square
+        for y in 0..rounded.height() {
+            for x in 12..28 {
+                assert_eq!(
+                    rounded.get_pixel(x, y),
+                    square.get_pixel(x, y),
+                    "at ({x}, {y})"
+                );
+            }
+        }
+
+        // Without dead ends, nothing is rounded
+        let mut grid = Grid::new(2, 2);
+        grid.carve_passage((0, 0), Cell::EAST).unwrap();
+        grid.carve_passage((0, 0), Cell::SOUTH).unwrap();
+        grid.carve_passage((1, 1), Cell::NORTH).unwrap();
+        grid.carve_passage((1, 1), Cell::WEST).unwrap();
+        assert_eq!(
+            formatter().round_dead_ends(4).format(&grid).0,
+            formatter().format(&grid).0
+        );
+    }
+
+    #[test]
+    fn format_passage_lines() {
+        let formatter = || {
+            Image::new()
+                .wall(2)
+                .passage(8)
+                .margin(3)
+                .passage_lines(2, Color::RGB(0, 0, 200))
+        };
+        let mut grid = Grid::new(2, 2);
+        grid.carve_passage((0, 0), Cell::EAST).unwrap();
+        grid.carve_passage((1, 0), Cell::SOUTH).unwrap();
+        grid.carve_opening((0, 0), Cell::WEST).unwrap();
+
+        let image = formatter().format(&grid).0;
+
+        // Centers of cells are at 3 + 2 + 10 * n + 4, so lines take the pixel before them too
+        assert_eq!(image.get_pixel(8, 8).0, [0, 0, 200]);
+        assert_eq!(image.get_pixel(19, 9).0, [0, 0, 200]);
+        assert_eq!(image.get_pixel(19, 14).0, [0, 0, 200]);
+        assert_eq!(image.get_pixel(18, 19).0, [0, 0, 200]);
+        assert_eq!(image.get_pixel(18, 20).0, [250, 250, 250]);
+        assert_eq!(image.get_pixel(21, 21).0, [250, 250, 250]);
+        assert_eq!(image.get_pixel(9, 18).0, [250, 250, 250]);
+        assert_eq!(image.get_pixel(8, 7).0, [250, 250, 250]);
+        // Lines go over the walls, and through the opening up to the margin
+        assert_eq!(image.get_pixel(14, 8).0, [0, 0, 200]);
+        assert_eq!(image.get_pixel(3, 8).0, [0, 0, 200]);
+        assert_eq!(image.get_pixel(2, 8).0, [250, 250, 250]);
+        assert_eq!(image.get_pixel(4, 4).0, [0, 0, 0]);
+
+        let hidden = formatter().hide_walls(true).format(&grid).0;
+        assert_eq!(hidden.get_pixel(4, 4).0, [250, 250, 250]);
+        assert_eq!(hidden.get_pixel(19, 19).0, [0, 0, 200]);
+        assert!(hidden.pixels().all(|pixel| pixel.0 != [0, 0, 0]));
+    }
+
+    #[test]
+    fn render_solution_layer() {
+        let formatter = Image::new()
+            .wall(2)
+            .passage(8)
+            .margin(3)
+            .passage_lines(2, Color::RGB(0, 0, 200));
+        let mut grid = Grid::new(2, 2);
+        grid.carve_passage((0, 0), Cell::EAST).unwrap();
+        grid.carve_passage((1, 0), Cell::SOUTH).unwrap();
+        let solution = Solution::new(vec![(0, 0), (1, 0), (1, 1)]);
+
+        let layer = formatter.render_solution_layer(&grid, &solution);
+
+        assert_eq!(layer.dimensions(), formatter.format(&grid).0.dimensions());
+        // The line takes the same pixels as passage lines, and nothing else is opaque
+        for (x, y) in [(8, 8), (14, 8), (19, 9), (19, 14), (18, 19)] {
+            assert_eq!(layer.get_pixel(x, y).0, [0, 0, 200, 255]);
+        }
+        for (x, y) in [(8, 7), (18, 20), (9, 18), (4, 4)] {
+            assert_eq!(layer.get_pixel(x, y).0[3], 0);
+        }
+        assert_eq!(
+            layer.pixels().filter(|pixel| pixel.0[3] == 255).count(),
+            2 * 12 * 2 - 4
+        );
+
+        let default = Image::new().render_solution_layer(&grid, &solution);
+        assert_eq!(
+            default.get_pixel(50 + 40 + 20, 50 + 40 + 20).0,
+            [0, 0, 0, 255]
+        );
+        assert_eq!(default.get_pixel(50 + 40 + 20, 50 + 40 + 40 + 20).0[3], 0);
+    }
+
+    #[test]
+    fn render_solution_layer_variants() {
+        let mut grid = Grid::new(2, 2);
+        grid.carve_passage((0, 0), Cell::EAST).unwrap();
+        grid.carve_passage((1, 0), Cell::SOUTH).unwrap();
+        let solution = Solution::new(vec![(0, 0), (1, 0), (1, 1)]);
+
+        let thumbnail = Image::new()
+            .thumbnail(1)
+            .render_solution_layer(&grid, &solution);
+        assert_eq!(thumbnail.dimensions(), (5, 5));
+        let opaque: Vec<_> = thumbnail
+            .enumerate_pixels()
+            .filter(|(_, _, pixel)| pixel.0[3] == 255)
+            .map(|(x, y, _)| (x, y))
+            .collect();
+        assert_eq!(opaque, vec![(1, 1), (2, 1), (3, 1), (3, 2), (3, 3)]);
+
+        let antialiased = Image::new()
+            .antialias(true)
+            .render_solution_layer(&grid, &solution);
+        assert_eq!(antialiased.dimensions(), (300, 300));
+        assert!(antialiased
+            .pixels()
+            .any(|pixel| (1..255).contains(&pixel.0[3])));
+        assert!(antialiased.pixels().all(|pixel| pixel.0[..3] == [0, 0, 0]));
+
+        // Cells around a torus are joined through the edges of the maze, out of the margin
+        let mut torus = Grid::new_torus(3, 1);
+        torus.carve_passage((0, 0), Cell::WEST).unwrap();
+        let layer = Image::new()
+            .wall(2)
+            .passage(8)
+            .margin(3)
+            .render_solution_layer(&torus, &Solution::new(vec![(0, 0), (2, 0)]));
+        assert_eq!(layer.width(), 38);
+        for (x, opacity) in [
+            (2, 0),
+            (3, 255),
+            (12, 0),
+            (18, 0),
+            (28, 255),
+            (34, 255),
+            (35, 0),
+        ] {
+            assert_eq!(layer.get_pixel(x, 9).0[3], opacity, "at {x}");
+        }
+    }
+
+    #[test]
+    fn format_wall_widths() {
+        let grid = Grid::new(3, 1);
+        let bold = Image::new()
+            .wall(2)
+            .passage(8)
+            .margin(0)
+            .wall_widths(HashMap::from([((1, 0), 6), ((2, 0), 1)]))
+            .format(&grid)
+            .0;
+        // The same image with regular walls all over, shifted by the grown margin, while the outer
+        // walls on the far sides overlap the missing margin by a pixel
+        let regular = Image::new().wall(2).passage(8).margin(2).format(&grid).0;
+
+        assert_eq!(bold.dimensions(), (37, 17));
+        assert_eq!(regular.dimensions(), (36, 16));
+        // The western wall of the middle cell spans 6 px instead of 12..=14
+        for x in 9..18 {
+            let wall = (10..=15).contains(&x);
+            assert_eq!(bold.get_pixel(x, 8).0 == [0, 0, 0], wall, "at {x}");
+        }
+        assert_eq!(regular.get_pixel(10, 8).0, [250, 250, 250]);
+        // The northern wall reaches the edge of the image, while the regular walls keep the margin
+        assert_eq!(bold.get_pixel(20, 0).0, [0, 0, 0]);
+        assert_eq!(bold.get_pixel(5, 0).0, [250, 250, 250]);
+        // A thinner wall makes no difference
+        for x in 27..36 {
+            assert_eq!(bold.get_pixel(x, 8), regular.get_pixel(x, 8), "at {x}");
+        }
+
+        let antialiased = Image::new()
+            .wall(2)
+            .passage(8)
+            .margin(0)
+            .antialias(true)
+            .wall_widths(HashMap::from([((1, 0), 6)]))
+            .format(&grid)
+            .0;
+        assert_eq!(antialiased.get_pixel(11, 8).0, [0, 0, 0]);
+        assert_eq!(antialiased.get_pixel(8, 8).0, [250, 250, 250]);
+    }
+
+    #[test]
+    fn format_outer_walls_fully() {
+        for (wall, passage, margin) in [(1, 1, 0), (3, 7, 0), (3, 7, 2), (2, 5, 1), (7, 3, 5)] {
+            for (columns, rows) in [(1, 1), (2, 3), (5, 4)] {
+                let image = Image::new()
+                    .wall(wall)
+                    .passage(passage)
+                    .margin(margin)
+                    .format(&Grid::new(columns, rows))
+                    .0;
+                let (width, height) = (image.width() as usize, image.height() as usize);
+
+                // Every side of the maze has outer walls of the full width, where the ones on the
+                // far sides overlap the margin by a pixel
+                let far_margin = margin.saturating_sub(1);
+                for (x, y, pixel) in image.enumerate_pixels() {
+                    let (x, y) = (x as usize, y as usize);
+                    let (near, far) = (x.min(y), (width - 1 - x).min(height - 1 - y));
+                    let expected = if near < margin || far < far_margin {
+                        [250, 250, 250]
+                    } else if near < margin + wall || far < far_margin + wall {
+                        [0, 0, 0]
+                    } else {
+                        continue;
+                    };
+                    assert_eq!(pixel.0, expected, "({x}, {y}) of {wall}/{passage}/{margin}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn format_fog() {
+        let image = Image::new()
+            .wall(2)
+            .passage(6)
+            .margin(5)
+            .visible_cells(HashSet::from([(0, 0), (7, 7)]))
+            .fog_color(Color::RGB(40, 40, 40))
+            .format(&Grid::new(2, 1))
+            .0;
+
+        // The visible cell and all of its walls
+        assert_eq!(image.get_pixel(9, 9).0, [250, 250, 250]);
+        assert_eq!(image.get_pixel(5, 9).0, [0, 0, 0]);
+        assert_eq!(image.get_pixel(12, 5).0, [0, 0, 0]);
+        assert_eq!(image.get_pixel(15, 9).0, [0, 0, 0]);
+        // The cell in the fog and its walls
+        assert_eq!(image.get_pixel(16, 9).0, [40, 40, 40]);
+        assert_eq!(image.get_pixel(18, 5).0, [40, 40, 40]);
+        assert_eq!(image.get_pixel(23, 9).0, [40, 40, 40]);
+        // The margin
+        assert_eq!(image.get_pixel(24, 9).0, [250, 250, 250]);
+        assert_eq!(image.get_pixel(9, 2).0, [250, 250, 250]);
+    }
+
+    #[test]
+    fn format_shadow() {
+        let mut grid = Grid::new(2, 1);
+        grid.carve_passage((0, 0), Cell::EAST).unwrap();
+        let formatter = || Image::new().wall(2).passage(6).margin(5);
+
+        let image = formatter()
+            .shadow((3, -2), Color::RGB(50, 100, 150), 0.5)
+            .format(&grid)
+            .0;
+
+        // The shadow of the western wall inside the passage, under the northern wall and out of it
+        assert_eq!(image.get_pixel(9, 9).0, [150, 175, 200]);
+        assert_eq!(image.get_pixel(11, 9).0, [250, 250, 250]);
+        assert_eq!(image.get_pixel(9, 6).0, [0, 0, 0]);
+        // The shadow of the eastern wall over the margin, and of the northern wall out of the image
+        assert_eq!(image.get_pixel(25, 12).0, [150, 175, 200]);
+        assert_eq!(image.get_pixel(25, 14).0, [250, 250, 250]);
+        assert_eq!(image.get_pixel(15, 3).0, [150, 175, 200]);
+
+        // Without opacity or walls, there is no shadow
+        let shadow = |opacity| formatter().shadow((3, -2), Color::RGB(50, 100, 150), opacity);
+        assert_eq!(shadow(0.0).format(&grid).0, formatter().format(&grid).0);
+        assert_eq!(
+            shadow(1.0).hide_walls(true).format(&grid).0,
+            formatter().hide_walls(true).format(&grid).0
+        );
+        assert_eq!(
+            shadow(7.0).format(&grid).0.get_pixel(9, 9).0,
+            [50, 100, 150]
+        );
+    }
+
+    #[test]
+    fn format_watermark() {
+        let grid = Grid::new(2, 1);
+        let formatter = || Image::new().wall(2).passage(6).margin(5);
+        let mut logo = RgbaImage::from_pixel(3, 2, image::Rgba([0, 0, 200, 255]));
+        logo.put_pixel(0, 0, image::Rgba([0, 0, 200, 0]));
+
+        // The image is 28x20, so the logo lies over the margin in every corner
+        let stamp = |corner| {
+            formatter()
+                .watermark(logo.clone(), corner, 1, 1.0)
+                .format(&grid)
+                .0
+        };
+        for (corner, (x, y)) in [
+            (Corner::TopLeft, (1, 1)),
+            (Corner::TopRight, (24, 1)),
+            (Corner::BottomLeft, (1, 17)),
+            (Corner::BottomRight, (24, 17)),
+        ] {
+            let image = stamp(corner);
+            assert_eq!(image.get_pixel(x, y).0, [250, 250, 250], "{corner:?}");
+            assert_eq!(image.get_pixel(x + 1, y).0, [0, 0, 200], "{corner:?}");
+            assert_eq!(image.get_pixel(x + 2, y + 1).0, [0, 0, 200], "{corner:?}");
+            assert_eq!(
+                image.get_pixel(x + 3, y + 1).0,
+                [250, 250, 250],
+                "{corner:?}"
+            );
+        }
+
+        // Translucent logos are blended, and no logo is left without any opacity
+        let image = formatter()
+            .watermark(logo.clone(), Corner::TopLeft, 6, 0.5)
+            .format(&grid)
+            .0;
+        assert_eq!(image.get_pixel(7, 6).0, [0, 0, 100]);
+        assert_eq!(image.get_pixel(7, 7).0, [125, 125, 225]);
+        assert_eq!(
+            formatter()
+                .watermark(logo.clone(), Corner::TopLeft, 7, 0.0)
+                .format(&grid)
+                .0,
+            formatter().format(&grid).0
+        );
+
+        // A logo larger than the image is clipped by the padding on the opposite side
+        let large = RgbaImage::from_pixel(40, 40, image::Rgba([0, 0, 200, 255]));
+        let image = formatter()
+            .watermark(large.clone(), Corner::BottomRight, 2, 1.0)
+            .format(&grid)
+            .0;
+        assert_eq!(image.get_pixel(2, 2).0, [0, 0, 200]);
+        assert_eq!(image.get_pixel(25, 17).0, [0, 0, 200]);
+        assert_eq!(image.get_pixel(1, 1).0, [250, 250, 250]);
+        assert_eq!(image.get_pixel(26, 18).0, [250, 250, 250]);
+        let image = formatter()
+            .watermark(large, Corner::TopLeft, 30, 1.0)
+            .format(&grid)
+            .0;
+        assert_eq!(image, formatter().format(&grid).0);
+
+        // Thumbnails and anti-aliased images are stamped after rendering at their final size
+        let image = formatter()
+            .thumbnail(2)
+            .watermark(logo.clone(), Corner::TopLeft, 0, 1.0)
+            .format(&grid)
+            .0;
+        assert_eq!(image.get_pixel(1, 0).0, [0, 0, 200]);
+        let image = formatter()
+            .antialias(true)
+            .watermark(logo, Corner::TopLeft, 1, 1.0)
+            .format(&grid)
+            .0;
+        assert_eq!(image.get_pixel(3, 2).0, [0, 0, 200]);
+    }
+
+    #[test]
+    fn format_thumbnail() {
+        let mut grid = Grid::new(2, 1);
+        grid.carve_passage((0, 0), Cell::EAST).unwrap();
+        grid.carve_opening((1, 0), Cell::EAST).unwrap();
+        let formatter = Image::new()
+            .passage_color(Color::RGB(1, 2, 3))
+            .foreground(Color::RGB(9, 9, 9));
+
+        let image = formatter.clone().thumbnail(1).format(&grid).0;
+
+        let (w, f) = ([9, 9, 9], [1, 2, 3]);
+        let expected = [[w, w, w, w, w], [w, f, f, f, f], [w, w, w, w, w]];
+        assert_eq!(image.dimensions(), (5, 3));
+        for (y, row) in expected.iter().enumerate() {
+            for (x, pixel) in row.iter().enumerate() {
+                assert_eq!(image.get_pixel(x as u32, y as u32).0, *pixel, "({x}, {y})");
+            }
+        }
+
+        let image = formatter.thumbnail(2).format(&grid).0;
+        assert_eq!(image.dimensions(), (7, 4));
+        assert_eq!(image.get_pixel(2, 2).0, f);
+        assert_eq!(image.get_pixel(3, 2).0, f);
+        assert_eq!(image.get_pixel(3, 3).0, w);
+    }
+
+    #[test]
+    fn format_wall_colors() {
+        let grid = Grid::new(2, 1);
+        let (h, v) = (Color::RGB(200, 0, 0), Color::RGB(0, 0, 200));
+        let formatter = || Image::new().wall(2).passage(6).margin(5);
+
+        let image = formatter().wall_colors(h, v).format(&grid).0;
+        let color = |x, y| {
+            let [r, g, b] = image.get_pixel(x, y).0;
+            Color::RGB(r, g, b)
+        };
+        assert_eq!(color(9, 5), h);
+        assert_eq!(color(9, 15), h);
+        assert_eq!(color(5, 9), v);
+        assert_eq!(color(14, 9), v);
+        assert_eq!(color(23, 9), v);
+        // Joints and corners
+        assert_eq!(color(5, 5), h);
+        assert_eq!(color(14, 6), h);
+        assert_eq!(color(9, 9), Color::RGB(250, 250, 250));
+
+        let foreground = Color::RGB(0, 0, 0);
+        assert_eq!(
+            formatter()
+                .wall_colors(foreground, foreground)
+                .format(&grid)
+                .0,
+            formatter().format(&grid).0
+        );
+
+        let thumbnail = formatter().wall_colors(h, v).thumbnail(2).format(&grid).0;
+        assert_eq!(thumbnail.get_pixel(0, 0).0, [200, 0, 0]);
+        assert_eq!(thumbnail.get_pixel(3, 0).0, [200, 0, 0]);
+        assert_eq!(thumbnail.get_pixel(3, 1).0, [0, 0, 200]);
+    }
+
+    #[test]
+    fn format_wall_gradient() {
+        let mut grid = Grid::new(2, 1);
+        grid.carve_passage((0, 0), Cell::EAST).unwrap();
+        let (top, bottom) = (Color::RGB(0, 0, 0), Color::RGB(200, 100, 50));
+        let formatter = || Image::new().wall(4).passage(6).margin(5);
+
+        let image = formatter().wall_gradient(top, bottom).format(&grid).0;
+        let color = |x, y| {
+            let [r, g, b] = image.get_pixel(x, y).0;
+            Color::RGB(r, g, b)
+        };
+        // The northern and the southern walls across their rows
+        assert_eq!(color(12, 5), top);
+        assert_eq!(color(12, 6), Color::RGB(67, 33, 17));
+        assert_eq!(color(12, 8), bottom);
+        assert_eq!(color(20, 16), top);
+        assert_eq!(color(20, 19), bottom);
+        // The western and the eastern walls across their columns
+        assert_eq!(color(5, 12), top);
+        assert_eq!(color(6, 12), Color::RGB(67, 33, 17));
+        assert_eq!(color(8, 12), bottom);
+        assert_eq!(color(26, 12), top);
+        assert_eq!(color(29, 12), bottom);
+        // Joints belong to the horizontal walls
+        assert_eq!(color(5, 5), top);
+        assert_eq!(color(29, 19), bottom);
+        // The passage between the cells stays open
+        assert_eq!(color(15, 12), Color::RGB(250, 250, 250));
+
+        // Without the colors changing, walls are flat again
+        assert_eq!(
+            formatter().wall_gradient(top, top).format(&grid).0,
+            formatter().format(&grid).0
+        );
+        let thumbnail = formatter()
+            .wall_gradient(top, bottom)
+            .thumbnail(2)
+            .format(&grid)
+            .0;
+        assert_eq!(thumbnail.get_pixel(0, 0).0, [0, 0, 0]);
+    }
+
+    #[test]
+    fn thumbnail_of_large_grid() {
+        let grid = Grid::new_packed(1000, 1000);
+
+        assert!(Image::new().try_format(&grid).is_err());
+        let image = Image::new().thumbnail(1).try_format(&grid).unwrap().0;
+        assert_eq!(image.dimensions(), (2001, 2001));
+        assert!(Image::new()
+            .thumbnail(1)
+            .max_pixels(2000 * 2000)
+            .try_format(&grid)
+            .is_err());
+    }
+
+    #[test]
+    fn draw_in_bands() {
+        let formatter = Image::new().wall(3).passage(5).margin(1);
+        let grid = generate_maze();
+        let (width, height) = formatter.sizes(&grid);
+        let draw = |rows| {
+            let mut image: RgbImage = ImageBuffer::new(width as u32, height as u32);
+            formatter.draw_bands(&mut image, &grid, rows);
+            image
+        };
+
+        let whole = draw(height);
+        for rows in [1, 2, 7, 8, 9, height + 1] {
+            assert_eq!(draw(rows), whole, "{} rows", rows);
+        }
+    }
+
+    #[test]
+    fn format_antialiased() {
+        let formatter = || Image::new().wall(3).passage(9).margin(4);
+        let grid = generate_maze();
+
+        let sharp = formatter().format(&grid).0;
+        let smooth = formatter().antialias(true).format(&grid).0;
+
+        assert_eq!(smooth.dimensions(), sharp.dimensions());
+        assert_ne!(smooth, sharp);
+        // Edges of walls blend the colors, while the middles of walls and passages keep them
+        assert!(smooth
+            .pixels()
+            .any(|pixel| pixel.0 != [0, 0, 0] && pixel.0 != [250, 250, 250]));
+        assert_eq!(smooth.get_pixel(5, 5).0, [0, 0, 0]);
+        assert_eq!(smooth.get_pixel(12, 12).0, [250, 250, 250]);
+        assert_eq!(formatter().parse(&smooth).unwrap(), grid);
+    }
+
+    #[test]
+    fn try_format_checks_max_pixels() {
+        let grid = generate_maze();
+        // Each side is 4 * 80 + 40 + 100 = 460 px
+        let formatter = || Image::new().max_pixels(460 * 460);
+
+        assert_eq!(
+            formatter().try_format(&grid).unwrap().0,
+            formatter().format(&grid).0
+        );
+        assert!(formatter().antialias(true).try_format(&grid).is_err());
+
+        let err = Image::new()
+            .max_pixels(460 * 460 - 1)
+            .try_format(&grid)
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.to_string(),
+            "Cannot format maze. Reason: A 4x4 maze exceeds the maximum of 211599 pixels"
+        );
+    }
+
+    #[test]
+    fn try_format_huge_dimensions() {
+        let grid = Grid::new(10, 10);
+
+        assert!(Image::new().wall(usize::MAX / 2).try_format(&grid).is_err());
+        assert!(Image::new()
+            .wall(1 << 31)
+            .max_pixels(usize::MAX)
+            .try_format(&Grid::new(1, 1))
+            .is_err());
+    }
+
+    #[test]
+    fn format_ignores_max_pixels() {
+        let grid = generate_maze();
+        let formatter = || Image::new().max_pixels(100);
+
+        assert_eq!(formatter().format(&grid).0.dimensions(), (460, 460));
+        assert!(Formatter::try_format(&formatter(), &grid).is_err());
+    }
+
+    #[test]
+    fn format_without_margin() {
+        let image = Image::new()
+            .wall(2)
+            .passage(3)
+            .margin(0)
+            .format(&generate_maze())
+            .0;
+
+        // The outer walls on the far sides overlap the missing margin by a pixel
+        assert_eq!(image.dimensions(), (23, 23));
+        assert_eq!(image.get_pixel(21, 21).0, [0, 0, 0]);
+        assert_eq!(image.get_pixel(22, 22).0, [0, 0, 0]);
+    }
+
+    #[test]
+    fn parse_fixture() {
+        let fixture = image::open("tests/fixtures/maze.png").unwrap().to_rgb8();
+
+        let grid = Image::new().parse(&fixture).unwrap();
+
+        assert_eq!(grid, generate_maze());
+    }
+
+    #[test]
+    fn parse_roundtrip_with_openings() {
+        let mut grid = generate_maze();
+        grid.carve_opening((0, 0), Cell::NORTH).unwrap();
+        grid.carve_opening((0, 3), Cell::WEST).unwrap();
+        grid.carve_opening((3, 3), Cell::SOUTH).unwrap();
+        grid.carve_opening((3, 2), Cell::EAST).unwrap();
+
+        for formatter in [
+            Image::new().wall(1).passage(2).margin(1),
+            Image::new().wall(3).passage(8).margin(2),
+            Image::new()
+                .foreground(Color::RGB(200, 10, 10))
+                .background(Color::RGB(10, 10, 200)),
+        ] {
+            let image = formatter.format(&grid).0;
+
+            assert_eq!(formatter.parse(&image).unwrap(), grid);
+        }
+    }
+
+    #[test]
+    fn parse_mismatched_dimensions() {
+        let image = Image::new().format(&generate_maze()).0;
+
+        let err = Image::new().wall(30).parse(&image).unwrap_err();
+        assert_eq!(
+            err.reason,
+            "Image dimensions 460x460 don't match a grid with 30 px walls, 40 px passages and a 50 px margin"
+        );
+
+        let err = Image::new().margin(300).parse(&image).unwrap_err();
+        assert!(err.reason.starts_with("Image dimensions 460x460"));
+
+        let err = Image::new().passage(1).parse(&image).unwrap_err();
+        assert_eq!(
+            err.reason,
+            "Wall width must be at least 1 px and passage width at least 2 px"
+        );
+    }
+
     fn generate_maze() -> Grid {
         let mut grid = Grid::new(4, 4);
 
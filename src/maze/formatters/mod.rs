@@ -2,16 +2,25 @@
 
 mod ascii;
 mod game_map;
+mod html;
 mod image;
+mod obj;
+mod svg;
+pub(crate) mod walls;
 
 use crate::maze::grid::Grid;
-use ::image::RgbImage;
+use ::image::{ImageFormat, RgbImage};
+use std::io::Cursor;
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
 use std::{fs::File, io::Write};
 
-pub use self::image::Image;
-use super::errors::MazeSaveError;
-pub use ascii::{AsciiNarrow, AsciiBroad};
+pub use self::image::{Corner, CornerStyle, Image};
+use super::errors::{FormatError, MazeSaveError};
+pub use ascii::{Ascii, AsciiNarrow, AsciiBroad};
 pub use game_map::GameMap;
+pub use html::Html;
+pub use obj::Obj;
+pub use svg::Svg;
 
 /// A trait for maze formatters
 pub trait Formatter<T>
@@ -20,6 +29,15 @@ where
 {
     /// Returns a given grid converted into a given type that implements [Saveable]
     fn format(&self, grid: &Grid) -> T;
+
+    /// Returns a given grid converted the same way as with [format](Formatter::format), or a
+    /// [FormatError] if the formatter refuses to convert it, e.g. when the output would be too
+    /// large
+    ///
+    /// Formatters accept any grid by default.
+    fn try_format(&self, grid: &Grid) -> Result<T, FormatError> {
+        Ok(self.format(grid))
+    }
 }
 
 /// A trait for data wrappers that must be returned after formatting the grid
@@ -34,9 +52,126 @@ pub trait Saveable {
 /// A custom wrapper over [RgbImage] for converting a maze to an image
 pub struct ImageWrapper(pub RgbImage);
 
+impl ImageWrapper {
+    /// Returns the image encoded as PNG, without writing to the filesystem
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let maze = OrthogonalMazeBuilder::new().seed(1).build().unwrap();
+    /// let png = maze.format(Image::new()).to_png_bytes().unwrap();
+    ///
+    /// assert!(png.starts_with(b"\x89PNG"));
+    /// ```
+    pub fn to_png_bytes(&self) -> Result<Vec<u8>, MazeSaveError> {
+        let mut bytes = Vec::new();
+        self.0
+            .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+            .map_err(|reason| MazeSaveError {
+                reason: reason.to_string(),
+            })?;
+
+        Ok(bytes)
+    }
+
+    /// Returns an image blending this image with another one of the same dimensions, e.g. a frame
+    /// of a crossfade between two mazes
+    ///
+    /// Every pixel is linearly interpolated, so an `alpha` of `0` returns this image and an `alpha`
+    /// of `1` returns the other one. Values out of the range are clamped, and `NaN` is treated as
+    /// `0`. Returns a [FormatError] if the dimensions of the images differ.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let build = |seed| OrthogonalMazeBuilder::new().seed(seed).build().unwrap();
+    /// let from = build(1).format(Image::new());
+    /// let to = build(2).format(Image::new());
+    ///
+    /// let frame = from.blend(&to, 0.5).unwrap();
+    ///
+    /// assert_eq!(frame.0.dimensions(), from.0.dimensions());
+    /// assert_eq!(from.blend(&to, 1.0).unwrap().0, to.0);
+    /// ```
+    pub fn blend(&self, other: &ImageWrapper, alpha: f32) -> Result<ImageWrapper, FormatError> {
+        if self.0.dimensions() != other.0.dimensions() {
+            let (width, height) = self.0.dimensions();
+            let (other_width, other_height) = other.0.dimensions();
+            return Err(FormatError::reason(format!(
+                "A {}x{} image doesn't match the dimensions of a {}x{} image",
+                width, height, other_width, other_height
+            )));
+        }
+
+        let alpha = if alpha.is_nan() {
+            0.0
+        } else {
+            alpha.clamp(0.0, 1.0)
+        };
+        let mut image = self.0.clone();
+        for (pixel, other) in image.pixels_mut().zip(other.0.pixels()) {
+            for (channel, target) in pixel.0.iter_mut().zip(other.0) {
+                let value = *channel as f32 + (target as f32 - *channel as f32) * alpha;
+                *channel = value.round() as u8;
+            }
+        }
+
+        Ok(ImageWrapper(image))
+    }
+
+    /// Returns the image enlarged by a given integer factor, where every pixel is repeated into a
+    /// square of `factor` by `factor` pixels
+    ///
+    /// Unlike rendering a maze with wider walls and passages, the pixels stay crisp, e.g. for
+    /// pixel-art games, and the image isn't rendered again. A factor of `0` returns an empty image.
+    /// Returns a [FormatError] if a side of the enlarged image exceeds [u32::MAX] pixels, or the
+    /// image can't be addressed in memory.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let maze = OrthogonalMazeBuilder::new().width(4).height(4).build().unwrap();
+    /// let image = maze.format(Image::new().wall(1).passage(2).margin(1));
+    ///
+    /// let zoomed = image.scale(3).unwrap();
+    ///
+    /// assert_eq!(zoomed.0.width(), image.0.width() * 3);
+    /// assert_eq!(zoomed.0.get_pixel(5, 5), image.0.get_pixel(1, 1));
+    /// ```
+    pub fn scale(&self, factor: u32) -> Result<ImageWrapper, FormatError> {
+        let (width, height) = self.0.dimensions();
+        let size = width
+            .checked_mul(factor)
+            .zip(height.checked_mul(factor))
+            .filter(|(width, height)| {
+                // Every pixel takes 3 bytes
+                (*width as usize)
+                    .checked_mul(*height as usize)
+                    .and_then(|pixels| pixels.checked_mul(3))
+                    .is_some()
+            });
+        let Some((scaled_width, scaled_height)) = size else {
+            return Err(FormatError::reason(format!(
+                "A {}x{} image is too large to scale by {}",
+                width, height, factor
+            )));
+        };
+
+        Ok(ImageWrapper(RgbImage::from_fn(
+            scaled_width,
+            scaled_height,
+            |x, y| *self.0.get_pixel(x / factor, y / factor),
+        )))
+    }
+}
+
 /// An implementation of [Saveable] for saving a maze image into a file
 impl Saveable for ImageWrapper {
     /// Saves an image to a file to a given path
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
     fn save(&self, path: &str) -> Result<String, MazeSaveError> {
         if let Err(reason) = self.0.save(path) {
             return Err(MazeSaveError {
@@ -46,6 +181,12 @@ impl Saveable for ImageWrapper {
 
         Ok(format!("Maze was successfully saved as an image: {}", path))
     }
+
+    /// Fails, as there is no filesystem on this target
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    fn save(&self, path: &str) -> Result<String, MazeSaveError> {
+        no_filesystem(path)
+    }
 }
 
 /// A custom wrapper over [std::string::String](std::string::String) for converting a maze into
@@ -55,6 +196,7 @@ pub struct StringWrapper(pub String);
 /// An implementation of [Saveable] for saving a maze string into a text file
 impl Saveable for StringWrapper {
     /// Saves a maze string to a file to a given path
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
     fn save(&self, path: &str) -> Result<String, MazeSaveError> {
         let path = match std::env::current_dir() {
             Err(why) => {
@@ -84,4 +226,75 @@ impl Saveable for StringWrapper {
             )),
         }
     }
+
+    /// Fails, as there is no filesystem on this target
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    fn save(&self, path: &str) -> Result<String, MazeSaveError> {
+        no_filesystem(path)
+    }
+}
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+fn no_filesystem(path: &str) -> Result<String, MazeSaveError> {
+    Err(MazeSaveError {
+        reason: format!("Couldn't save {}: no filesystem on this target", path),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use ::image::Rgb;
+
+    use super::*;
+
+    #[test]
+    fn blend() {
+        let from = ImageWrapper(RgbImage::from_pixel(2, 1, Rgb([0, 100, 255])));
+        let to = ImageWrapper(RgbImage::from_pixel(2, 1, Rgb([255, 100, 0])));
+
+        assert_eq!(
+            from.blend(&to, 0.5).unwrap().0.get_pixel(1, 0).0,
+            [128, 100, 128]
+        );
+        assert_eq!(from.blend(&to, 0.0).unwrap().0, from.0);
+        assert_eq!(from.blend(&to, 2.0).unwrap().0, to.0);
+        assert_eq!(from.blend(&to, f32::NAN).unwrap().0, from.0);
+    }
+
+    #[test]
+    fn scale() {
+        let mut image = RgbImage::new(2, 1);
+        image.put_pixel(1, 0, Rgb([255, 100, 0]));
+        let image = ImageWrapper(image);
+
+        let scaled = image.scale(3).unwrap();
+        assert_eq!(scaled.0.dimensions(), (6, 3));
+        for (x, y, pixel) in scaled.0.enumerate_pixels() {
+            assert_eq!(pixel, image.0.get_pixel(x / 3, y / 3), "at ({x}, {y})");
+        }
+
+        assert_eq!(image.scale(1).unwrap().0, image.0);
+        assert_eq!(image.scale(0).unwrap().0.dimensions(), (0, 0));
+    }
+
+    #[test]
+    fn scale_overflowing_dimensions() {
+        let image = ImageWrapper(RgbImage::new(70000, 1));
+
+        assert_eq!(
+            image.scale(70000).err().unwrap().reason,
+            "A 70000x1 image is too large to scale by 70000"
+        );
+    }
+
+    #[test]
+    fn blend_mismatched_dimensions() {
+        let from = ImageWrapper(RgbImage::new(2, 1));
+        let to = ImageWrapper(RgbImage::new(1, 2));
+
+        assert_eq!(
+            from.blend(&to, 0.5).err().unwrap().to_string(),
+            "Cannot format maze. Reason: A 2x1 image doesn't match the dimensions of a 1x2 image"
+        );
+    }
 }
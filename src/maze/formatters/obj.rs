@@ -0,0 +1,276 @@
+use crate::maze::{formatters::Formatter, grid::Grid};
+use std::fmt::Write;
+
+use super::walls::{walls, Wall};
+use super::StringWrapper;
+
+/// A Wavefront OBJ formatter for a generated maze, e.g. for 3D printing
+///
+/// Wall joints and wall segments follow the same geometry as the [Image] formatter, and each of
+/// them is extruded into a box standing on a base plate under the whole maze. Every box is a closed
+/// mesh with outward-facing faces, so slicers can merge the touching boxes into a solid model. The
+/// `Z` axis points up and the northern side of the maze faces the `Y` axis.
+///
+/// [Image]: super::Image
+///
+/// # Example
+/// ```rust,no_run
+/// use bevy_knossos::maze::*;
+///
+/// let maze = OrthogonalMazeBuilder::new().build().unwrap();
+///
+/// // Save as an OBJ mesh with 1 mm walls and 4 mm passages
+/// maze.save("output/maze.obj", Obj::new().wall(1.0).passage(4.0).height(5.0)).unwrap();
+/// ```
+pub struct Obj {
+    wall_width: f64,
+    passage_width: f64,
+    wall_height: f64,
+    base_thickness: f64,
+}
+
+impl Obj {
+    /// Returns a new instance of an [Obj] formatter with a default settings
+    pub const fn new() -> Obj {
+        Obj {
+            wall_width: 1.0,
+            passage_width: 4.0,
+            wall_height: 5.0,
+            base_thickness: 2.0,
+        }
+    }
+
+    /// Sets a wall width and returns itself
+    pub const fn wall(mut self, width: f64) -> Self {
+        self.wall_width = width;
+        self
+    }
+
+    /// Sets a passage width and returns itself
+    pub const fn passage(mut self, width: f64) -> Self {
+        self.passage_width = width;
+        self
+    }
+
+    /// Sets a height of walls above the base and returns itself
+    pub const fn height(mut self, height: f64) -> Self {
+        self.wall_height = height;
+        self
+    }
+
+    /// Sets a thickness of the base under the maze and returns itself
+    ///
+    /// A zero thickness leaves the walls without a base.
+    pub const fn base(mut self, thickness: f64) -> Self {
+        self.base_thickness = thickness;
+        self
+    }
+
+    const fn step(&self) -> f64 {
+        self.wall_width + self.passage_width
+    }
+
+    /// Returns the length of a side of the maze with a given number of cells
+    fn side(&self, cells: usize) -> f64 {
+        cells as f64 * self.step() + self.wall_width
+    }
+
+    /// Returns the minimum and the maximum corners of a wall segment in the `XY` plane
+    fn wall_bounds(&self, wall: Wall, grid: &Grid) -> ([f64; 2], [f64; 2]) {
+        let (w, p) = (self.wall_width, self.passage_width);
+        let at = |line: usize| line as f64 * self.step();
+        let (x, y, width, depth) = match wall {
+            Wall::Post(i, j) => (at(i), at(j), w, w),
+            Wall::Horizontal(i, j) => (at(i) + w, at(j), p, w),
+            Wall::Vertical(i, j) => (at(i), at(j) + w, w, p),
+        };
+
+        // Rows of cells go southwards, while the Y axis points northwards
+        let north = self.side(grid.height());
+        ([x, north - y - depth], [x + width, north - y])
+    }
+}
+
+impl Default for Obj {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An implementation of a formatter
+impl Formatter<StringWrapper> for Obj {
+    /// Converts a given grid into an OBJ mesh and returns a [StringWrapper] over that mesh
+    fn format(&self, grid: &Grid) -> StringWrapper {
+        let mut obj = String::new();
+        writeln!(obj, "# A {}x{} maze", grid.width(), grid.height()).unwrap();
+        writeln!(obj, "o maze").unwrap();
+
+        let mut boxes = 0;
+        let mut write_box = |obj: &mut String, min: [f64; 3], max: [f64; 3]| {
+            write_box(obj, boxes, min, max);
+            boxes += 1;
+        };
+
+        if self.base_thickness > 0.0 {
+            write_box(
+                &mut obj,
+                [0.0, 0.0, 0.0],
+                [
+                    self.side(grid.width()),
+                    self.side(grid.height()),
+                    self.base_thickness,
+                ],
+            );
+        }
+
+        let top = self.base_thickness + self.wall_height;
+        for wall in walls(grid) {
+            let ([x0, y0], [x1, y1]) = self.wall_bounds(wall, grid);
+            write_box(&mut obj, [x0, y0, self.base_thickness], [x1, y1, top]);
+        }
+
+        StringWrapper(obj)
+    }
+}
+
+/// Writes vertices and faces of an axis-aligned box, which is the `index`-th box of a mesh
+fn write_box(obj: &mut String, index: usize, [x0, y0, z0]: [f64; 3], [x1, y1, z1]: [f64; 3]) {
+    for (x, y, z) in [
+        (x0, y0, z0),
+        (x1, y0, z0),
+        (x1, y1, z0),
+        (x0, y1, z0),
+        (x0, y0, z1),
+        (x1, y0, z1),
+        (x1, y1, z1),
+        (x0, y1, z1),
+    ] {
+        writeln!(obj, "v {} {} {}", x, y, z).unwrap();
+    }
+
+    // Vertices of every face go counterclockwise when looking at the box from the outside, and OBJ
+    // indices start at 1
+    let offset = index * 8;
+    for [a, b, c, d] in [
+        [1, 4, 3, 2],
+        [5, 6, 7, 8],
+        [1, 2, 6, 5],
+        [3, 4, 8, 7],
+        [4, 1, 5, 8],
+        [2, 3, 7, 6],
+    ] {
+        writeln!(
+            obj,
+            "f {} {} {} {}",
+            offset + a,
+            offset + b,
+            offset + c,
+            offset + d
+        )
+        .unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::maze::grid::cell::Cell;
+
+    #[test]
+    fn new_call_default_params() {
+        let obj = Obj::new();
+        assert_eq!(1.0, obj.wall_width);
+        assert_eq!(4.0, obj.passage_width);
+        assert_eq!(5.0, obj.wall_height);
+        assert_eq!(2.0, obj.base_thickness);
+    }
+
+    #[test]
+    fn params_change() {
+        let obj = Obj::new().wall(2.0).passage(6.0).height(10.0).base(0.5);
+
+        assert_eq!(2.0, obj.wall_width);
+        assert_eq!(6.0, obj.passage_width);
+        assert_eq!(10.0, obj.wall_height);
+        assert_eq!(0.5, obj.base_thickness);
+    }
+
+    #[test]
+    fn format_single_wall() {
+        let mut grid = Grid::new(1, 1);
+        for direction in [Cell::NORTH, Cell::SOUTH, Cell::EAST] {
+            grid.carve_opening((0, 0), direction).unwrap();
+        }
+
+        let actual = Obj::new()
+            .wall(1.0)
+            .passage(2.0)
+            .height(3.0)
+            .base(0.0)
+            .format(&grid)
+            .0;
+
+        let mut expected = vec![];
+        // Posts at the western corners, then the western wall between them
+        for (y0, y1) in [(3, 4), (0, 1), (1, 3)] {
+            for (x, y, z) in [
+                (0, y0, 0),
+                (1, y0, 0),
+                (1, y1, 0),
+                (0, y1, 0),
+                (0, y0, 3),
+                (1, y0, 3),
+                (1, y1, 3),
+                (0, y1, 3),
+            ] {
+                expected.push(format!("v {x} {y} {z}"));
+            }
+        }
+        assert!(actual.starts_with("# A 1x1 maze\no maze\nv 0 3 0\n"));
+        assert_eq!(
+            actual
+                .lines()
+                .filter(|line| line.starts_with("v "))
+                .collect::<Vec<_>>(),
+            expected
+        );
+        assert_eq!(
+            actual.lines().filter(|line| line.starts_with("f ")).count(),
+            18
+        );
+        assert!(actual.ends_with("f 18 19 23 22\n"));
+    }
+
+    #[test]
+    fn format_closed_mesh() {
+        let mut grid = Grid::new(3, 2);
+        grid.carve_passage((0, 0), Cell::EAST).unwrap();
+        grid.carve_passage((1, 0), Cell::SOUTH).unwrap();
+        grid.carve_passage((1, 1), Cell::EAST).unwrap();
+
+        let obj = Obj::new().format(&grid).0;
+        let vertices = obj.lines().filter(|line| line.starts_with("v ")).count();
+        let faces: Vec<Vec<usize>> = obj
+            .lines()
+            .filter_map(|line| line.strip_prefix("f "))
+            .map(|face| face.split(' ').map(|i| i.parse().unwrap()).collect())
+            .collect();
+
+        // A base and the boxes of walls
+        assert_eq!(vertices, (1 + walls(&grid).len()) * 8);
+        assert_eq!(faces.len(), vertices / 8 * 6);
+
+        // Every edge of a closed mesh is shared by two faces going along it in opposite directions
+        let edges: HashSet<(usize, usize)> = faces
+            .iter()
+            .flat_map(|face| (0..4).map(|k| (face[k], face[(k + 1) % 4])))
+            .collect();
+        assert_eq!(edges.len(), faces.len() * 4);
+        assert!(edges.iter().all(|(a, b)| edges.contains(&(*b, *a))));
+        assert!(edges
+            .iter()
+            .all(|(a, b)| (1..=vertices).contains(a) && a != b));
+    }
+}
@@ -0,0 +1,415 @@
+use crate::maze::{formatters::Formatter, grid::Grid};
+use crate::utils::color::Color;
+use std::fmt::Write;
+
+use super::walls::{contours, has_horizontal_wall, has_vertical_wall, walls, Wall};
+use super::StringWrapper;
+
+/// An SVG formatter for a generated maze
+///
+/// By default, walls are rendered as centerlines stroked with the wall width. In a filled mode,
+/// the wall area is rendered as filled rectangles following the same geometry as the [Image]
+/// formatter, so the thickness of walls is preserved, e.g. for laser-cutting. In an outline mode,
+/// only the [contours](Svg::contours) of that wall area are stroked, e.g. for pen plotters.
+///
+/// [Image]: super::Image
+///
+/// # Example
+/// ```rust,no_run
+/// use bevy_knossos::maze::*;
+///
+/// let maze = OrthogonalMazeBuilder::new().build().unwrap();
+///
+/// // Save as an SVG with centerline walls
+/// maze.save("output/maze.svg", Svg::new()).unwrap();
+/// // Save as an SVG with filled walls
+/// maze.save("output/maze_filled.svg", Svg::new().wall(10).passage(30).filled(true)).unwrap();
+/// // Save as an SVG with outlines of walls
+/// maze.save("output/maze_outline.svg", Svg::new().wall(10).passage(30).outline(true)).unwrap();
+/// ```
+pub struct Svg {
+    wall_width: usize,
+    passage_width: usize,
+    margin: usize,
+    background_color: Color,
+    foreground_color: Color,
+    filled: bool,
+    outline: bool,
+}
+
+impl Svg {
+    /// Returns a new instance of an [Svg] formatter with a default settings
+    pub const fn new() -> Svg {
+        Svg {
+            wall_width: 40,
+            passage_width: 40,
+            background_color: Color::RGB(250, 250, 250),
+            foreground_color: Color::RGB(0, 0, 0),
+            margin: 50,
+            filled: false,
+            outline: false,
+        }
+    }
+
+    /// Sets a wall width and returns itself
+    pub const fn wall(mut self, width: usize) -> Self {
+        self.wall_width = width;
+        self
+    }
+
+    /// Sets a passage width and returns itself
+    pub const fn passage(mut self, width: usize) -> Self {
+        self.passage_width = width;
+        self
+    }
+
+    /// Sets a background color and returns itself
+    pub const fn background(mut self, color: Color) -> Self {
+        self.background_color = color;
+        self
+    }
+
+    /// Sets a maze (foreground) color and returns itself
+    pub const fn foreground(mut self, color: Color) -> Self {
+        self.foreground_color = color;
+        self
+    }
+
+    /// Sets a margin (a distance between a maze and the image borders) and returns itself
+    pub const fn margin(mut self, value: usize) -> Self {
+        self.margin = value;
+        self
+    }
+
+    /// Sets whether walls are rendered as filled rectangles instead of centerlines and returns
+    /// itself
+    pub const fn filled(mut self, filled: bool) -> Self {
+        self.filled = filled;
+        self
+    }
+
+    /// Sets whether only the outlines of the filled walls are rendered and returns itself
+    ///
+    /// The outline mode takes precedence over the filled mode.
+    pub const fn outline(mut self, outline: bool) -> Self {
+        self.outline = outline;
+        self
+    }
+
+    /// Returns closed contours around the area of filled walls of a given grid, each as a list of
+    /// its corners in the coordinates of the SVG document
+    ///
+    /// A contour is a polyline going from a corner to the next one along the walls, and back from
+    /// the last corner to the first one, so a plotter draws every contour in a single stroke. Every
+    /// contour goes clockwise around the walls and starts at its top left corner, and contours are
+    /// ordered row by row. Joined walls share contours, so there are as few of them as possible,
+    /// e.g. a perfect maze without openings has one contour along the outer border and another one
+    /// along its passages.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let mut grid = Grid::new(1, 1);
+    /// grid.carve_opening((0, 0), Cell::NORTH).unwrap();
+    ///
+    /// let contours = Svg::new().wall(2).passage(6).margin(0).contours(&grid);
+    ///
+    /// assert_eq!(contours, vec![vec![(0, 0), (2, 0), (2, 8), (8, 8), (8, 0), (10, 0), (10, 10), (0, 10)]]);
+    /// ```
+    pub fn contours(&self, grid: &Grid) -> Vec<Vec<(usize, usize)>> {
+        // Even lattice lines start posts and odd ones start passages
+        let position = |k: usize| self.margin + k / 2 * self.step() + k % 2 * self.wall_width;
+
+        contours(grid)
+            .into_iter()
+            .map(|contour| {
+                contour
+                    .into_iter()
+                    .map(|(k, l)| (position(k), position(l)))
+                    .collect()
+            })
+            .collect()
+    }
+
+    const fn step(&self) -> usize {
+        self.wall_width + self.passage_width
+    }
+
+    const fn sizes(&self, grid: &Grid) -> (usize, usize) {
+        let width = self.step() * grid.width() + self.wall_width + self.margin * 2;
+        let height = self.step() * grid.height() + self.wall_width + self.margin * 2;
+
+        (width, height)
+    }
+
+    /// Returns the top left corner of a wall joint (a post) at given lattice coordinates
+    const fn post_origin(&self, i: usize, j: usize) -> (usize, usize) {
+        (self.margin + i * self.step(), self.margin + j * self.step())
+    }
+
+    fn draw_filled(&self, svg: &mut String, grid: &Grid) {
+        let wall = self.wall_width;
+        let passage = self.passage_width;
+
+        writeln!(svg, r#"<g fill="{}">"#, self.foreground_color).unwrap();
+
+        for segment in walls(grid) {
+            match segment {
+                Wall::Post(i, j) => {
+                    let (x, y) = self.post_origin(i, j);
+                    write_rect(svg, x, y, wall, wall);
+                }
+                Wall::Horizontal(i, j) => {
+                    let (x, y) = self.post_origin(i, j);
+                    write_rect(svg, x + wall, y, passage, wall);
+                }
+                Wall::Vertical(i, j) => {
+                    let (x, y) = self.post_origin(i, j);
+                    write_rect(svg, x, y + wall, wall, passage);
+                }
+            }
+        }
+
+        writeln!(svg, "</g>").unwrap();
+    }
+
+    fn draw_lines(&self, svg: &mut String, grid: &Grid) {
+        // Lines go through the centers of posts, and the square line caps cover the posts
+        let half = self.wall_width as f64 / 2.0;
+        let center = |i: usize, j: usize| {
+            let (x, y) = self.post_origin(i, j);
+            (x as f64 + half, y as f64 + half)
+        };
+
+        let mut path = String::new();
+
+        for j in 0..=grid.height() {
+            for i in 0..grid.width() {
+                if has_horizontal_wall(grid, i, j) {
+                    let (x0, y) = center(i, j);
+                    let (x1, _) = center(i + 1, j);
+                    write!(path, "M{} {}H{}", x0, y, x1).unwrap();
+                }
+            }
+        }
+
+        for j in 0..grid.height() {
+            for i in 0..=grid.width() {
+                if has_vertical_wall(grid, i, j) {
+                    let (x, y0) = center(i, j);
+                    let (_, y1) = center(i, j + 1);
+                    write!(path, "M{} {}V{}", x, y0, y1).unwrap();
+                }
+            }
+        }
+
+        writeln!(
+            svg,
+            r#"<path d="{}" fill="none" stroke="{}" stroke-width="{}" stroke-linecap="square"/>"#,
+            path, self.foreground_color, self.wall_width
+        )
+        .unwrap();
+    }
+
+    fn draw_outline(&self, svg: &mut String, grid: &Grid) {
+        let mut path = String::new();
+
+        for contour in self.contours(grid) {
+            let (x, y) = contour[0];
+            write!(path, "M{} {}", x, y).unwrap();
+
+            // Contours are rectilinear, so every next corner is either on the same row or column
+            for (&(_, y0), &(x1, y1)) in contour.iter().zip(&contour[1..]) {
+                if y0 == y1 {
+                    write!(path, "H{}", x1).unwrap();
+                } else {
+                    write!(path, "V{}", y1).unwrap();
+                }
+            }
+            path.push('Z');
+        }
+
+        writeln!(
+            svg,
+            r#"<path d="{}" fill="none" stroke="{}" stroke-width="1"/>"#,
+            path, self.foreground_color
+        )
+        .unwrap();
+    }
+}
+
+impl Default for Svg {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An implementation of a formatter
+impl Formatter<StringWrapper> for Svg {
+    /// Converts a given grid into an SVG document and returns a [StringWrapper] over that document
+    fn format(&self, grid: &Grid) -> StringWrapper {
+        let (width, height) = self.sizes(grid);
+        let mut svg = String::new();
+
+        writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            r#"<rect width="{}" height="{}" fill="{}"/>"#,
+            width, height, self.background_color
+        )
+        .unwrap();
+
+        if self.outline {
+            self.draw_outline(&mut svg, grid);
+        } else if self.filled {
+            self.draw_filled(&mut svg, grid);
+        } else {
+            self.draw_lines(&mut svg, grid);
+        }
+
+        writeln!(svg, "</svg>").unwrap();
+
+        StringWrapper(svg)
+    }
+}
+
+fn write_rect(svg: &mut String, x: usize, y: usize, width: usize, height: usize) {
+    writeln!(
+        svg,
+        r#"<rect x="{}" y="{}" width="{}" height="{}"/>"#,
+        x, y, width, height
+    )
+    .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze::grid::cell::Cell;
+
+    #[test]
+    fn new_call_default_params() {
+        let svg = Svg::new();
+        assert_eq!(40, svg.wall_width);
+        assert_eq!(40, svg.passage_width);
+        assert_eq!(Color::RGB(250, 250, 250), svg.background_color);
+        assert_eq!(Color::RGB(0, 0, 0), svg.foreground_color);
+        assert_eq!(50, svg.margin);
+        assert!(!svg.filled);
+        assert!(!svg.outline);
+    }
+
+    #[test]
+    fn params_change() {
+        let svg = Svg::new()
+            .wall(10)
+            .passage(5)
+            .background(Color::RGB(1, 1, 1))
+            .foreground(Color::RGB(100, 100, 100))
+            .margin(20)
+            .filled(true);
+
+        assert_eq!(10, svg.wall_width);
+        assert_eq!(5, svg.passage_width);
+        assert_eq!(Color::RGB(1, 1, 1), svg.background_color);
+        assert_eq!(Color::RGB(100, 100, 100), svg.foreground_color);
+        assert_eq!(20, svg.margin);
+        assert!(svg.filled);
+        assert!(Svg::new().outline(true).outline);
+    }
+
+    #[test]
+    fn format_lines() {
+        let mut expected = String::new();
+        expected.push_str(r#"<svg xmlns="http://www.w3.org/2000/svg" width="28" height="20" viewBox="0 0 28 20">"#);
+        expected.push('\n');
+        expected.push_str(r#"<rect width="28" height="20" fill="rgb(250, 250, 250)"/>"#);
+        expected.push('\n');
+        expected.push_str(r#"<path d="M6 6H14M14 6H22M6 14H14M14 14H22M6 6V14M22 6V14" fill="none" stroke="rgb(0, 0, 0)" stroke-width="2" stroke-linecap="square"/>"#);
+        expected.push('\n');
+        expected.push_str("</svg>\n");
+
+        let formatter = Svg::new().wall(2).passage(6).margin(5);
+        let actual = formatter.format(&generate_maze()).0;
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn format_filled() {
+        let mut expected = String::new();
+        expected.push_str(r#"<svg xmlns="http://www.w3.org/2000/svg" width="28" height="20" viewBox="0 0 28 20">"#);
+        expected.push('\n');
+        expected.push_str(r#"<rect width="28" height="20" fill="rgb(250, 250, 250)"/>"#);
+        expected.push('\n');
+        expected.push_str(r#"<g fill="rgb(0, 0, 0)">"#);
+        expected.push('\n');
+        for (x, y) in [(5, 5), (13, 5), (21, 5), (5, 13), (13, 13), (21, 13)] {
+            expected.push_str(&format!(r#"<rect x="{x}" y="{y}" width="2" height="2"/>"#));
+            expected.push('\n');
+        }
+        for (x, y) in [(7, 5), (15, 5), (7, 13), (15, 13)] {
+            expected.push_str(&format!(r#"<rect x="{x}" y="{y}" width="6" height="2"/>"#));
+            expected.push('\n');
+        }
+        for (x, y) in [(5, 7), (21, 7)] {
+            expected.push_str(&format!(r#"<rect x="{x}" y="{y}" width="2" height="6"/>"#));
+            expected.push('\n');
+        }
+        expected.push_str("</g>\n</svg>\n");
+
+        let formatter = Svg::new().wall(2).passage(6).margin(5).filled(true);
+        let actual = formatter.format(&generate_maze()).0;
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn format_outline() {
+        let mut expected = String::new();
+        expected.push_str(r#"<svg xmlns="http://www.w3.org/2000/svg" width="28" height="20" viewBox="0 0 28 20">"#);
+        expected.push('\n');
+        expected.push_str(r#"<rect width="28" height="20" fill="rgb(250, 250, 250)"/>"#);
+        expected.push('\n');
+        expected.push_str(r#"<path d="M5 5H23V15H5ZM7 7V13H21V7Z" fill="none" stroke="rgb(0, 0, 0)" stroke-width="1"/>"#);
+        expected.push('\n');
+        expected.push_str("</svg>\n");
+
+        // The outline mode takes precedence over the filled one
+        let formatter = Svg::new()
+            .wall(2)
+            .passage(6)
+            .margin(5)
+            .filled(true)
+            .outline(true);
+        let actual = formatter.format(&generate_maze()).0;
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn filled_skips_joints_without_walls() {
+        let mut grid = Grid::new(2, 2);
+        grid.carve_passage((0, 0), Cell::EAST).unwrap();
+        grid.carve_passage((0, 0), Cell::SOUTH).unwrap();
+        grid.carve_passage((1, 0), Cell::SOUTH).unwrap();
+        grid.carve_passage((0, 1), Cell::EAST).unwrap();
+
+        let formatter = Svg::new().wall(2).passage(6).margin(0).filled(true);
+        let actual = formatter.format(&grid).0;
+
+        assert!(!actual.contains(r#"<rect x="8" y="8" width="2" height="2"/>"#));
+        assert!(actual.contains(r#"<rect x="0" y="0" width="2" height="2"/>"#));
+    }
+
+    fn generate_maze() -> Grid {
+        let mut grid = Grid::new(2, 1);
+        grid.carve_passage((0, 0), Cell::EAST).unwrap();
+        grid
+    }
+}
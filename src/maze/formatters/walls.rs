@@ -0,0 +1,235 @@
+use std::collections::BTreeMap;
+
+use crate::maze::grid::{cell::Cell, Grid};
+
+/// A segment of the wall area of a maze at lattice coordinates, where `(i, j)` is the wall joint
+/// at the top left corner of a cell `(i, j)`
+///
+/// Walls of a maze are split into wall joints (posts) and wall segments between adjacent posts,
+/// which can be drawn or extruded as rectangles following the same geometry as the [Image]
+/// formatter.
+///
+/// [Image]: super::Image
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Wall {
+    /// A wall joint, which is a square of the wall width
+    Post(usize, usize),
+    /// A wall between the posts `(i, j)` and `(i + 1, j)`
+    Horizontal(usize, usize),
+    /// A wall between the posts `(i, j)` and `(i, j + 1)`
+    Vertical(usize, usize),
+}
+
+/// Returns all the posts, then all the horizontal walls and then all the vertical walls of a grid,
+/// each ordered row by row
+pub(crate) fn walls(grid: &Grid) -> Vec<Wall> {
+    let mut walls = vec![];
+
+    for j in 0..=grid.height() {
+        for i in 0..=grid.width() {
+            if has_post(grid, i, j) {
+                walls.push(Wall::Post(i, j));
+            }
+        }
+    }
+
+    for j in 0..=grid.height() {
+        for i in 0..grid.width() {
+            if has_horizontal_wall(grid, i, j) {
+                walls.push(Wall::Horizontal(i, j));
+            }
+        }
+    }
+
+    for j in 0..grid.height() {
+        for i in 0..=grid.width() {
+            if has_vertical_wall(grid, i, j) {
+                walls.push(Wall::Vertical(i, j));
+            }
+        }
+    }
+
+    walls
+}
+
+/// Returns closed contours around the wall area of a grid, each as a list of its corners
+///
+/// The wall area is split into blocks of `2 * width + 1` columns and `2 * height + 1` rows, where
+/// blocks at even columns and rows are posts, blocks at odd columns and rows are the passages of
+/// cells, and the rest are wall segments. Corners are at lattice coordinates of those blocks,
+/// i.e. `(k, l)` is the top left corner of the block in the `k`-th column and the `l`-th row.
+///
+/// Every contour goes clockwise around the wall area and starts at its top left corner, and
+/// contours are ordered by their first corners row by row. Walls only meet at posts, so blocks of
+/// the wall area never touch at a corner alone, and contours never touch each other.
+pub(super) fn contours(grid: &Grid) -> Vec<Vec<(usize, usize)>> {
+    let (columns, rows) = (2 * grid.width() + 1, 2 * grid.height() + 1);
+    let mut filled = vec![false; columns * rows];
+    for wall in walls(grid) {
+        let (k, l) = match wall {
+            Wall::Post(i, j) => (2 * i, 2 * j),
+            Wall::Horizontal(i, j) => (2 * i + 1, 2 * j),
+            Wall::Vertical(i, j) => (2 * i, 2 * j + 1),
+        };
+        filled[l * columns + k] = true;
+    }
+    let is_filled = |k: usize, l: usize| k < columns && l < rows && filled[l * columns + k];
+
+    // Edges along the sides of filled blocks facing the empty ones, keyed by their starting
+    // corners as `(l, k)`, so that contours are found row by row
+    let mut edges = BTreeMap::new();
+    for l in 0..rows {
+        for k in 0..columns {
+            if !is_filled(k, l) {
+                continue;
+            }
+            if l == 0 || !is_filled(k, l - 1) {
+                edges.insert((l, k), (l, k + 1));
+            }
+            if !is_filled(k + 1, l) {
+                edges.insert((l, k + 1), (l + 1, k + 1));
+            }
+            if !is_filled(k, l + 1) {
+                edges.insert((l + 1, k + 1), (l + 1, k));
+            }
+            if k == 0 || !is_filled(k - 1, l) {
+                edges.insert((l + 1, k), (l, k));
+            }
+        }
+    }
+
+    let mut contours = vec![];
+    while let Some((&start, _)) = edges.first_key_value() {
+        let mut points = vec![start];
+        let mut point = edges.remove(&start).unwrap();
+        while point != start {
+            points.push(point);
+            point = edges.remove(&point).expect("Every contour is closed");
+        }
+
+        // Only the corners where a contour turns are kept
+        let direction =
+            |(l0, k0): (usize, usize), (l1, k1): (usize, usize)| (l1.cmp(&l0), k1.cmp(&k0));
+        let n = points.len();
+        let contour = (0..n)
+            .filter(|&idx| {
+                let (prev, next) = (points[(idx + n - 1) % n], points[(idx + 1) % n]);
+                direction(prev, points[idx]) != direction(points[idx], next)
+            })
+            .map(|idx| (points[idx].1, points[idx].0))
+            .collect();
+        contours.push(contour);
+    }
+
+    contours
+}
+
+/// Indicates if there is a wall along the top side of a cell `(i, j)`, or along the bottom side of
+/// the last row when `j` equals the grid height
+pub(super) fn has_horizontal_wall(grid: &Grid, i: usize, j: usize) -> bool {
+    if j < grid.height() {
+        !grid.is_carved((i, j), Cell::NORTH)
+    } else {
+        !grid.is_carved((i, j - 1), Cell::SOUTH)
+    }
+}
+
+/// Indicates if there is a wall along the left side of a cell `(i, j)`, or along the right side of
+/// the last column when `i` equals the grid width
+pub(super) fn has_vertical_wall(grid: &Grid, i: usize, j: usize) -> bool {
+    if i < grid.width() {
+        !grid.is_carved((i, j), Cell::WEST)
+    } else {
+        !grid.is_carved((i - 1, j), Cell::EAST)
+    }
+}
+
+/// Indicates if a wall joint at lattice coordinates `(i, j)` must display, which is the case when
+/// at least one wall is attached to it
+fn has_post(grid: &Grid, i: usize, j: usize) -> bool {
+    (i > 0 && has_horizontal_wall(grid, i - 1, j))
+        || (i < grid.width() && has_horizontal_wall(grid, i, j))
+        || (j > 0 && has_vertical_wall(grid, i, j - 1))
+        || (j < grid.height() && has_vertical_wall(grid, i, j))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze::{algorithms::Algorithm, RecursiveBacktracking};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn walls_of_grid() {
+        let mut grid = Grid::new(2, 1);
+        grid.carve_passage((0, 0), Cell::EAST).unwrap();
+        grid.carve_opening((1, 0), Cell::EAST).unwrap();
+
+        assert_eq!(
+            walls(&grid),
+            vec![
+                Wall::Post(0, 0),
+                Wall::Post(1, 0),
+                Wall::Post(2, 0),
+                Wall::Post(0, 1),
+                Wall::Post(1, 1),
+                Wall::Post(2, 1),
+                Wall::Horizontal(0, 0),
+                Wall::Horizontal(1, 0),
+                Wall::Horizontal(0, 1),
+                Wall::Horizontal(1, 1),
+                Wall::Vertical(0, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn contours_of_grid() {
+        let mut grid = Grid::new(2, 1);
+        grid.carve_passage((0, 0), Cell::EAST).unwrap();
+
+        assert_eq!(
+            contours(&grid),
+            vec![
+                vec![(0, 0), (5, 0), (5, 3), (0, 3)],
+                vec![(1, 1), (1, 2), (4, 2), (4, 1)],
+            ]
+        );
+    }
+
+    #[test]
+    fn contours_around_openings() {
+        let mut grid = Grid::new(1, 1);
+        grid.carve_opening((0, 0), Cell::NORTH).unwrap();
+
+        assert_eq!(
+            contours(&grid),
+            vec![vec![
+                (0, 0),
+                (1, 0),
+                (1, 2),
+                (2, 2),
+                (2, 0),
+                (3, 0),
+                (3, 3),
+                (0, 3)
+            ],]
+        );
+    }
+
+    #[test]
+    fn contours_of_perfect_maze() {
+        let mut grid = Grid::new(12, 8);
+        RecursiveBacktracking.generate(&mut grid, None, &mut StdRng::seed_from_u64(1));
+
+        // The outer border of the maze and the outline of its passages
+        let contours = contours(&grid);
+        assert_eq!(contours.len(), 2);
+        assert_eq!(contours[0], vec![(0, 0), (25, 0), (25, 17), (0, 17)]);
+        assert!(contours
+            .iter()
+            .flat_map(|contour| (0..contour.len())
+                .map(|idx| (contour[idx], contour[(idx + 1) % contour.len()])))
+            .all(|((k0, l0), (k1, l1))| (k0 == k1) != (l0 == l1)));
+    }
+}
@@ -3,9 +3,28 @@ use std::fmt;
 use bevy::{ecs::component::Component, reflect::Reflect};
 use bitflags::bitflags;
 
+use crate::maze::errors::ParseError;
+
 bitflags! {
     /// Maze Cell defining open passages
-    #[derive(Default, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Component, Reflect)]
+    ///
+    /// A cell is packed into the lower 4 bits of a `u8`, where a set bit is a carved passage:
+    /// `NORTH` is bit 0, `SOUTH` is bit 1, `EAST` is bit 2 and `WEST` is bit 3. The raw bits are
+    /// returned by [bits](Cell::bits), and [from_bits](Cell::from_bits) or
+    /// [try_from](Cell::try_from) turn them back into a cell, rejecting any of the upper 4 bits,
+    /// e.g. for custom serializers or GPU buffers.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::Cell;
+    ///
+    /// let cell = Cell::NORTH | Cell::EAST;
+    ///
+    /// assert_eq!(cell.bits(), 0b0101);
+    /// assert_eq!(Cell::from_bits(0b0101), Some(cell));
+    /// assert!(Cell::try_from(0b1_0000).is_err());
+    /// ```
+    #[derive(Default, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Component, Reflect)]
     #[reflect(opaque)]
     pub struct Cell: u8 {
         /// Has passage to NORTH
@@ -37,6 +56,40 @@ impl Cell {
     pub const fn to_bits(&self) -> u8 {
         self.bits()
     }
+
+    /// Returns the number of carved passages
+    pub const fn passage_count(&self) -> usize {
+        self.bits().count_ones() as usize
+    }
+
+    /// Returns a cell with its carved passages rotated 90° clockwise, i.e. `NORTH` becomes `EAST`,
+    /// `EAST` becomes `SOUTH`, `SOUTH` becomes `WEST` and `WEST` becomes `NORTH`
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::Cell;
+    ///
+    /// let cell = Cell::NORTH | Cell::WEST;
+    ///
+    /// assert_eq!(cell.rotated_cw(), Cell::NORTH | Cell::EAST);
+    /// assert_eq!(cell.rotated_cw().rotated_cw().rotated_cw().rotated_cw(), cell);
+    /// ```
+    pub const fn rotated_cw(&self) -> Cell {
+        let mut bits = 0;
+        if self.contains(Cell::NORTH) {
+            bits |= Cell::EAST.bits();
+        }
+        if self.contains(Cell::EAST) {
+            bits |= Cell::SOUTH.bits();
+        }
+        if self.contains(Cell::SOUTH) {
+            bits |= Cell::WEST.bits();
+        }
+        if self.contains(Cell::WEST) {
+            bits |= Cell::NORTH.bits();
+        }
+        Cell::from_bits_retain(bits)
+    }
 }
 
 impl fmt::Display for Cell {
@@ -56,13 +109,31 @@ impl fmt::Display for Cell {
     }
 }
 
-#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+impl TryFrom<u8> for Cell {
+    type Error = ParseError;
+
+    /// Converts raw bits into a cell, or returns a [ParseError] if any unknown bit is set
+    fn try_from(bits: u8) -> Result<Self, Self::Error> {
+        Cell::from_bits(bits).ok_or_else(|| {
+            ParseError::reason(format!(
+                "Unknown bits {:#010b} of a cell, expected only the lower 4 bits",
+                bits & !Cell::all().bits()
+            ))
+        })
+    }
+}
+
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct CellStatus {
     visited: bool,
     marked: bool,
 }
 
 impl CellStatus {
+    pub(crate) const fn new(visited: bool, marked: bool) -> CellStatus {
+        CellStatus { visited, marked }
+    }
+
     pub const fn visited(&self) -> bool {
         self.visited
     }
@@ -71,16 +142,18 @@ impl CellStatus {
         self.marked
     }
 
-    pub fn visit(&mut self) {
+    pub const fn visit(&mut self) {
         self.visited = true;
     }
 
-    pub fn mark(&mut self) {
+    pub const fn mark(&mut self) {
         self.marked = true;
     }
 }
 #[cfg(test)]
 mod tests {
+    use std::collections::HashSet;
+
     use super::Cell;
 
     #[test]
@@ -229,4 +302,68 @@ mod tests {
 
         assert_eq!(cell.to_string(), "SW");
     }
+
+    #[test]
+    fn hash_cells() {
+        let cells: HashSet<Cell> = [Cell::NORTH, Cell::NORTH | Cell::EAST, Cell::NORTH]
+            .into_iter()
+            .collect();
+
+        assert_eq!(cells.len(), 2);
+        assert!(cells.contains(&(Cell::EAST | Cell::NORTH)));
+    }
+
+    #[test]
+    fn try_from_bits() {
+        for bits in 0..=0b1111 {
+            assert_eq!(Cell::try_from(bits).unwrap().bits(), bits);
+        }
+
+        let error = Cell::try_from(0b1010_0001).unwrap_err();
+        assert_eq!(
+            error.reason,
+            "Unknown bits 0b10100000 of a cell, expected only the lower 4 bits"
+        );
+    }
+
+    #[test]
+    fn passage_count() {
+        assert_eq!(Cell::empty().passage_count(), 0);
+        assert_eq!(Cell::NORTH.passage_count(), 1);
+        assert_eq!((Cell::SOUTH | Cell::WEST).passage_count(), 2);
+        assert_eq!(Cell::all().passage_count(), 4);
+    }
+
+    #[test]
+    fn rotated_cw_single_passages() {
+        assert_eq!(Cell::NORTH.rotated_cw(), Cell::EAST);
+        assert_eq!(Cell::EAST.rotated_cw(), Cell::SOUTH);
+        assert_eq!(Cell::SOUTH.rotated_cw(), Cell::WEST);
+        assert_eq!(Cell::WEST.rotated_cw(), Cell::NORTH);
+    }
+
+    #[test]
+    fn rotated_cw_keeps_passage_count() {
+        for bits in 0..=0b1111 {
+            let cell = Cell::from_bits(bits).unwrap();
+
+            assert_eq!(cell.rotated_cw().passage_count(), cell.passage_count());
+        }
+        assert_eq!(Cell::empty().rotated_cw(), Cell::empty());
+        assert_eq!(Cell::all().rotated_cw(), Cell::all());
+        assert_eq!(
+            (Cell::NORTH | Cell::SOUTH).rotated_cw(),
+            Cell::EAST | Cell::WEST
+        );
+    }
+
+    #[test]
+    fn four_rotations_cw_return_original_cell() {
+        for bits in 0..=0b1111 {
+            let cell = Cell::from_bits(bits).unwrap();
+            let rotated = cell.rotated_cw().rotated_cw().rotated_cw().rotated_cw();
+
+            assert_eq!(rotated, cell);
+        }
+    }
 }
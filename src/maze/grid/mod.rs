@@ -1,83 +1,422 @@
 pub mod cell;
-use self::cell::CellStatus;
+pub mod pole;
+mod storage;
+use self::pole::Pole;
+use self::storage::Storage;
 
 use super::errors::TransitError;
+use super::formatters::AsciiNarrow;
+use super::observer::{GenerationObserver, ObserverSlot};
 use crate::utils::types::Coords;
 use cell::Cell;
 use std::fmt;
 
 type TransitResult<T> = Result<T, TransitError>;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// A rectangular grid of maze cells
+///
+/// Cells are stored row by row, so that a cell at `(x, y)` coords has the `y * width + x` index.
+/// The origin of a grid is its top left corner. A grid is what algorithms carve passages in and
+/// what formatters convert into other data types.
+///
+/// A grid created with [Grid::new_packed] keeps its cells in bits rather than bytes, which takes
+/// about a sixth of the memory at the cost of slower access, e.g. for very large mazes. Both
+/// representations expose the same API, and grids with the same passages are equal regardless of
+/// their representation.
+///
+/// A grid created with [Grid::new_torus] wraps around both axes, see there for details.
+#[derive(Debug, Clone)]
 pub struct Grid {
     width: usize,
     height: usize,
-    pub(crate) cells: Vec<Cell>,
-    cell_statuses: Vec<CellStatus>,
+    torus: bool,
+    pub(crate) storage: Storage,
+    observer: ObserverSlot,
 }
 
 impl Grid {
+    /// Returns a new instance of a grid with a given width and height where all the walls are
+    /// standing
     pub fn new(width: usize, height: usize) -> Grid {
         Grid {
             width,
             height,
-            cells: vec![Cell::default(); width * height],
-            cell_statuses: vec![CellStatus::default(); width * height],
+            torus: false,
+            storage: Storage::new(width, height),
+            observer: ObserverSlot::default(),
         }
     }
 
+    /// Returns a new instance of a grid with a given width and height where all the walls are
+    /// standing, wrapping around both axes like a torus
+    ///
+    /// The cells on the western edge are the neighbours of the cells on the eastern edge in the
+    /// same row, and the cells on the northern edge are the neighbours of the cells on the southern
+    /// edge in the same column. Thus, carving off any edge carves a passage to the opposite side,
+    /// and algorithms, solvers and connectivity checks going through
+    /// [get_next_cell_coords](Grid::get_next_cell_coords) treat such neighbours as any other ones.
+    /// There are no outer walls to [open](Grid::carve_opening), except along an axis with a single
+    /// cell, which has nothing to wrap around. Formatters render a torus flat, with the wrapping
+    /// passages as gaps in the edges.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::{Cell, Grid};
+    ///
+    /// let mut grid = Grid::new_torus(4, 3);
+    ///
+    /// assert_eq!(grid.carve_passage((0, 0), Cell::WEST).unwrap(), (3, 0));
+    /// assert_eq!(grid.carve_passage((1, 2), Cell::SOUTH).unwrap(), (1, 0));
+    /// assert!(grid[(3, 0)].contains(Cell::EAST));
+    /// assert!(grid.boundary_cells().is_empty());
+    /// ```
+    pub fn new_torus(width: usize, height: usize) -> Grid {
+        Grid {
+            torus: true,
+            ..Grid::new(width, height)
+        }
+    }
+
+    /// Returns a new instance of a grid with a given width and height where all the walls are
+    /// standing, packing its cells into bits
+    ///
+    /// Only eastern and southern passages are stored for every cell, as northern and western ones
+    /// are the passages of neighbours. Along with visited and marked flags, a cell takes 4 bits
+    /// instead of 3 bytes.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::{Cell, Grid};
+    ///
+    /// let mut grid = Grid::new_packed(3, 3);
+    /// grid.carve_passage((1, 1), Cell::NORTH).unwrap();
+    ///
+    /// assert_eq!(grid[(1, 0)], Cell::SOUTH);
+    /// assert!(grid.is_packed());
+    /// ```
+    pub fn new_packed(width: usize, height: usize) -> Grid {
+        Grid {
+            width,
+            height,
+            torus: false,
+            storage: Storage::packed(width, height),
+            observer: ObserverSlot::default(),
+        }
+    }
+
+    /// Returns a new instance of a grid with a given width and height where passages are carved
+    /// between given pairs of cells, e.g. to import a maze designed elsewhere
+    ///
+    /// Edges are undirected, so an edge and its reverse carve the same passage, and duplicates
+    /// carve it only once. Returns a [TransitError] if cells of an edge are out of the grid bounds
+    /// or not orthogonally adjacent.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::{Cell, Grid};
+    ///
+    /// let grid = Grid::from_edges(2, 2, &[((0, 0), (1, 0)), ((1, 1), (1, 0))]).unwrap();
+    ///
+    /// assert_eq!(grid[(1, 0)], Cell::WEST | Cell::SOUTH);
+    /// assert!(Grid::from_edges(2, 2, &[((0, 0), (1, 1))]).is_err());
+    /// ```
+    pub fn from_edges(
+        width: usize,
+        height: usize,
+        edges: &[(Coords, Coords)],
+    ) -> TransitResult<Grid> {
+        let mut grid = Grid::new(width, height);
+        grid.carve_edges(edges)?;
+
+        Ok(grid)
+    }
+
+    /// Carves passages between given pairs of adjacent cells, see [from_edges](Grid::from_edges)
+    pub(crate) fn carve_edges(&mut self, edges: &[(Coords, Coords)]) -> TransitResult<()> {
+        for &(from, to) in edges {
+            for coords in [from, to] {
+                if coords.0 >= self.width || coords.1 >= self.height {
+                    return Err(TransitError::reason(
+                        format!("Cell is out of the {}x{} grid", self.width, self.height),
+                        coords,
+                    ));
+                }
+            }
+
+            let direction = Pole::all().into_iter().map(Cell::from).find(|dir| {
+                self.get_next_cell_coords(from, *dir)
+                    .is_ok_and(|coords| coords == to)
+            });
+            let Some(direction) = direction else {
+                return Err(TransitError::reason(
+                    format!("Cell is not adjacent to the ({}, {}) cell", to.0, to.1),
+                    from,
+                ));
+            };
+
+            self.carve_passage(from, direction)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns `true` if cells of the grid are packed into bits. Otherwise, returns `false`
+    pub const fn is_packed(&self) -> bool {
+        self.storage.is_packed()
+    }
+
+    /// Returns `true` if the grid wraps around both axes like a torus. Otherwise, returns `false`
+    pub const fn is_torus(&self) -> bool {
+        self.torus
+    }
+
+    /// Returns the number of rows of the grid
     pub const fn height(&self) -> usize {
         self.height
     }
 
+    /// Returns the number of columns of the grid
     pub const fn width(&self) -> usize {
         self.width
     }
 
+    /// Returns an iterator over the columns of the grid from West to East, where each column is an
+    /// iterator over its cells from North to South
+    ///
+    /// Cells are stored row by row, so the columns are a view over the grid that doesn't copy or
+    /// allocate anything. See [OrthogonalMaze::iter](crate::maze::OrthogonalMaze::iter) for the
+    /// row-major order.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::{Cell, Grid};
+    ///
+    /// let mut grid = Grid::new(3, 2);
+    /// grid.carve_passage((2, 0), Cell::SOUTH).unwrap();
+    ///
+    /// let last: Vec<Cell> = grid.columns().last().unwrap().copied().collect();
+    /// assert_eq!(last, vec![Cell::SOUTH, Cell::NORTH]);
+    /// ```
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &Cell> + '_> + '_ {
+        (0..self.width).map(move |x| (0..self.height).map(move |y| &self[(x, y)]))
+    }
+
+    /// Changes the size of the grid, keeping the cells of its top left part which fits into both
+    /// the old and the new size, e.g. to enlarge the canvas of a maze in an editor
+    ///
+    /// Growing the grid adds new cells with all their walls standing, and shrinking it drops the
+    /// cells out of the new size. Passages between the kept cells stay as they are, while passages
+    /// leading out of them to the dropped cells become walls. Openings in the outer walls are kept
+    /// as long as they are still in the outer walls of the resized grid, and walled otherwise, so
+    /// the kept cells are only connected to the new ones once passages are carved to them. The
+    /// grid keeps its representation, and a [torus](Grid::new_torus) keeps only the passages
+    /// wrapping around its new edges.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::{Cell, Grid};
+    ///
+    /// let mut grid = Grid::new(2, 2);
+    /// grid.carve_passage((0, 0), Cell::EAST).unwrap();
+    /// grid.carve_passage((1, 0), Cell::SOUTH).unwrap();
+    ///
+    /// grid.resize(3, 3);
+    /// assert_eq!(grid[(1, 0)], Cell::WEST | Cell::SOUTH);
+    /// assert_eq!(grid[(2, 2)], Cell::empty());
+    ///
+    /// grid.resize(1, 2);
+    /// assert_eq!(grid[(0, 0)], Cell::empty());
+    /// ```
+    pub fn resize(&mut self, width: usize, height: usize) {
+        let mut resized = Grid {
+            width,
+            height,
+            torus: self.torus,
+            storage: if self.is_packed() {
+                Storage::packed(width, height)
+            } else {
+                Storage::new(width, height)
+            },
+            observer: ObserverSlot::default(),
+        };
+
+        for y in 0..self.height.min(height) {
+            for x in 0..self.width.min(width) {
+                for direction in Pole::all().map(Cell::from) {
+                    if !self.is_carved((x, y), direction) {
+                        continue;
+                    }
+
+                    match (
+                        self.get_next_cell_coords((x, y), direction),
+                        resized.get_next_cell_coords((x, y), direction),
+                    ) {
+                        // Passages going the other way are carved from the cells they lead to
+                        (Ok(next), Ok(resized_next))
+                            if next == resized_next
+                                && matches!(direction, Cell::EAST | Cell::SOUTH) =>
+                        {
+                            resized.carve_passage((x, y), direction).unwrap();
+                        }
+                        (Err(_), Err(_)) => resized.carve_opening((x, y), direction).unwrap(),
+                        _ => {}
+                    }
+                }
+                if self.is_cell_marked((x, y)) {
+                    resized.mark_cell((x, y));
+                }
+            }
+        }
+
+        resized.observer = std::mem::take(&mut self.observer);
+        *self = resized;
+    }
+
+    /// Rebuilds all the walls and forgets visited and marked cells, reusing the allocated cells
+    pub(crate) fn reset(&mut self) {
+        self.storage.reset();
+    }
+
+    /// Sets an observer notified of carved passages and returns the previous one
+    pub(crate) fn replace_observer(
+        &mut self,
+        observer: Option<Box<dyn GenerationObserver>>,
+    ) -> Option<Box<dyn GenerationObserver>> {
+        std::mem::replace(&mut self.observer.0, observer)
+    }
+
+    /// Returns coords of the geometric center cell of the grid
+    ///
+    /// When a dimension is even, two cells are equally central along it, and the one closer to the
+    /// origin is returned, e.g. `(1, 1)` for a 4x4 grid.
+    pub const fn center(&self) -> Coords {
+        (
+            self.width.saturating_sub(1) / 2,
+            self.height.saturating_sub(1) / 2,
+        )
+    }
+
+    /// Returns coords of the corner cells of the grid, i.e. the top left, top right, bottom left and
+    /// bottom right ones in this order
+    ///
+    /// The corners of a grid that is a single row or column wide repeat, e.g. all of them are the
+    /// same cell of a 1x1 grid. An empty grid has no cells, so all of its corners are `(0, 0)`.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::Grid;
+    ///
+    /// assert_eq!(Grid::new(4, 3).corners(), [(0, 0), (3, 0), (0, 2), (3, 2)]);
+    /// assert_eq!(Grid::new(1, 3).corners(), [(0, 0), (0, 0), (0, 2), (0, 2)]);
+    /// ```
+    pub const fn corners(&self) -> [Coords; 4] {
+        let (right, bottom) = (self.width.saturating_sub(1), self.height.saturating_sub(1));
+        [(0, 0), (right, 0), (0, bottom), (right, bottom)]
+    }
+
+    /// Returns the row-major index of a given cell, i.e. `y * width + x`, or `None` if the cell is
+    /// out of the grid
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::Grid;
+    ///
+    /// let grid = Grid::new(4, 3);
+    ///
+    /// assert_eq!(grid.index((1, 2)), Some(9));
+    /// assert_eq!(grid.coords(9), Some((1, 2)));
+    /// assert_eq!(grid.index((4, 0)), None);
+    /// assert_eq!(grid.coords(12), None);
+    /// ```
+    pub const fn index(&self, (x, y): Coords) -> Option<usize> {
+        if x < self.width && y < self.height {
+            Some(y * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    /// Returns coords of a cell at a given row-major index, or `None` if the index is out of the
+    /// grid. See [Grid::index] for an example.
+    pub const fn coords(&self, index: usize) -> Option<Coords> {
+        if index < self.width * self.height {
+            Some((index % self.width, index / self.width))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the pole of the wall between two adjacent cells from the side of the first cell, or
+    /// `None` if the cells aren't adjacent or any of them is out of the grid
+    ///
+    /// The cells on the opposite edges of a [torus](Grid::new_torus) are adjacent too.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::{Grid, Pole};
+    ///
+    /// let grid = Grid::new(4, 3);
+    ///
+    /// assert_eq!(grid.shared_wall((1, 1), (1, 0)), Some(Pole::N));
+    /// assert_eq!(grid.shared_wall((1, 1), (2, 1)), Some(Pole::E));
+    /// assert_eq!(grid.shared_wall((1, 1), (2, 2)), None);
+    /// ```
+    pub const fn shared_wall(&self, a: Coords, b: Coords) -> Option<Pole> {
+        if self.index(a).is_none() || self.index(b).is_none() {
+            return None;
+        }
+
+        // Cells on the opposite edges of a torus are adjacent across the edges
+        let (width, height) = (self.width as isize, self.height as isize);
+        let (wraps_x, wraps_y) = (self.torus && width > 1, self.torus && height > 1);
+        match (b.0 as isize - a.0 as isize, b.1 as isize - a.1 as isize) {
+            (0, -1) => Some(Pole::N),
+            (0, 1) => Some(Pole::S),
+            (-1, 0) => Some(Pole::W),
+            (1, 0) => Some(Pole::E),
+            (0, dy) if wraps_y && dy == height - 1 => Some(Pole::N),
+            (0, dy) if wraps_y && dy == 1 - height => Some(Pole::S),
+            (dx, 0) if wraps_x && dx == width - 1 => Some(Pole::W),
+            (dx, 0) if wraps_x && dx == 1 - width => Some(Pole::E),
+            _ => None,
+        }
+    }
+
+    /// Marks a cell, e.g. to track a frontier of a generating algorithm
     pub fn mark_cell(&mut self, coords: Coords) {
-        self.get_cell_status_mut(coords).mark()
+        self.storage.mark(coords)
     }
 
+    /// Returns `true` if a passage was carved to or from a given cell. Otherwise, returns `false`
     pub fn is_cell_visited(&self, coords: Coords) -> bool {
-        self.get_cell_status(coords).visited()
+        self.storage.status(coords).visited()
     }
 
+    /// Returns `true` if a given cell is marked. Otherwise, returns `false`
     pub fn is_cell_marked(&self, coords: Coords) -> bool {
-        self.get_cell_status(coords).marked()
-    }
-
-    pub fn get_cell_status(&self, coords: Coords) -> CellStatus {
-        let (x, y) = coords;
-        self.cell_statuses[y * self.width + x]
+        self.storage.status(coords).marked()
     }
 
+    /// Returns `true` if a given cell has a passage carved in a given direction. Otherwise, returns
+    /// `false`
     pub fn is_carved(&self, coords: Coords, direction: Cell) -> bool {
-        let (x, y) = coords;
-        self.cells[y * self.width + x].contains(direction)
+        self.storage.cell(coords).contains(direction)
     }
 
+    /// Carves a passage from a given cell to its neighbour in a given direction and returns the
+    /// neighbour coords
+    ///
+    /// Returns a [TransitError] if the neighbour is out of the grid bounds.
     pub fn carve_passage(&mut self, coords: Coords, direction: Cell) -> TransitResult<Coords> {
-        let (x, y) = coords;
         let (nx, ny) = self.get_next_cell_coords(coords, direction)?;
 
-        match direction {
-            Cell::NORTH => {
-                self.cells[y * self.width + x] |= Cell::NORTH;
-                self.cells[ny * self.width + nx] |= Cell::SOUTH;
-            }
-            Cell::SOUTH => {
-                self.cells[y * self.width + x] |= Cell::SOUTH;
-                self.cells[ny * self.width + nx] |= Cell::NORTH;
-            }
-            Cell::EAST => {
-                self.cells[y * self.width + x] |= Cell::EAST;
-                self.cells[ny * self.width + nx] |= Cell::WEST;
-            }
-            Cell::WEST => {
-                self.cells[y * self.width + x] |= Cell::WEST;
-                self.cells[ny * self.width + nx] |= Cell::EAST;
-            }
-            _ => (),
+        if let Some(pole) = Pole::from_cell(direction) {
+            self.storage.set(coords, direction, true);
+            self.storage
+                .set((nx, ny), Cell::from(pole.opposite()), true);
+        }
+        if let Some(observer) = self.observer.0.as_mut() {
+            observer.on_carve(coords, (nx, ny));
         }
 
         self.visit_cell(coords);
@@ -86,30 +425,251 @@ impl Grid {
         Ok((nx, ny))
     }
 
+    /// Returns a [TransitError] if given coords are out of the grid bounds
+    fn check_bounds(&self, coords: Coords) -> TransitResult<()> {
+        if coords.0 >= self.width || coords.1 >= self.height {
+            return Err(TransitError::reason(
+                format!("Cell is out of the {}x{} grid", self.width, self.height),
+                coords,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Removes a passage between a given cell and its neighbour in a given direction, i.e. builds
+    /// a wall between them, and returns the neighbour coords
+    pub(crate) fn remove_passage(
+        &mut self,
+        coords: Coords,
+        direction: Cell,
+    ) -> TransitResult<Coords> {
+        let (nx, ny) = self.get_next_cell_coords(coords, direction)?;
+
+        if let Some(pole) = Pole::from_cell(direction) {
+            self.storage.set(coords, direction, false);
+            self.storage
+                .set((nx, ny), Cell::from(pole.opposite()), false);
+        }
+
+        Ok((nx, ny))
+    }
+
+    /// Carves an opening through the outer wall of a boundary cell, e.g. an entrance or an exit
+    pub fn carve_opening(&mut self, coords: Coords, direction: Cell) -> TransitResult<()> {
+        if Pole::from_cell(direction).is_none() || self.validate_transit(coords, direction).is_ok()
+        {
+            return Err(TransitError::reason(
+                "Only an outer wall of the grid can be opened",
+                coords,
+            ));
+        }
+
+        self.storage.set(coords, direction, true);
+
+        Ok(())
+    }
+
+    /// Flips a wall of a given cell towards a given pole, i.e. carves a passage through a standing
+    /// wall or builds a wall across a carved passage, and returns `true` if the wall stood before
+    ///
+    /// The matching wall of the neighbour is flipped too, so both cells always agree on the
+    /// passage between them. An outer wall of the grid is flipped as an
+    /// [opening](Grid::carve_opening). Flipping the same wall twice restores the grid, so an editor
+    /// can undo changes by keeping a stack of the flipped `(coords, pole)` pairs.
+    ///
+    /// Returns a [TransitError] if the cell is out of the grid bounds.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let mut grid = Grid::new(2, 2);
+    /// let mut undo = vec![];
+    ///
+    /// assert!(grid.toggle_wall((0, 0), Pole::E).unwrap());
+    /// undo.push(((0, 0), Pole::E));
+    /// assert!(grid[(1, 0)].contains(Cell::WEST));
+    ///
+    /// let (coords, pole) = undo.pop().unwrap();
+    /// assert!(!grid.toggle_wall(coords, pole).unwrap());
+    /// assert_eq!(grid[(0, 0)], Cell::empty());
+    /// assert_eq!(grid[(1, 0)], Cell::empty());
+    /// ```
+    pub fn toggle_wall(&mut self, coords: Coords, pole: Pole) -> TransitResult<bool> {
+        self.check_bounds(coords)?;
+
+        let direction = Cell::from(pole);
+        let standing = !self.is_carved(coords, direction);
+        if self.validate_transit(coords, direction).is_err() {
+            self.storage.set(coords, direction, standing);
+        } else if standing {
+            self.carve_passage(coords, direction)?;
+        } else {
+            self.remove_passage(coords, direction)?;
+        }
+
+        Ok(standing)
+    }
+
+    /// Carves passages from a given cell to all of its neighbours, e.g. to build a room cell by
+    /// cell
+    ///
+    /// The neighbours get the matching passages too. Only the walls between cells are carved, so
+    /// the outer walls of a boundary cell keep standing.
+    ///
+    /// Returns a [TransitError] if the cell is out of the grid bounds.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let mut grid = Grid::new(3, 3);
+    /// grid.open_cell((1, 1)).unwrap();
+    /// grid.open_cell((0, 0)).unwrap();
+    ///
+    /// assert_eq!(grid[(1, 1)], Cell::all());
+    /// assert_eq!(grid[(0, 0)], Cell::EAST | Cell::SOUTH);
+    /// assert_eq!(grid[(1, 0)], Cell::WEST | Cell::SOUTH);
+    /// ```
+    pub fn open_cell(&mut self, coords: Coords) -> TransitResult<()> {
+        self.check_bounds(coords)?;
+
+        for direction in Pole::all().map(Cell::from) {
+            if self.validate_transit(coords, direction).is_ok() {
+                self.carve_passage(coords, direction)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Builds all the walls of a given cell, i.e. removes its passages to the neighbours and its
+    /// openings in the outer walls, so that the cell is walled off entirely
+    ///
+    /// The neighbours lose the matching passages too.
+    ///
+    /// Returns a [TransitError] if the cell is out of the grid bounds.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let mut grid = Grid::new(2, 1);
+    /// grid.carve_passage((0, 0), Cell::EAST).unwrap();
+    /// grid.carve_opening((1, 0), Cell::EAST).unwrap();
+    ///
+    /// grid.close_cell((1, 0)).unwrap();
+    ///
+    /// assert_eq!(grid[(0, 0)], Cell::empty());
+    /// assert_eq!(grid[(1, 0)], Cell::empty());
+    /// ```
+    pub fn close_cell(&mut self, coords: Coords) -> TransitResult<()> {
+        self.check_bounds(coords)?;
+
+        for direction in Pole::all().map(Cell::from) {
+            if self.validate_transit(coords, direction).is_ok() {
+                self.remove_passage(coords, direction)?;
+            } else {
+                self.storage.set(coords, direction, false);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the cells on the outer edge of the grid in clockwise order starting top-left
+    ///
+    /// Every cell appears once, including the corners and the cells of a grid that is a single row
+    /// or column wide. A [torus](Grid::new_torus) has no outer edges unless it is a single row or
+    /// column wide. The cells can be opened with [boundary_poles](Grid::boundary_poles) and
+    /// [carve_opening](Grid::carve_opening), e.g. for custom entrances and exits.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let mut maze = OrthogonalMaze::new(4, 3);
+    /// let grid = maze.get_grid_mut();
+    /// let boundary = grid.boundary_cells();
+    ///
+    /// assert_eq!(boundary.len(), 10);
+    /// assert_eq!(boundary[..5], [(0, 0), (1, 0), (2, 0), (3, 0), (3, 1)]);
+    ///
+    /// // Opens an exit in the bottom right corner
+    /// let exit = boundary[5];
+    /// let pole = grid.boundary_poles(exit)[0];
+    /// grid.carve_opening(exit, Cell::from(pole)).unwrap();
+    /// assert!(maze[(3, 2)].contains(Cell::SOUTH));
+    /// ```
+    pub fn boundary_cells(&self) -> Vec<Coords> {
+        let (width, height) = (self.width, self.height);
+        if width == 0 || height == 0 {
+            return vec![];
+        }
+
+        let mut cells: Vec<Coords> = (0..width).map(|x| (x, 0)).collect();
+        cells.extend((1..height).map(|y| (width - 1, y)));
+        if height > 1 {
+            cells.extend((0..width - 1).rev().map(|x| (x, height - 1)));
+        }
+        if width > 1 {
+            cells.extend((1..height - 1).rev().map(|y| (0, y)));
+        }
+
+        // The edges of a torus wrap around, so only the ones along a single cell face the outside
+        if self.torus {
+            cells.retain(|coords| !self.boundary_poles(*coords).is_empty());
+        }
+
+        cells
+    }
+
+    /// Returns the poles of a cell that face the outside of the grid
+    pub fn boundary_poles(&self, coords: Coords) -> Vec<Pole> {
+        Pole::all()
+            .into_iter()
+            .filter(|pole| self.validate_transit(coords, Cell::from(*pole)).is_err())
+            .collect()
+    }
+
+    /// Returns coords of the neighbour of a given cell in a given direction
+    ///
+    /// The neighbours of the cells on the edges of a [torus](Grid::new_torus) are on the opposite
+    /// edges. Returns a [TransitError] if the neighbour is out of the grid bounds.
     pub fn get_next_cell_coords(&self, coords: Coords, direction: Cell) -> TransitResult<Coords> {
         self.validate_transit(coords, direction)?;
 
-        let (x, y) = coords;
-        let (nx, ny) = match direction {
-            Cell::NORTH => (x, y - 1),
-            Cell::SOUTH => (x, y + 1),
-            Cell::WEST => (x - 1, y),
-            Cell::EAST => (x + 1, y),
-            _ => (x, y),
+        // The transit is validated, so moving off an edge only happens around a torus
+        let step = |value: usize, delta: i32, len: usize| match delta {
+            -1 if value == 0 => len - 1,
+            1 if value + 1 == len => 0,
+            _ => value.wrapping_add_signed(delta as isize),
         };
+
+        let (x, y) = coords;
+        let (nx, ny) = Pole::from_cell(direction).map_or((x, y), |pole| {
+            let (dx, dy) = pole.offset();
+            (step(x, dx, self.width), step(y, dy, self.height))
+        });
         Ok((nx, ny))
     }
 
     fn visit_cell(&mut self, coords: Coords) {
-        self.get_cell_status_mut(coords).visit()
-    }
-
-    fn get_cell_status_mut(&mut self, coords: Coords) -> &mut CellStatus {
-        let (x, y) = coords;
-        &mut self.cell_statuses[y * self.width + x]
+        self.storage.visit(coords)
     }
 
     fn validate_transit(&self, coords: Coords, direction: Cell) -> TransitResult<()> {
+        // An axis of a torus with a single cell has no other side to wrap around to
+        if self.torus
+            && match direction {
+                Cell::NORTH | Cell::SOUTH => self.height > 1,
+                _ => self.width > 1,
+            }
+        {
+            return Ok(());
+        }
+
         let (x, y) = coords;
         let reason = match direction {
             Cell::NORTH if y < 1 => Some("First row in the grid cannot go North"),
@@ -134,46 +694,502 @@ impl std::ops::Index<Coords> for Grid {
     type Output = Cell;
 
     fn index(&self, index: Coords) -> &Self::Output {
-        let width = self.width();
-        let idx = index.1 * width + index.0;
-        self.cells
-            .get(idx)
-            .unwrap_or_else(|| panic!("Cell at {:?} doesn't exist.", &index))
+        if index.0 >= self.width || index.1 >= self.height {
+            panic!("Cell at {:?} doesn't exist.", &index);
+        }
+        self.storage.cell(index)
+    }
+}
+
+impl PartialEq for Grid {
+    /// Compares sizes, wrapping, passages and statuses of cells, regardless of the grid
+    /// representations
+    fn eq(&self, other: &Self) -> bool {
+        if (self.width, self.height, self.torus) != (other.width, other.height, other.torus) {
+            return false;
+        }
+
+        (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+            .all(|coords| {
+                self.storage.cell(coords) == other.storage.cell(coords)
+                    && self.storage.status(coords) == other.storage.status(coords)
+            })
+    }
+}
+
+impl Eq for Grid {}
+
+impl std::hash::Hash for Grid {
+    /// Hashes sizes, wrapping, passages and statuses of cells, so that equal grids of different
+    /// representations have equal hashes
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        (self.width, self.height, self.torus).hash(state);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                self.storage.cell((x, y)).hash(state);
+                self.storage.status((x, y)).hash(state);
+            }
+        }
     }
 }
 
 impl fmt::Display for Grid {
+    /// Writes a grid formatted as [AsciiNarrow] into a buffer
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let top_border = "_".repeat(self.width * 2 - 1);
+        AsciiNarrow.write_grid(self, f)
+    }
+}
 
-        writeln!(f, " {} ", top_border)?;
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
 
-        for y in 0..self.height {
-            write!(f, "|")?; // display left border
+    use rand::SeedableRng;
 
-            for x in 0..self.width {
-                if self.is_carved((x, y), Cell::SOUTH) {
-                    write!(f, " ")?;
-                } else {
-                    write!(f, "_")?;
-                }
+    use super::*;
+    use crate::maze::{algorithms::Algorithm, RecursiveBacktracking};
 
-                if self.is_carved((x, y), Cell::EAST) {
-                    if self.is_carved((x, y), Cell::SOUTH)
-                        || self.is_carved((x + 1, y), Cell::SOUTH)
-                    {
-                        write!(f, " ")?;
-                    } else {
-                        write!(f, "_")?;
-                    }
-                } else {
-                    write!(f, "|")?;
-                }
+    #[test]
+    fn index_and_coords() {
+        let grid = Grid::new(5, 3);
+
+        for index in 0..15 {
+            let coords = grid.coords(index).unwrap();
+            assert_eq!(grid.index(coords), Some(index));
+        }
+        assert_eq!(grid.index((0, 0)), Some(0));
+        assert_eq!(grid.index((4, 2)), Some(14));
+        assert_eq!(grid.index((5, 0)), None);
+        assert_eq!(grid.index((0, 3)), None);
+        assert_eq!(grid.coords(15), None);
+        assert_eq!(Grid::new(0, 0).coords(0), None);
+    }
+
+    #[test]
+    fn torus_neighbours() {
+        let grid = Grid::new_torus(4, 3);
+
+        assert_eq!(
+            grid.get_next_cell_coords((0, 1), Cell::WEST).unwrap(),
+            (3, 1)
+        );
+        assert_eq!(
+            grid.get_next_cell_coords((3, 1), Cell::EAST).unwrap(),
+            (0, 1)
+        );
+        assert_eq!(
+            grid.get_next_cell_coords((2, 0), Cell::NORTH).unwrap(),
+            (2, 2)
+        );
+        assert_eq!(
+            grid.get_next_cell_coords((2, 2), Cell::SOUTH).unwrap(),
+            (2, 0)
+        );
+        assert_eq!(
+            grid.get_next_cell_coords((1, 1), Cell::EAST).unwrap(),
+            (2, 1)
+        );
+        assert!(grid.is_torus());
+        assert!(!Grid::new(4, 3).is_torus());
+        assert!(grid.boundary_cells().is_empty());
+        assert!(grid.boundary_poles((0, 0)).is_empty());
+        assert_ne!(grid, Grid::new(4, 3));
+
+        // An axis with a single cell keeps its outer walls
+        let mut row = Grid::new_torus(3, 1);
+        assert_eq!(
+            row.get_next_cell_coords((0, 0), Cell::WEST).unwrap(),
+            (2, 0)
+        );
+        assert!(row.get_next_cell_coords((0, 0), Cell::NORTH).is_err());
+        assert_eq!(row.boundary_cells(), vec![(0, 0), (1, 0), (2, 0)]);
+        assert!(row.carve_opening((1, 0), Cell::SOUTH).is_ok());
+        assert!(row.carve_opening((0, 0), Cell::WEST).is_err());
+    }
+
+    #[test]
+    fn carve_around_torus() {
+        let mut grid = Grid::new_torus(3, 2);
+
+        grid.carve_passage((2, 1), Cell::EAST).unwrap();
+        grid.carve_passage((2, 1), Cell::SOUTH).unwrap();
+        assert_eq!(grid[(2, 1)], Cell::EAST | Cell::SOUTH);
+        assert_eq!(grid[(0, 1)], Cell::WEST);
+        assert_eq!(grid[(2, 0)], Cell::NORTH);
+
+        assert!(!grid.toggle_wall((0, 1), Pole::W).unwrap());
+        assert_eq!(grid[(2, 1)], Cell::SOUTH);
+        grid.close_cell((2, 0)).unwrap();
+        assert_eq!(grid[(2, 1)], Cell::empty());
+    }
+
+    #[test]
+    fn shared_wall() {
+        let grid = Grid::new(3, 3);
+
+        assert_eq!(grid.shared_wall((1, 1), (1, 0)), Some(Pole::N));
+        assert_eq!(grid.shared_wall((1, 1), (1, 2)), Some(Pole::S));
+        assert_eq!(grid.shared_wall((1, 1), (0, 1)), Some(Pole::W));
+        assert_eq!(grid.shared_wall((1, 1), (2, 1)), Some(Pole::E));
+        assert_eq!(grid.shared_wall((1, 0), (1, 1)), Some(Pole::S));
+        // Not adjacent cells, including the same cell, diagonal ones and ones out of the grid
+        assert_eq!(grid.shared_wall((1, 1), (1, 1)), None);
+        assert_eq!(grid.shared_wall((1, 1), (2, 2)), None);
+        assert_eq!(grid.shared_wall((0, 0), (2, 0)), None);
+        assert_eq!(grid.shared_wall((2, 1), (3, 1)), None);
+        assert_eq!(grid.shared_wall((3, 1), (2, 1)), None);
+
+        let torus = Grid::new_torus(3, 4);
+        assert_eq!(torus.shared_wall((0, 1), (2, 1)), Some(Pole::W));
+        assert_eq!(torus.shared_wall((2, 1), (0, 1)), Some(Pole::E));
+        assert_eq!(torus.shared_wall((1, 0), (1, 3)), Some(Pole::N));
+        assert_eq!(torus.shared_wall((1, 3), (1, 0)), Some(Pole::S));
+        assert_eq!(torus.shared_wall((1, 0), (1, 2)), None);
+        assert_eq!(Grid::new_torus(1, 1).shared_wall((0, 0), (0, 0)), None);
+    }
+
+    #[test]
+    fn corners() {
+        assert_eq!(Grid::new(5, 4).corners(), [(0, 0), (4, 0), (0, 3), (4, 3)]);
+        assert_eq!(Grid::new(1, 1).corners(), [(0, 0); 4]);
+        assert_eq!(Grid::new(3, 1).corners(), [(0, 0), (2, 0), (0, 0), (2, 0)]);
+        assert_eq!(Grid::new(1, 3).corners(), [(0, 0), (0, 0), (0, 2), (0, 2)]);
+        assert_eq!(Grid::new(0, 0).corners(), [(0, 0); 4]);
+    }
+
+    #[test]
+    fn center() {
+        assert_eq!(Grid::new(5, 5).center(), (2, 2));
+        assert_eq!(Grid::new(4, 4).center(), (1, 1));
+        assert_eq!(Grid::new(4, 3).center(), (1, 1));
+        assert_eq!(Grid::new(1, 6).center(), (0, 2));
+        assert_eq!(Grid::new(0, 0).center(), (0, 0));
+    }
+
+    #[test]
+    fn boundary_cells_clockwise() {
+        let grid = Grid::new(3, 3);
+        assert_eq!(
+            grid.boundary_cells(),
+            vec![
+                (0, 0),
+                (1, 0),
+                (2, 0),
+                (2, 1),
+                (2, 2),
+                (1, 2),
+                (0, 2),
+                (0, 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn boundary_cells_of_a_single_row_or_column() {
+        assert_eq!(Grid::new(1, 1).boundary_cells(), vec![(0, 0)]);
+        assert_eq!(
+            Grid::new(3, 1).boundary_cells(),
+            vec![(0, 0), (1, 0), (2, 0)]
+        );
+        assert_eq!(
+            Grid::new(1, 3).boundary_cells(),
+            vec![(0, 0), (0, 1), (0, 2)]
+        );
+    }
+
+    #[test]
+    fn boundary_poles() {
+        let grid = Grid::new(3, 3);
+        assert_eq!(grid.boundary_poles((0, 0)), vec![Pole::N, Pole::W]);
+        assert_eq!(grid.boundary_poles((1, 2)), vec![Pole::S]);
+        assert!(grid.boundary_poles((1, 1)).is_empty());
+    }
+
+    #[test]
+    fn carve_opening() {
+        let mut grid = Grid::new(3, 3);
+
+        grid.carve_opening((2, 1), Cell::EAST).unwrap();
+        assert!(grid.is_carved((2, 1), Cell::EAST));
+
+        assert!(grid.carve_opening((1, 1), Cell::EAST).is_err());
+        assert!(grid
+            .carve_opening((0, 0), Cell::NORTH | Cell::WEST)
+            .is_err());
+    }
+
+    #[test]
+    fn toggle_wall() {
+        for mut grid in [Grid::new(3, 2), Grid::new_packed(3, 2)] {
+            assert!(grid.toggle_wall((1, 1), Pole::N).unwrap());
+            assert_eq!(grid[(1, 1)], Cell::NORTH);
+            assert_eq!(grid[(1, 0)], Cell::SOUTH);
+
+            assert!(grid.toggle_wall((1, 0), Pole::W).unwrap());
+            assert!(!grid.toggle_wall((1, 0), Pole::S).unwrap());
+            assert_eq!(grid[(1, 0)], Cell::WEST);
+            assert_eq!(grid[(1, 1)], Cell::empty());
+            assert_eq!(grid[(0, 0)], Cell::EAST);
+        }
+    }
+
+    #[test]
+    fn toggle_outer_wall() {
+        for mut grid in [Grid::new(2, 2), Grid::new_packed(2, 2)] {
+            assert!(grid.toggle_wall((0, 1), Pole::W).unwrap());
+            assert!(grid.toggle_wall((1, 1), Pole::S).unwrap());
+            assert_eq!(grid[(0, 1)], Cell::WEST);
+            assert_eq!(grid[(1, 1)], Cell::SOUTH);
+
+            assert!(!grid.toggle_wall((0, 1), Pole::W).unwrap());
+            assert_eq!(grid[(0, 1)], Cell::empty());
+        }
+    }
+
+    #[test]
+    fn toggle_wall_out_of_bounds() {
+        let mut grid = Grid::new(2, 2);
+
+        let err = grid.toggle_wall((2, 0), Pole::W).unwrap_err();
+        assert_eq!(err.reason, "Cell is out of the 2x2 grid");
+        assert_eq!(grid[(1, 0)], Cell::empty());
+    }
+
+    #[test]
+    fn open_cell() {
+        let mut grid = Grid::new(3, 2);
+
+        grid.open_cell((2, 1)).unwrap();
+
+        assert_eq!(grid[(2, 1)], Cell::NORTH | Cell::WEST);
+        assert_eq!(grid[(2, 0)], Cell::SOUTH);
+        assert_eq!(grid[(1, 1)], Cell::EAST);
+        assert!(grid.is_cell_visited((2, 0)));
+
+        grid.open_cell((1, 1)).unwrap();
+        assert_eq!(grid[(1, 1)], Cell::NORTH | Cell::EAST | Cell::WEST);
+    }
+
+    #[test]
+    fn close_cell() {
+        let mut grid = Grid::new(3, 3);
+        grid.open_cell((1, 1)).unwrap();
+        grid.open_cell((1, 0)).unwrap();
+        grid.carve_opening((1, 0), Cell::NORTH).unwrap();
+
+        grid.close_cell((1, 0)).unwrap();
+
+        assert_eq!(grid[(1, 0)], Cell::empty());
+        assert_eq!(grid[(0, 0)], Cell::empty());
+        assert_eq!(grid[(2, 0)], Cell::empty());
+        assert_eq!(grid[(1, 1)], Cell::SOUTH | Cell::EAST | Cell::WEST);
+    }
+
+    #[test]
+    fn open_and_close_cells_out_of_bounds() {
+        let mut grid = Grid::new(2, 2);
+
+        let err = grid.open_cell((0, 2)).unwrap_err();
+        assert_eq!(err.reason, "Cell is out of the 2x2 grid");
+        assert!(grid.close_cell((2, 0)).is_err());
+    }
+
+    #[test]
+    fn from_edges() {
+        let grid = Grid::from_edges(
+            3,
+            2,
+            &[
+                ((0, 0), (0, 1)),
+                ((1, 1), (0, 1)),
+                ((1, 1), (1, 0)),
+                ((1, 1), (2, 1)),
+            ],
+        )
+        .unwrap();
+
+        let mut expected = Grid::new(3, 2);
+        expected.carve_passage((0, 0), Cell::SOUTH).unwrap();
+        expected.carve_passage((0, 1), Cell::EAST).unwrap();
+        expected.carve_passage((1, 1), Cell::NORTH).unwrap();
+        expected.carve_passage((1, 1), Cell::EAST).unwrap();
+        assert_eq!(grid, expected);
+    }
+
+    #[test]
+    fn from_edges_deduplicates() {
+        let edges = [((0, 0), (1, 0)), ((1, 0), (0, 0)), ((0, 0), (1, 0))];
+        let grid = Grid::from_edges(2, 1, &edges).unwrap();
+
+        assert_eq!(grid[(0, 0)], Cell::EAST);
+        assert_eq!(grid[(1, 0)], Cell::WEST);
+    }
+
+    #[test]
+    fn from_invalid_edges() {
+        let err = Grid::from_edges(2, 2, &[((0, 0), (1, 1))]).unwrap_err();
+        assert_eq!(err.coords, (0, 0));
+        assert_eq!(err.reason, "Cell is not adjacent to the (1, 1) cell");
+
+        let err = Grid::from_edges(2, 2, &[((0, 0), (0, 0))]).unwrap_err();
+        assert_eq!(err.reason, "Cell is not adjacent to the (0, 0) cell");
+
+        let err = Grid::from_edges(2, 2, &[((1, 1), (2, 1))]).unwrap_err();
+        assert_eq!(err.coords, (2, 1));
+        assert_eq!(err.reason, "Cell is out of the 2x2 grid");
+    }
+
+    #[test]
+    fn columns() {
+        for mut grid in [Grid::new(3, 2), Grid::new_packed(3, 2)] {
+            grid.carve_passage((0, 0), Cell::EAST).unwrap();
+            grid.carve_passage((1, 0), Cell::SOUTH).unwrap();
+
+            let columns: Vec<Vec<Cell>> = grid
+                .columns()
+                .map(|column| column.copied().collect())
+                .collect();
+
+            assert_eq!(
+                columns,
+                vec![
+                    vec![Cell::EAST, Cell::empty()],
+                    vec![Cell::WEST | Cell::SOUTH, Cell::NORTH],
+                    vec![Cell::empty(), Cell::empty()],
+                ]
+            );
+        }
+        assert_eq!(Grid::new(0, 3).columns().count(), 0);
+    }
+
+    #[test]
+    fn packed_grid_api() {
+        let mut grid = Grid::new_packed(3, 3);
+
+        grid.carve_passage((1, 1), Cell::EAST).unwrap();
+        grid.carve_passage((1, 1), Cell::NORTH).unwrap();
+        grid.carve_opening((0, 2), Cell::WEST).unwrap();
+        grid.mark_cell((2, 2));
+
+        assert_eq!(grid[(1, 1)], Cell::EAST | Cell::NORTH);
+        assert_eq!(grid[(2, 1)], Cell::WEST);
+        assert_eq!(grid[(1, 0)], Cell::SOUTH);
+        assert_eq!(grid[(0, 2)], Cell::WEST);
+        assert!(grid.is_cell_visited((2, 1)));
+        assert!(!grid.is_cell_visited((0, 0)));
+        assert!(grid.is_cell_marked((2, 2)));
+
+        grid.remove_passage((1, 1), Cell::EAST).unwrap();
+        assert_eq!(grid[(1, 1)], Cell::NORTH);
+        assert_eq!(grid[(2, 1)], Cell::empty());
+    }
+
+    #[test]
+    fn packed_grid_equals_unpacked() {
+        let mut grid = Grid::new(4, 2);
+        let mut packed = Grid::new_packed(4, 2);
+        assert_eq!(grid, packed);
+        assert!(!grid.is_packed());
+
+        for grid in [&mut grid, &mut packed] {
+            grid.carve_passage((0, 0), Cell::SOUTH).unwrap();
+            grid.carve_passage((3, 1), Cell::WEST).unwrap();
+            grid.carve_opening((3, 0), Cell::NORTH).unwrap();
+        }
+        assert_eq!(grid, packed);
+
+        packed.mark_cell((1, 0));
+        assert_ne!(grid, packed);
+        assert_ne!(Grid::new(2, 4), Grid::new_packed(4, 2));
+    }
+
+    #[test]
+    fn hash_grids() {
+        let mut grid = Grid::new(3, 2);
+        let mut packed = Grid::new_packed(3, 2);
+        for grid in [&mut grid, &mut packed] {
+            grid.carve_passage((1, 0), Cell::EAST).unwrap();
+            grid.carve_opening((0, 1), Cell::WEST).unwrap();
+        }
+        let mut other = grid.clone();
+        other.carve_passage((1, 1), Cell::NORTH).unwrap();
+
+        let grids: HashSet<Grid> = [grid.clone(), packed, other].into_iter().collect();
+        assert_eq!(grids.len(), 2);
+        assert!(grids.contains(&grid));
+        assert!(!grids.contains(&Grid::new(3, 2)));
+    }
+
+    #[test]
+    fn resize() {
+        let mut grid = Grid::new(4, 4);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(1);
+        RecursiveBacktracking.generate(&mut grid, None, &mut rng);
+        grid.carve_opening((0, 0), Cell::NORTH).unwrap();
+        grid.carve_opening((3, 3), Cell::EAST).unwrap();
+        grid.mark_cell((1, 1));
+        let original = grid.clone();
+
+        grid.resize(6, 5);
+        assert_eq!((grid.width(), grid.height()), (6, 5));
+        for y in 0..5 {
+            for x in 0..6 {
+                let expected = match (x, y) {
+                    (3, 3) => original[(3, 3)] - Cell::EAST,
+                    (x, y) if x < 4 && y < 4 => original[(x, y)],
+                    _ => Cell::empty(),
+                };
+                assert_eq!(grid[(x, y)], expected, "at ({x}, {y})");
             }
+        }
+        assert!(grid.is_cell_marked((1, 1)));
 
-            writeln!(f)?; // goto next line
+        let mut grid = original.clone();
+        grid.resize(3, 2);
+        for y in 0..2 {
+            for x in 0..3 {
+                let mut expected = original[(x, y)];
+                if x == 2 {
+                    expected -= Cell::EAST;
+                }
+                if y == 1 {
+                    expected -= Cell::SOUTH;
+                }
+                assert_eq!(grid[(x, y)], expected, "at ({x}, {y})");
+            }
         }
 
-        Ok(())
+        let mut packed = Grid::new_packed(4, 4);
+        RecursiveBacktracking.generate(
+            &mut packed,
+            None,
+            &mut rand::rngs::StdRng::seed_from_u64(1),
+        );
+        packed.carve_opening((0, 0), Cell::NORTH).unwrap();
+        packed.mark_cell((1, 1));
+        packed.resize(3, 2);
+        assert!(packed.is_packed());
+        assert_eq!(packed, grid);
+    }
+
+    #[test]
+    fn resize_torus() {
+        let mut grid = Grid::new_torus(3, 2);
+        grid.carve_passage((0, 0), Cell::WEST).unwrap();
+        grid.carve_passage((1, 0), Cell::SOUTH).unwrap();
+        grid.carve_passage((1, 1), Cell::SOUTH).unwrap();
+
+        grid.resize(2, 2);
+        assert!(grid.is_torus());
+        assert_eq!(grid[(0, 0)], Cell::empty());
+        assert_eq!(grid[(1, 0)], Cell::NORTH | Cell::SOUTH);
+        assert_eq!(grid[(1, 1)], Cell::NORTH | Cell::SOUTH);
+    }
+
+    #[test]
+    #[should_panic(expected = "Cell at (3, 0) doesn't exist.")]
+    fn index_out_of_row() {
+        let _ = Grid::new_packed(3, 2)[(3, 0)];
     }
 }
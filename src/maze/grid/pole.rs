@@ -0,0 +1,275 @@
+use std::str::FromStr;
+
+use rand::Rng;
+
+use super::cell::Cell;
+use crate::{maze::errors::ParseError, utils::rand::shuffle};
+
+/// A cardinal direction (pole) of a cell
+///
+/// Simplifies iterating over the neighbours of a cell, as well as going in the opposite direction,
+/// e.g. when carving a passage through the joint wall of two cells.
+///
+/// # Example
+/// ```
+/// use bevy_knossos::maze::{Cell, Pole};
+///
+/// for pole in Pole::all() {
+///     assert_eq!(pole.opposite().opposite(), pole);
+/// }
+///
+/// assert_eq!(Cell::from(Pole::N), Cell::NORTH);
+/// assert_eq!(Pole::E.offset(), (1, 0));
+/// assert_eq!("north".parse::<Pole>().unwrap(), Pole::N);
+/// assert_eq!(Pole::try_from('w').unwrap(), Pole::W);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Pole {
+    /// North
+    N,
+    /// South
+    S,
+    /// East
+    E,
+    /// West
+    W,
+}
+
+impl Pole {
+    /// Returns all the poles in the `N`, `S`, `W`, `E` order
+    pub const fn all() -> [Pole; 4] {
+        [Pole::N, Pole::S, Pole::W, Pole::E]
+    }
+
+    /// Returns all the poles in a random order
+    ///
+    /// The same state of a random number generator always returns the same order.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::Pole;
+    /// use rand::{rngs::StdRng, SeedableRng};
+    ///
+    /// let poles = Pole::shuffled(&mut StdRng::seed_from_u64(1));
+    ///
+    /// assert_eq!(poles, Pole::shuffled(&mut StdRng::seed_from_u64(1)));
+    /// assert!(Pole::all().iter().all(|pole| poles.contains(pole)));
+    /// ```
+    pub fn shuffled<R: Rng + ?Sized>(rng: &mut R) -> [Pole; 4] {
+        let mut poles = Pole::all();
+        shuffle(&mut poles, rng);
+        poles
+    }
+
+    /// Returns the opposite pole, i.e. `N` for `S`, `E` for `W` and vice versa
+    pub const fn opposite(&self) -> Pole {
+        match self {
+            Pole::N => Pole::S,
+            Pole::S => Pole::N,
+            Pole::E => Pole::W,
+            Pole::W => Pole::E,
+        }
+    }
+
+    /// Returns the `(x, y)` coordinates delta of moving one cell in the pole's direction
+    ///
+    /// The origin of a grid is its top left corner, so going North decreases `y`.
+    pub const fn offset(&self) -> (i32, i32) {
+        match self {
+            Pole::N => (0, -1),
+            Pole::S => (0, 1),
+            Pole::E => (1, 0),
+            Pole::W => (-1, 0),
+        }
+    }
+
+    /// Returns a pole matching a given cell direction, or `None` unless the cell has exactly one
+    /// passage
+    pub const fn from_cell(cell: Cell) -> Option<Pole> {
+        match cell {
+            Cell::NORTH => Some(Pole::N),
+            Cell::SOUTH => Some(Pole::S),
+            Cell::EAST => Some(Pole::E),
+            Cell::WEST => Some(Pole::W),
+            _ => None,
+        }
+    }
+}
+
+impl From<Pole> for Cell {
+    fn from(pole: Pole) -> Self {
+        match pole {
+            Pole::N => Cell::NORTH,
+            Pole::S => Cell::SOUTH,
+            Pole::E => Cell::EAST,
+            Pole::W => Cell::WEST,
+        }
+    }
+}
+
+/// An axis a maze is crossed along, e.g. from an entrance to an exit on the opposite sides
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Axis {
+    /// From the western side to the eastern one
+    Horizontal,
+    /// From the northern side to the southern one
+    Vertical,
+}
+
+impl Axis {
+    /// Returns the poles of the sides at both ends of the axis, i.e. `W` and `E` for the
+    /// [Horizontal](Axis::Horizontal) axis, and `N` and `S` for the [Vertical](Axis::Vertical) one
+    pub const fn poles(&self) -> (Pole, Pole) {
+        match self {
+            Axis::Horizontal => (Pole::W, Pole::E),
+            Axis::Vertical => (Pole::N, Pole::S),
+        }
+    }
+}
+
+impl TryFrom<char> for Pole {
+    type Error = ParseError;
+
+    /// Converts an initial of a pole, e.g. `N` or `n`, into the pole
+    fn try_from(ch: char) -> Result<Self, Self::Error> {
+        match ch.to_ascii_uppercase() {
+            'N' => Ok(Pole::N),
+            'S' => Ok(Pole::S),
+            'E' => Ok(Pole::E),
+            'W' => Ok(Pole::W),
+            _ => Err(unrecognized(&ch.to_string())),
+        }
+    }
+}
+
+impl FromStr for Pole {
+    type Err = ParseError;
+
+    /// Parses either an initial or a full name of a pole regardless of the case, e.g. `N`, `n`,
+    /// `North` or `north`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        if let (Some(ch), None) = (chars.next(), chars.next()) {
+            return Pole::try_from(ch).map_err(|_| unrecognized(s));
+        }
+
+        match s.to_ascii_lowercase().as_str() {
+            "north" => Ok(Pole::N),
+            "south" => Ok(Pole::S),
+            "east" => Ok(Pole::E),
+            "west" => Ok(Pole::W),
+            _ => Err(unrecognized(s)),
+        }
+    }
+}
+
+fn unrecognized(s: &str) -> ParseError {
+    ParseError::reason(format!(
+        "Unrecognized pole `{}`, expected one of `N`, `S`, `E`, `W` or their full names",
+        s
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn shuffled_poles() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let orders: Vec<[Pole; 4]> = (0..20).map(|_| Pole::shuffled(&mut rng)).collect();
+
+        for poles in &orders {
+            let mut sorted = *poles;
+            sorted.sort_by_key(|pole| Pole::all().iter().position(|p| p == pole));
+            assert_eq!(sorted, Pole::all());
+        }
+        assert!(orders.iter().any(|poles| *poles != orders[0]));
+
+        let mut rng = StdRng::seed_from_u64(7);
+        assert_eq!(Pole::shuffled(&mut rng), orders[0]);
+    }
+
+    #[test]
+    fn opposite_of_opposite_is_same_pole() {
+        for pole in Pole::all() {
+            assert_eq!(pole.opposite().opposite(), pole);
+        }
+    }
+
+    #[test]
+    fn opposite_poles() {
+        assert_eq!(Pole::N.opposite(), Pole::S);
+        assert_eq!(Pole::S.opposite(), Pole::N);
+        assert_eq!(Pole::E.opposite(), Pole::W);
+        assert_eq!(Pole::W.opposite(), Pole::E);
+    }
+
+    #[test]
+    fn axis_poles() {
+        for axis in [Axis::Horizontal, Axis::Vertical] {
+            let (from, to) = axis.poles();
+            assert_eq!(from.opposite(), to);
+        }
+        assert_eq!(Axis::Horizontal.poles(), (Pole::W, Pole::E));
+    }
+
+    #[test]
+    fn opposite_offsets_cancel_out() {
+        for pole in Pole::all() {
+            let (dx, dy) = pole.offset();
+            let (ox, oy) = pole.opposite().offset();
+            assert_eq!((dx + ox, dy + oy), (0, 0));
+        }
+    }
+
+    #[test]
+    fn cell_conversion() {
+        for pole in Pole::all() {
+            assert_eq!(Pole::from_cell(Cell::from(pole)), Some(pole));
+        }
+        assert_eq!(Pole::from_cell(Cell::empty()), None);
+        assert_eq!(Pole::from_cell(Cell::NORTH | Cell::EAST), None);
+    }
+
+    #[test]
+    fn parse_initials() {
+        for (s, pole) in [
+            ("N", Pole::N),
+            ("s", Pole::S),
+            ("E", Pole::E),
+            ("w", Pole::W),
+        ] {
+            assert_eq!(s.parse::<Pole>().unwrap(), pole);
+        }
+        assert_eq!(Pole::try_from('n').unwrap(), Pole::N);
+        assert_eq!(Pole::try_from('E').unwrap(), Pole::E);
+    }
+
+    #[test]
+    fn parse_names() {
+        for (s, pole) in [
+            ("north", Pole::N),
+            ("South", Pole::S),
+            ("EAST", Pole::E),
+            ("west", Pole::W),
+        ] {
+            assert_eq!(s.parse::<Pole>().unwrap(), pole);
+        }
+    }
+
+    #[test]
+    fn parse_unrecognized() {
+        let err = "up".parse::<Pole>().unwrap_err();
+        assert_eq!(
+            err.reason,
+            "Unrecognized pole `up`, expected one of `N`, `S`, `E`, `W` or their full names"
+        );
+        assert!("".parse::<Pole>().is_err());
+        assert!("x".parse::<Pole>().is_err());
+        assert!(" north".parse::<Pole>().is_err());
+        assert!(Pole::try_from('1').is_err());
+    }
+}
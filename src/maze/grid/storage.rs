@@ -0,0 +1,279 @@
+use bit_vec::BitVec;
+
+use super::cell::{Cell, CellStatus};
+use crate::utils::types::Coords;
+
+/// All the possible cells indexed by their bits, so that a packed cell can be returned by reference
+const CELLS: [Cell; 16] = {
+    let mut cells = [Cell::empty(); 16];
+    let mut bits = 0;
+    while bits < cells.len() {
+        cells[bits] = Cell::from_bits_retain(bits as u8);
+        bits += 1;
+    }
+    cells
+};
+
+/// A backing store of the passages and statuses of grid cells
+#[derive(Debug, Clone)]
+pub(crate) enum Storage {
+    /// A byte per cell, with all of its passages, and a status per cell
+    Cells {
+        width: usize,
+        cells: Vec<Cell>,
+        statuses: Vec<CellStatus>,
+    },
+    /// Bits of eastern and southern passages per cell, see [Packed]
+    Packed(Packed),
+}
+
+/// Cells packed into bits
+///
+/// Only eastern and southern passages are stored per cell, while northern and western ones are
+/// derived from the neighbours. Openings in the northern and western outer walls, having no
+/// neighbours to be derived from, are stored separately.
+#[derive(Debug, Clone)]
+pub(crate) struct Packed {
+    width: usize,
+    /// Eastern and southern passages, two bits per cell
+    passages: BitVec,
+    /// Openings in the northern outer wall, a bit per column
+    north: BitVec,
+    /// Openings in the western outer wall, a bit per row
+    west: BitVec,
+    /// Visited and marked flags, two bits per cell
+    statuses: BitVec,
+}
+
+/// A location of a passage bit in a packed storage
+enum Bit {
+    Passage(usize),
+    North(usize),
+    West(usize),
+}
+
+impl Storage {
+    pub(crate) fn new(width: usize, height: usize) -> Storage {
+        Storage::Cells {
+            width,
+            cells: vec![Cell::default(); width * height],
+            statuses: vec![CellStatus::default(); width * height],
+        }
+    }
+
+    pub(crate) fn packed(width: usize, height: usize) -> Storage {
+        Storage::Packed(Packed {
+            width,
+            passages: BitVec::from_elem(width * height * 2, false),
+            north: BitVec::from_elem(width, false),
+            west: BitVec::from_elem(height, false),
+            statuses: BitVec::from_elem(width * height * 2, false),
+        })
+    }
+
+    pub(crate) fn cell(&self, (x, y): Coords) -> &Cell {
+        match self {
+            Storage::Cells { width, cells, .. } => &cells[y * width + x],
+            Storage::Packed(packed) => {
+                let cell = [Cell::NORTH, Cell::SOUTH, Cell::EAST, Cell::WEST]
+                    .into_iter()
+                    .filter(|dir| packed.get(packed.locate((x, y), *dir)))
+                    .fold(Cell::empty(), |cell, dir| cell | dir);
+                &CELLS[cell.bits() as usize]
+            }
+        }
+    }
+
+    /// Sets or clears a single passage of a cell, without updating its neighbour
+    pub(crate) fn set(&mut self, (x, y): Coords, direction: Cell, carved: bool) {
+        match self {
+            Storage::Cells { width, cells, .. } => {
+                cells[y * *width + x].set(direction, carved);
+            }
+            Storage::Packed(packed) => {
+                let bit = packed.locate((x, y), direction);
+                packed.put(bit, carved);
+            }
+        }
+    }
+
+    pub(crate) fn status(&self, (x, y): Coords) -> CellStatus {
+        match self {
+            Storage::Cells {
+                width, statuses, ..
+            } => statuses[y * width + x],
+            Storage::Packed(packed) => {
+                let idx = (y * packed.width + x) * 2;
+                let get = |i| packed.statuses.get(i).unwrap_or_default();
+                CellStatus::new(get(idx), get(idx + 1))
+            }
+        }
+    }
+
+    pub(crate) fn visit(&mut self, (x, y): Coords) {
+        match self {
+            Storage::Cells {
+                width, statuses, ..
+            } => statuses[y * *width + x].visit(),
+            Storage::Packed(packed) => packed.statuses.set((y * packed.width + x) * 2, true),
+        }
+    }
+
+    pub(crate) fn mark(&mut self, (x, y): Coords) {
+        match self {
+            Storage::Cells {
+                width, statuses, ..
+            } => statuses[y * *width + x].mark(),
+            Storage::Packed(packed) => packed.statuses.set((y * packed.width + x) * 2 + 1, true),
+        }
+    }
+
+    /// Rebuilds all the walls and clears all the statuses, keeping the allocated memory
+    pub(crate) fn reset(&mut self) {
+        match self {
+            Storage::Cells {
+                cells, statuses, ..
+            } => {
+                cells.fill(Cell::default());
+                statuses.fill(CellStatus::default());
+            }
+            Storage::Packed(packed) => {
+                packed.passages.clear();
+                packed.north.clear();
+                packed.west.clear();
+                packed.statuses.clear();
+            }
+        }
+    }
+
+    pub(crate) const fn is_packed(&self) -> bool {
+        matches!(self, Storage::Packed(_))
+    }
+
+    /// Returns a pointer to the allocated passages
+    #[cfg(test)]
+    pub(crate) fn as_ptr(&self) -> *const () {
+        match self {
+            Storage::Cells { cells, .. } => cells.as_ptr().cast(),
+            Storage::Packed(packed) => packed.passages.storage().as_ptr().cast(),
+        }
+    }
+
+    /// Returns the number of bytes allocated for cells and their statuses
+    #[cfg(test)]
+    pub(crate) fn allocated_bytes(&self) -> usize {
+        match self {
+            Storage::Cells {
+                cells, statuses, ..
+            } => {
+                cells.capacity() * size_of::<Cell>() + statuses.capacity() * size_of::<CellStatus>()
+            }
+            Storage::Packed(packed) => [
+                &packed.passages,
+                &packed.north,
+                &packed.west,
+                &packed.statuses,
+            ]
+            .iter()
+            .map(|bits| size_of_val(bits.storage()))
+            .sum(),
+        }
+    }
+}
+
+impl Packed {
+    fn locate(&self, (x, y): Coords, direction: Cell) -> Bit {
+        let idx = y * self.width + x;
+        match direction {
+            Cell::EAST => Bit::Passage(idx * 2),
+            Cell::SOUTH => Bit::Passage(idx * 2 + 1),
+            Cell::NORTH if y == 0 => Bit::North(x),
+            Cell::NORTH => Bit::Passage((idx - self.width) * 2 + 1),
+            Cell::WEST if x == 0 => Bit::West(y),
+            Cell::WEST => Bit::Passage((idx - 1) * 2),
+            _ => unreachable!("A passage always goes in a single direction"),
+        }
+    }
+
+    fn get(&self, bit: Bit) -> bool {
+        match bit {
+            Bit::Passage(i) => self.passages.get(i),
+            Bit::North(i) => self.north.get(i),
+            Bit::West(i) => self.west.get(i),
+        }
+        .unwrap_or_default()
+    }
+
+    fn put(&mut self, bit: Bit, value: bool) {
+        match bit {
+            Bit::Passage(i) => self.passages.set(i, value),
+            Bit::North(i) => self.north.set(i, value),
+            Bit::West(i) => self.west.set(i, value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cells_table() {
+        for (bits, cell) in CELLS.iter().enumerate() {
+            assert_eq!(cell.bits() as usize, bits);
+        }
+    }
+
+    #[test]
+    fn packed_passages_are_shared_with_neighbours() {
+        let mut storage = Storage::packed(3, 2);
+
+        storage.set((1, 1), Cell::NORTH, true);
+        storage.set((1, 1), Cell::WEST, true);
+
+        assert_eq!(*storage.cell((1, 0)), Cell::SOUTH);
+        assert_eq!(*storage.cell((0, 1)), Cell::EAST);
+        assert_eq!(*storage.cell((1, 1)), Cell::NORTH | Cell::WEST);
+        assert_eq!(*storage.cell((2, 1)), Cell::empty());
+    }
+
+    #[test]
+    fn packed_outer_openings() {
+        let mut storage = Storage::packed(2, 2);
+
+        storage.set((1, 0), Cell::NORTH, true);
+        storage.set((0, 1), Cell::WEST, true);
+        storage.set((1, 1), Cell::EAST, true);
+        storage.set((0, 1), Cell::SOUTH, true);
+
+        assert_eq!(*storage.cell((1, 0)), Cell::NORTH);
+        assert_eq!(*storage.cell((0, 1)), Cell::WEST | Cell::SOUTH);
+        assert_eq!(*storage.cell((1, 1)), Cell::EAST);
+
+        storage.set((0, 1), Cell::WEST, false);
+        assert_eq!(*storage.cell((0, 1)), Cell::SOUTH);
+    }
+
+    #[test]
+    fn packed_statuses() {
+        let mut storage = Storage::packed(2, 2);
+
+        storage.visit((1, 0));
+        storage.mark((0, 1));
+
+        assert_eq!(storage.status((1, 0)), CellStatus::new(true, false));
+        assert_eq!(storage.status((0, 1)), CellStatus::new(false, true));
+        assert_eq!(storage.status((1, 1)), CellStatus::default());
+
+        storage.reset();
+        assert_eq!(storage.status((1, 0)), CellStatus::default());
+    }
+
+    #[test]
+    fn packed_takes_less_memory() {
+        let cells = Storage::new(256, 256).allocated_bytes();
+        let packed = Storage::packed(256, 256).allocated_bytes();
+
+        assert!(packed * 5 < cells);
+    }
+}
@@ -0,0 +1,395 @@
+use std::collections::VecDeque;
+
+use rand::RngCore;
+
+use super::grid::{cell::Cell, pole::Pole, Grid};
+use crate::utils::{arena::join_parts, types::Coords};
+
+/// A mask of solid areas a maze is generated around
+///
+/// Cells in a solid area keep all of their walls and are excluded from the maze, e.g. to reserve
+/// a place for a logo in the middle of a printed maze, or to confine the maze to a
+/// [polygon](Mask::from_polygon). The rest of the maze is generated as usual and stays connected
+/// around the solid areas, so there is a path between any two cells out of them.
+///
+/// # Example
+/// ```
+/// use bevy_knossos::maze::*;
+///
+/// let maze = OrthogonalMazeBuilder::new()
+///     .width(10)
+///     .height(10)
+///     .mask(Mask::new().solid_rect((3, 3), 4, 4))
+///     .build()
+///     .unwrap();
+///
+/// assert!(maze.is_valid());
+/// assert_eq!(maze[(4, 4)], Cell::empty());
+/// assert!(maze.solve((0, 0), (9, 9)).is_some());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Mask {
+    polygon: Option<Vec<Coords>>,
+    solid_rects: Vec<Rect>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Rect {
+    left: usize,
+    top: usize,
+    width: usize,
+    height: usize,
+}
+
+impl Rect {
+    const fn contains(&self, (x, y): Coords) -> bool {
+        x >= self.left && x - self.left < self.width && y >= self.top && y - self.top < self.height
+    }
+}
+
+impl Mask {
+    /// Returns a new instance of a mask without solid areas
+    pub const fn new() -> Mask {
+        Mask {
+            polygon: None,
+            solid_rects: Vec::new(),
+        }
+    }
+
+    /// Returns a new instance of a mask where all the cells out of a polygon with given vertices
+    /// are solid
+    ///
+    /// Vertices are the coords of joints of cells, i.e. `(0, 0)` is the top left corner of the
+    /// maze and `(width, height)` is its bottom right corner, and they may describe a convex or a
+    /// concave polygon. A cell is in the polygon when any part of it is, rather than only its
+    /// corners or walls, so the cells of a connected polygon are connected too, even along its
+    /// slanted edges. The inside of a self-intersecting polygon follows the even-odd rule. Cells on
+    /// the edges of the polygon keep the walls facing the solid cells, so they make an outer wall
+    /// of the polygon shape.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// // A diamond touching the middle of every side of the maze
+    /// let diamond = Mask::from_polygon(&[(5, 0), (10, 5), (5, 10), (0, 5)]);
+    /// let maze = OrthogonalMazeBuilder::new()
+    ///     .width(10)
+    ///     .height(10)
+    ///     .mask(diamond.clone())
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert!(diamond.is_solid((0, 0)));
+    /// assert!(!diamond.is_solid((4, 0)));
+    /// assert_eq!(maze[(0, 0)], Cell::empty());
+    /// assert!(maze.solve((4, 0), (5, 9)).is_some());
+    /// ```
+    pub fn from_polygon(vertices: &[Coords]) -> Mask {
+        Mask {
+            polygon: Some(vertices.to_vec()),
+            ..Mask::new()
+        }
+    }
+
+    /// Adds a solid rectangle of a given width and height with its top left corner at given coords
+    /// and returns itself
+    ///
+    /// Parts of the rectangle out of the maze bounds are ignored.
+    pub fn solid_rect(mut self, coords: impl Into<Coords>, width: usize, height: usize) -> Self {
+        let (left, top) = coords.into();
+        self.solid_rects.push(Rect {
+            left,
+            top,
+            width,
+            height,
+        });
+        self
+    }
+
+    /// Indicates if a cell at given coords is in a solid area of the mask
+    pub fn is_solid(&self, coords: Coords) -> bool {
+        self.polygon
+            .as_ref()
+            .is_some_and(|vertices| !overlaps(vertices, coords))
+            || self.solid_rects.iter().any(|rect| rect.contains(coords))
+    }
+
+    /// Indicates if the cells of a maze of a given size out of the solid areas make a single
+    /// connected part, i.e. a maze can be generated around the solid areas
+    pub(crate) fn is_connected(&self, width: usize, height: usize) -> bool {
+        let Some(start) = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .find(|coords| !self.is_solid(*coords))
+        else {
+            return false;
+        };
+
+        let grid = Grid::new(width, height);
+        let mut visited = vec![false; width * height];
+        let mut queue = VecDeque::from([start]);
+        visited[start.1 * width + start.0] = true;
+        let mut count = 1;
+
+        while let Some(coords) = queue.pop_front() {
+            for pole in Pole::all() {
+                let Ok((nx, ny)) = grid.get_next_cell_coords(coords, Cell::from(pole)) else {
+                    continue;
+                };
+                if !visited[ny * width + nx] && !self.is_solid((nx, ny)) {
+                    visited[ny * width + nx] = true;
+                    count += 1;
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+
+        let open = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .filter(|coords| !self.is_solid(*coords))
+            .count();
+        count == open
+    }
+
+    /// Walls off the solid cells of a generated grid and reconnects the rest of the grid around them
+    ///
+    /// Every passage of a solid cell is removed, and the parts of the rest of the grid this splits
+    /// are joined with passages through randomly chosen walls, so a perfect maze stays perfect out
+    /// of the solid areas.
+    pub(crate) fn apply(&self, grid: &mut Grid, rng: &mut dyn RngCore) {
+        for y in 0..grid.height() {
+            for x in 0..grid.width() {
+                for direction in [Cell::EAST, Cell::SOUTH] {
+                    let Ok(next) = grid.get_next_cell_coords((x, y), direction) else {
+                        continue;
+                    };
+
+                    if (self.is_solid((x, y)) || self.is_solid(next))
+                        && grid.is_carved((x, y), direction)
+                    {
+                        grid.remove_passage((x, y), direction).unwrap();
+                    }
+                }
+            }
+        }
+
+        let open = |from: Coords, to: Coords| !self.is_solid(from) && !self.is_solid(to);
+        join_parts(grid, Some(&open), rng);
+    }
+}
+
+/// Indicates if a part of a cell at given coords is inside a polygon with given vertices, i.e. the
+/// cell is inside it, or an edge of the polygon goes across the cell
+fn overlaps(vertices: &[Coords], coords: Coords) -> bool {
+    // A polygon without an area has no inside
+    if vertices.len() < 3 {
+        return false;
+    }
+
+    let point = |(x, y): Coords| (x as f64, y as f64);
+    contains_center(vertices, coords)
+        || (0..vertices.len()).any(|idx| {
+            let (a, b) = (vertices[idx], vertices[(idx + 1) % vertices.len()]);
+            crosses_cell(point(a), point(b), point(coords))
+        })
+}
+
+/// Indicates if a segment goes through the inside of a cell with its top left corner at given
+/// coords, and not only along its walls or through its corners
+fn crosses_cell((ax, ay): (f64, f64), (bx, by): (f64, f64), (x, y): (f64, f64)) -> bool {
+    // Clips the parameter of the segment going from `a` at `0` to `b` at `1` to the open cell
+    let (mut lo, mut hi) = (0.0_f64, 1.0_f64);
+    for (start, delta, min) in [(ax, bx - ax, x), (ay, by - ay, y)] {
+        if delta == 0.0 {
+            if start <= min || start >= min + 1.0 {
+                return false;
+            }
+        } else {
+            let (t0, t1) = ((min - start) / delta, (min + 1.0 - start) / delta);
+            lo = lo.max(t0.min(t1));
+            hi = hi.min(t0.max(t1));
+        }
+    }
+
+    lo < hi
+}
+
+/// Indicates if the center of a cell at given coords is inside a polygon with given vertices
+///
+/// Coords are doubled, so that centers of cells are integral. A ray going East from a center
+/// never passes through a vertex, since centers are odd and vertices are even.
+fn contains_center(vertices: &[Coords], (x, y): Coords) -> bool {
+    let (px, py) = (2 * x as i64 + 1, 2 * y as i64 + 1);
+    let mut inside = false;
+
+    for (idx, &(ax, ay)) in vertices.iter().enumerate() {
+        let (bx, by) = vertices[(idx + 1) % vertices.len()];
+        let (ax, ay, bx, by) = (2 * ax as i64, 2 * ay as i64, 2 * bx as i64, 2 * by as i64);
+        if (ay > py) == (by > py) {
+            continue;
+        }
+
+        // The edge crosses the row of the center to the East of it
+        let crosses = if by > ay {
+            (px - ax) * (by - ay) < (py - ay) * (bx - ax)
+        } else {
+            (px - ax) * (by - ay) > (py - ay) * (bx - ax)
+        };
+        if crosses {
+            inside = !inside;
+        }
+    }
+
+    inside
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze::{algorithms::Algorithm, HuntAndKill, Kruskal, Prim, RecursiveBacktracking};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn solid_cells() {
+        let mask = Mask::new()
+            .solid_rect((1, 2), 3, 2)
+            .solid_rect((0, 0), 1, 1);
+
+        assert!(mask.is_solid((0, 0)));
+        assert!(mask.is_solid((1, 2)));
+        assert!(mask.is_solid((3, 3)));
+        assert!(!mask.is_solid((4, 3)));
+        assert!(!mask.is_solid((1, 4)));
+        assert!(!mask.is_solid((1, 0)));
+        assert!(!Mask::new().is_solid((0, 0)));
+    }
+
+    #[test]
+    fn polygon_cells() {
+        // An L shape with a concave corner at (2, 2)
+        let mask = Mask::from_polygon(&[(0, 0), (2, 0), (2, 2), (4, 2), (4, 4), (0, 4)]);
+        let solid: Vec<Coords> = (0..4)
+            .flat_map(|y| (0..4).map(move |x| (x, y)))
+            .filter(|coords| mask.is_solid(*coords))
+            .collect();
+
+        assert_eq!(solid, vec![(2, 0), (3, 0), (2, 1), (3, 1)]);
+        assert!(mask.is_solid((4, 3)));
+        assert!(mask.solid_rect((0, 3), 1, 1).is_solid((0, 3)));
+    }
+
+    #[test]
+    fn triangle_cells() {
+        let mask = Mask::from_polygon(&[(0, 0), (4, 0), (0, 4)]);
+
+        // Cells the diagonal edge goes across are in the triangle, unlike the ones it touches
+        assert!(!mask.is_solid((0, 0)));
+        assert!(!mask.is_solid((2, 1)));
+        assert!(!mask.is_solid((0, 3)));
+        assert!(mask.is_solid((2, 2)));
+        assert!(mask.is_solid((3, 1)));
+        assert!(mask.is_solid((4, 0)));
+        assert!(Mask::from_polygon(&[(0, 0), (4, 0)]).is_solid((0, 0)));
+        assert!(Mask::from_polygon(&[]).is_solid((0, 0)));
+    }
+
+    #[test]
+    fn connected_masks() {
+        assert!(Mask::new().is_connected(3, 3));
+        assert!(Mask::new().solid_rect((1, 1), 1, 1).is_connected(3, 3));
+        assert!(Mask::new().solid_rect((0, 0), 3, 1).is_connected(3, 3));
+        // A solid row across the maze splits it
+        assert!(!Mask::new().solid_rect((0, 1), 3, 1).is_connected(3, 3));
+        assert!(!Mask::new().solid_rect((0, 0), 5, 5).is_connected(3, 3));
+    }
+
+    #[test]
+    fn apply_star_polygon() {
+        let star = Mask::from_polygon(&[
+            (8, 0),
+            (10, 6),
+            (16, 6),
+            (11, 10),
+            (13, 16),
+            (8, 12),
+            (3, 16),
+            (5, 10),
+            (0, 6),
+            (6, 6),
+        ]);
+        assert!(star.is_connected(16, 16));
+
+        let mut grid = Grid::new(16, 16);
+        let mut rng = StdRng::seed_from_u64(1);
+        RecursiveBacktracking.generate(&mut grid, None, &mut rng);
+        star.apply(&mut grid, &mut rng);
+
+        let open: Vec<Coords> = (0..16)
+            .flat_map(|y| (0..16).map(move |x| (x, y)))
+            .filter(|coords| !star.is_solid(*coords))
+            .collect();
+        assert_eq!(reachable(&grid, (8, 8)), open.len());
+        assert!(star.is_solid((0, 0)) && star.is_solid((8, 15)));
+        assert!(!star.is_solid((8, 0)) && !star.is_solid((2, 6)));
+    }
+
+    #[test]
+    fn apply_to_generated_grids() {
+        let algorithms: Vec<Box<dyn Algorithm>> = vec![
+            Box::new(RecursiveBacktracking),
+            Box::new(Kruskal::new()),
+            Box::new(Prim::new()),
+            Box::new(HuntAndKill::new()),
+        ];
+        let mask = Mask::new()
+            .solid_rect((4, 2), 4, 3)
+            .solid_rect((0, 7), 2, 5);
+
+        for (seed, mut algorithm) in algorithms.into_iter().enumerate() {
+            let mut grid = Grid::new(12, 10);
+            let mut rng = StdRng::seed_from_u64(seed as u64);
+            algorithm.generate(&mut grid, None, &mut rng);
+            mask.apply(&mut grid, &mut rng);
+
+            let mut passages = 0;
+            let mut open = 0;
+            for y in 0..10 {
+                for x in 0..12 {
+                    if mask.is_solid((x, y)) {
+                        assert_eq!(grid[(x, y)], Cell::empty());
+                    } else {
+                        open += 1;
+                        passages += grid[(x, y)].passage_count();
+                    }
+                }
+            }
+            // The rest of the grid is a spanning tree of the open cells
+            assert_eq!(passages / 2, open - 1);
+            assert_eq!(reachable(&grid, (0, 0)), open);
+        }
+    }
+
+    fn reachable(grid: &Grid, from: Coords) -> usize {
+        let width = grid.width();
+        let mut visited = vec![false; width * grid.height()];
+        let mut queue = VecDeque::from([from]);
+        visited[from.1 * width + from.0] = true;
+        let mut count = 0;
+
+        while let Some(coords) = queue.pop_front() {
+            count += 1;
+            for direction in Pole::all().map(Cell::from) {
+                if !grid.is_carved(coords, direction) {
+                    continue;
+                }
+                let (nx, ny) = grid.get_next_cell_coords(coords, direction).unwrap();
+                if !visited[ny * width + nx] {
+                    visited[ny * width + nx] = true;
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+
+        count
+    }
+}
@@ -1,258 +1,3799 @@
 use bevy::ecs::system::Resource;
+use rand::{prelude::*, rngs::StdRng};
 
-use crate::utils::types::Coords;
+use crate::utils::{
+    arena::{join_parts, ArenaTree, NodeId},
+    rand::shuffle,
+    types::Coords,
+};
+
+use super::{
+    algorithms::{Algorithm, RecursiveBacktracking},
+    corridor::straighten_corridors,
+    errors::{MazeError, MazeSaveError, ParseError},
+    formatters::{
+        walls::{walls, Wall},
+        AsciiNarrow, Formatter, Saveable,
+    },
+    grid::{
+        cell::Cell,
+        pole::{Axis, Pole},
+        Grid,
+    },
+    mask::Mask,
+    metrics::BiasMetrics,
+    observer::GenerationObserver,
+    partial::join_partial,
+    room::carve_goal_room,
+    solution::Solution,
+    symmetry::Symmetry,
+    validate::{validate, validate_masked},
+};
+use std::{
+    collections::{BinaryHeap, HashSet, VecDeque},
+    fmt,
+    io::{self, Read, Write},
+    sync::{Arc, Mutex, PoisonError},
+};
+#[cfg(feature = "serde")]
+use std::collections::BTreeMap;
+
+/// The bytes every maze in the binary format starts with
+const BINARY_MAGIC: [u8; 4] = *b"KNOS";
+
+/// The version of the binary format mazes are written in
+///
+/// Version 1 has no flags after the version byte, and is read as a maze without any flags set.
+const BINARY_VERSION: u8 = 2;
+
+/// The flag of the binary format marking a maze wrapping around like a [torus](Grid::new_torus)
+const BINARY_TORUS: u8 = 0b1;
+
+/// An algorithm with the settings a maze is generated with
+pub(crate) struct Generator {
+    algorithm: Box<dyn Algorithm>,
+    start_coords: Option<Coords>,
+    min_corridor: usize,
+    observer: Option<Box<dyn GenerationObserver>>,
+    symmetry: Option<Symmetry>,
+    goal_room: Option<usize>,
+    mask: Option<Mask>,
+    sparsity: Option<f64>,
+    partial: Option<Grid>,
+}
+
+impl Generator {
+    pub(crate) fn new(
+        algorithm: Box<dyn Algorithm>,
+        start_coords: Option<Coords>,
+        min_corridor: usize,
+    ) -> Generator {
+        Generator {
+            algorithm,
+            start_coords,
+            min_corridor,
+            observer: None,
+            symmetry: None,
+            goal_room: None,
+            mask: None,
+            sparsity: None,
+            partial: None,
+        }
+    }
+
+    /// Sets a partially carved grid whose carved cells the generated maze keeps and returns itself
+    pub(crate) fn partial(mut self, partial: Option<Grid>) -> Generator {
+        self.partial = partial;
+        self
+    }
+
+    /// Sets a symmetry of the generated maze and returns itself
+    pub(crate) const fn symmetry(mut self, symmetry: Option<Symmetry>) -> Generator {
+        self.symmetry = symmetry;
+        self
+    }
+
+    /// Sets a size of the goal room carved at the deepest point of the maze and returns itself
+    pub(crate) const fn goal_room(mut self, size: Option<usize>) -> Generator {
+        self.goal_room = size;
+        self
+    }
+
+    /// Sets a mask of solid areas the maze is generated around and returns itself
+    pub(crate) fn mask(mut self, mask: Option<Mask>) -> Generator {
+        self.mask = mask;
+        self
+    }
+
+    /// Sets a fraction of dead ends walled off after the generation and returns itself
+    pub(crate) const fn sparsity(mut self, fraction: Option<f64>) -> Generator {
+        self.sparsity = fraction;
+        self
+    }
+
+    /// Sets an observer of the generation and returns itself
+    pub(crate) fn observer(mut self, observer: Option<Box<dyn GenerationObserver>>) -> Generator {
+        self.observer = observer;
+        self
+    }
+
+    fn generate(&mut self, grid: &mut Grid, rng: &mut dyn RngCore) {
+        // Only passages carved by the algorithm are observed, not the swaps straightening corridors
+        grid.replace_observer(self.observer.take());
+        match self.symmetry {
+            Some(symmetry) => {
+                symmetry.generate(
+                    self.algorithm.as_mut(),
+                    grid,
+                    self.start_coords,
+                    self.min_corridor,
+                    rng,
+                );
+                self.observer = grid.replace_observer(None);
+            }
+            None => {
+                self.algorithm.generate(grid, self.start_coords, rng);
+                self.observer = grid.replace_observer(None);
+                straighten_corridors(grid, self.min_corridor);
+            }
+        }
+
+        if let Some(partial) = &self.partial {
+            join_partial(grid, partial, rng);
+        }
+        if let Some(mask) = &self.mask {
+            mask.apply(grid, rng);
+        }
+        if let Some(size) = self.goal_room {
+            carve_goal_room(grid, self.start_coords.unwrap_or((0, 0)), size, rng);
+        }
+        if let Some(fraction) = self.sparsity {
+            wall_off_dead_ends(grid, fraction, rng);
+        }
+    }
+}
+
+/// Walls off a given fraction of randomly chosen dead ends, removing the only passage leading into
+/// each of them, so every walled off dead end becomes an isolated cell
+fn wall_off_dead_ends(grid: &mut Grid, fraction: f64, rng: &mut dyn RngCore) {
+    let mut dead_ends = vec![];
+    for y in 0..grid.height() {
+        for x in 0..grid.width() {
+            if grid[(x, y)].passage_count() == 1 {
+                dead_ends.push((x, y));
+            }
+        }
+    }
+
+    shuffle(&mut dead_ends, rng);
+    let count = (fraction * dead_ends.len() as f64).round() as usize;
+    for coords in dead_ends.into_iter().take(count) {
+        // Walling off a neighbouring dead end might have already isolated this one
+        let Some(direction) = Pole::all()
+            .map(Cell::from)
+            .into_iter()
+            .find(|direction| grid.is_carved(coords, *direction))
+        else {
+            continue;
+        };
+
+        // Openings through the outer walls are left as they are
+        if grid.get_next_cell_coords(coords, direction).is_ok() {
+            grid.remove_passage(coords, direction).unwrap();
+        }
+    }
+}
+
+/// A cell waiting to be expanded by the A* search, ordered by its estimated total cost, so the
+/// cheapest one comes first out of a max-heap
+struct Frontier {
+    estimate: f64,
+    steps: usize,
+    coords: Coords,
+}
+
+impl PartialEq for Frontier {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl Eq for Frontier {}
+
+impl PartialOrd for Frontier {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Frontier {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        // Ties go to the cell further from the start, which is usually closer to the goal
+        other
+            .estimate
+            .total_cmp(&self.estimate)
+            .then(self.steps.cmp(&other.steps))
+    }
+}
+
+/// An orthogonal maze
+///
+/// Represents a standard orthogonal maze where each cell is a square containing zero or maximum
+/// three walls
+///
+/// Mazes are equal when their grids are equal, regardless of the algorithm they were generated
+/// with.
+#[derive(Clone, Resource)]
+pub struct OrthogonalMaze {
+    grid: Grid,
+    // Shared between clones, since an algorithm cannot be cloned
+    generator: Arc<Mutex<Generator>>,
+    openings_seed: Option<u64>,
+    openings_rng: Option<StdRng>,
+    // Continues the sequence the maze was generated with, for seeded post-processing
+    rng: Option<StdRng>,
+    // The seed the maze was generated from, if it is known
+    seed: Option<u64>,
+    // Whether the walls were generated by the algorithm, rather than read or left standing
+    generated: bool,
+}
+
+impl OrthogonalMaze {
+    /// Returns a new instance of an orthogonal maze with a given width and height
+    ///
+    /// All the walls are standing, and [regenerate](OrthogonalMaze::regenerate) uses
+    /// [RecursiveBacktracking] to generate the maze.
+    pub fn new(width: usize, height: usize) -> OrthogonalMaze {
+        OrthogonalMaze::with_generator(
+            width,
+            height,
+            Generator::new(Box::new(RecursiveBacktracking), None, 0),
+        )
+    }
+
+    pub(crate) fn with_generator(
+        width: usize,
+        height: usize,
+        generator: Generator,
+    ) -> OrthogonalMaze {
+        OrthogonalMaze {
+            grid: Grid::new(width, height),
+            generator: Arc::new(Mutex::new(generator)),
+            openings_seed: None,
+            openings_rng: None,
+            rng: None,
+            seed: None,
+            generated: false,
+        }
+    }
+
+    /// Sets a seed for placing random openings, e.g. [entrances](OrthogonalMaze::add_random_entrances)
+    pub(crate) fn set_openings_seed(&mut self, seed: Option<u64>) {
+        self.openings_seed = seed;
+        self.openings_rng = seed.map(StdRng::seed_from_u64);
+    }
+
+    /// Sets a seed the maze was generated from, which is a part of its [id](OrthogonalMaze::id)
+    pub(crate) const fn set_seed(&mut self, seed: Option<u64>) {
+        self.seed = seed;
+    }
+
+    /// Runs the stored algorithm through the grid, keeping a given random number generator for
+    /// post-processing
+    pub(crate) fn generate(&mut self, mut rng: StdRng) {
+        self.run_generator(&mut rng);
+        self.rng = Some(rng);
+    }
+
+    /// Runs the stored algorithm through the grid with a custom random number generator, keeping a
+    /// generator seeded from it for post-processing
+    pub(crate) fn generate_with(&mut self, mut rng: &mut dyn RngCore) {
+        self.run_generator(rng);
+        self.rng = Some(StdRng::from_rng(&mut rng));
+    }
+
+    fn run_generator(&mut self, rng: &mut dyn RngCore) {
+        self.generator
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .generate(&mut self.grid, rng);
+        self.generated = true;
+    }
+
+    /// Clears the grid and generates the maze again from a given seed, reusing the grid allocation
+    ///
+    /// The maze keeps its dimensions, algorithm, start coords and [min
+    /// corridor](super::OrthogonalMazeBuilder::min_corridor) hint, so only the walls change. Any
+    /// post-processing, e.g. [culled dead ends](OrthogonalMaze::cull_dead_ends), is discarded.
+    /// Building a maze with the same [seed](super::OrthogonalMazeBuilder::seed) generates the same
+    /// walls. Random openings are placed from the [openings
+    /// seed](super::OrthogonalMazeBuilder::openings_seed) again, if there is one.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let mut maze = OrthogonalMazeBuilder::new()
+    ///     .algorithm(Box::new(Kruskal::new()))
+    ///     .seed(1)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// for seed in 2..10 {
+    ///     maze.regenerate(seed);
+    ///     assert!(maze.is_valid());
+    /// }
+    ///
+    /// maze.regenerate(1);
+    /// assert_eq!(maze, OrthogonalMazeBuilder::new().algorithm(Box::new(Kruskal::new())).seed(1).build().unwrap());
+    /// ```
+    pub fn regenerate(&mut self, seed: u64) {
+        self.grid.reset();
+        self.generate(StdRng::seed_from_u64(seed));
+        self.seed = Some(seed);
+        self.set_openings_seed(self.openings_seed);
+    }
+
+    /// Returns the [name](super::Algorithm::name) of the algorithm the maze is generated with, e.g.
+    /// for logging mazes built with randomly chosen algorithms
+    ///
+    /// Returns `None` for mazes which weren't generated by an algorithm, e.g.
+    /// [new](OrthogonalMaze::new) ones or [read](OrthogonalMaze::read_from) ones, until they are
+    /// [regenerated](OrthogonalMaze::regenerate).
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let maze = OrthogonalMazeBuilder::new()
+    ///     .algorithm(Box::new(Prim::new()))
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(maze.algorithm_name(), Some("Prim"));
+    /// assert_eq!(OrthogonalMaze::new(5, 5).algorithm_name(), None);
+    /// ```
+    pub fn algorithm_name(&self) -> Option<&'static str> {
+        if !self.generated {
+            return None;
+        }
+
+        let generator = self
+            .generator
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        Some(generator.algorithm.name())
+    }
+
+    /// Returns a short identifier of the maze, e.g. to share which maze was solved on a leaderboard
+    ///
+    /// The id consists of the dimensions of the maze and a code of 8 characters hashed from all the
+    /// passages and openings of the maze, whether it is a [torus](Grid::new_torus), and its
+    /// [seed](super::OrthogonalMazeBuilder::seed),
+    /// e.g. `10x10-7KQ2-M9XD`. The code only uses digits and capital letters without the easily
+    /// confused `I`, `L`, `O` and `U`, so it is easy to type. Mazes with the same walls generated
+    /// from the same seed have the same id on every platform, while changing any wall changes it
+    /// with an overwhelming probability. Unlike a full serialization, the maze can't be restored
+    /// from its id.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let build = || OrthogonalMazeBuilder::new().width(12).height(8).seed(7).build().unwrap();
+    /// let mut maze = build();
+    ///
+    /// assert_eq!(maze.id(), build().id());
+    /// assert!(maze.id().starts_with("12x8-"));
+    ///
+    /// maze.get_grid_mut().toggle_wall((0, 0), Pole::E).unwrap();
+    /// assert_ne!(maze.id(), build().id());
+    /// ```
+    pub fn id(&self) -> String {
+        // A 64-bit FNV-1a hash, which is stable across platforms and releases unlike the std one
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        let mut write = |bytes: &[u8]| {
+            for byte in bytes {
+                hash = (hash ^ *byte as u64).wrapping_mul(0x0100_0000_01b3);
+            }
+        };
+
+        write(&(self.grid.width() as u64).to_le_bytes());
+        write(&(self.grid.height() as u64).to_le_bytes());
+        write(&[u8::from(self.grid.is_torus())]);
+        for (_, cell) in self.iter() {
+            write(&[cell.bits()]);
+        }
+        match self.seed {
+            Some(seed) => {
+                write(&[1]);
+                write(&seed.to_le_bytes());
+            }
+            None => write(&[0]),
+        }
+
+        // 40 bits of the hash make 8 characters of Crockford's base32
+        const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+        let bits = (hash ^ (hash >> 40)) & ((1 << 40) - 1);
+        let code: String = (0..8)
+            .rev()
+            .map(|i| ALPHABET[(bits >> (i * 5)) as usize & 31] as char)
+            .collect();
+
+        format!(
+            "{}x{}-{}-{}",
+            self.grid.width(),
+            self.grid.height(),
+            &code[..4],
+            &code[4..]
+        )
+    }
+
+    /// Returns a mutable ref to a grid
+    pub const fn get_grid_mut(&mut self) -> &mut Grid {
+        &mut self.grid
+    }
+
+    pub(crate) const fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    /// Returns `true` if a maze is valid. Otherwise, returns `false`
+    ///
+    /// A maze generated with a [Mask] is valid when all the cells out of its solid areas are
+    /// reachable.
+    pub fn is_valid(&self) -> bool {
+        let generator = self
+            .generator
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        generator.mask.as_ref().map_or_else(
+            || validate(&self.grid),
+            |mask| validate_masked(&self.grid, mask),
+        )
+    }
+
+    /// Prints a maze formatted as [AsciiNarrow](super::AsciiNarrow) to the standard output
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let maze = OrthogonalMazeBuilder::new().width(5).height(5).build().unwrap();
+    /// maze.print();
+    /// ```
+    pub fn print(&self) {
+        print!("{}", self);
+    }
+
+    /// Returns a maze converted by a given formatter, without writing to the filesystem
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let maze = OrthogonalMazeBuilder::new().width(5).height(5).build().unwrap();
+    /// let ascii = maze.format(AsciiNarrow).0;
+    ///
+    /// assert_eq!(ascii.lines().count(), 6);
+    /// ```
+    pub fn format<F, T>(&self, formatter: F) -> T
+    where
+        F: Formatter<T>,
+        T: Saveable,
+    {
+        formatter.format(&self.grid)
+    }
+
+    /// Saves a maze into a file to a given path using a given formatter
+    ///
+    /// Returns a [MazeSaveError] if the formatter refuses to [convert](Formatter::try_format) the
+    /// maze, e.g. into an image over the [maximum number of
+    /// pixels](super::Image::max_pixels), or the file cannot be written.
+    pub fn save<F, T>(&self, path: &str, formatter: F) -> Result<String, MazeSaveError>
+    where
+        F: Formatter<T>,
+        T: Saveable,
+    {
+        let data = formatter
+            .try_format(&self.grid)
+            .map_err(|err| MazeSaveError::reason(err.reason))?;
+        Saveable::save(&data, path)
+    }
+
+    /// Returns the maze as a wall grid of `2 * height + 1` rows of `2 * width + 1` entries, where
+    /// `true` stands for a wall, indexed by rows first, i.e. `walls[row][column]`
+    ///
+    /// The cell `(x, y)` is at `walls[2 * y + 1][2 * x + 1]` and is never a wall, its walls are the
+    /// adjacent entries, and the entries at even rows and columns are the wall joints, which are
+    /// always walls. Openings in the outer walls are not walls either. A [torus](Grid::new_torus) is
+    /// laid out flat, so every wall wrapping around an axis is on both outer sides along that axis,
+    /// and [from_wall_grid](OrthogonalMaze::from_wall_grid) reads its passages back as openings.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let mut maze = OrthogonalMaze::new(2, 1);
+    /// maze.get_grid_mut().carve_passage((0, 0), Cell::EAST).unwrap();
+    ///
+    /// assert_eq!(
+    ///     maze.to_wall_grid(),
+    ///     vec![
+    ///         vec![true, true, true, true, true],
+    ///         vec![true, false, false, false, true],
+    ///         vec![true, true, true, true, true],
+    ///     ]
+    /// );
+    /// assert_eq!(OrthogonalMaze::from_wall_grid(&maze.to_wall_grid()).unwrap(), maze);
+    /// ```
+    pub fn to_wall_grid(&self) -> Vec<Vec<bool>> {
+        let (width, height) = (self.grid.width(), self.grid.height());
+        let mut walls = vec![vec![true; 2 * width + 1]; 2 * height + 1];
+
+        for ((x, y), cell) in self.iter() {
+            walls[2 * y + 1][2 * x + 1] = false;
+            for pole in Pole::all() {
+                if cell.contains(Cell::from(pole)) {
+                    let (column, row) = wall_entry((x, y), pole);
+                    walls[row][column] = false;
+                }
+            }
+        }
+
+        walls
+    }
+
+    /// Returns a new instance of an orthogonal maze with the walls of a given wall grid, as
+    /// returned by [to_wall_grid](OrthogonalMaze::to_wall_grid)
+    ///
+    /// Only the entries between cells and along the outer sides are read, so the cells and the
+    /// wall joints may be marked either way. A missing outer wall becomes an opening.
+    ///
+    /// Returns a [ParseError] if the rows have different lengths, or the wall grid doesn't have an
+    /// odd number of rows and columns of at least 3 each.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let walls = vec![
+    ///     vec![true, false, true],
+    ///     vec![true, false, true],
+    ///     vec![true, true, true],
+    /// ];
+    /// let maze = OrthogonalMaze::from_wall_grid(&walls).unwrap();
+    ///
+    /// assert_eq!(maze[(0, 0)], Cell::NORTH);
+    /// assert!(OrthogonalMaze::from_wall_grid(&walls[..2]).is_err());
+    /// ```
+    pub fn from_wall_grid(walls: &[Vec<bool>]) -> Result<OrthogonalMaze, ParseError> {
+        let (rows, columns) = (walls.len(), walls.first().map_or(0, Vec::len));
+        if walls.iter().any(|row| row.len() != columns) {
+            return Err(ParseError::reason(
+                "Rows of a wall grid have different lengths",
+            ));
+        }
+        if rows < 3 || columns < 3 || rows % 2 == 0 || columns % 2 == 0 {
+            return Err(ParseError::reason(format!(
+                "A wall grid must have an odd number of at least 3 rows and columns, but it is {}x{}",
+                columns, rows
+            )));
+        }
+
+        let mut maze = OrthogonalMaze::new(columns / 2, rows / 2);
+        let grid = maze.get_grid_mut();
+        for y in 0..grid.height() {
+            for x in 0..grid.width() {
+                for pole in Pole::all() {
+                    let (column, row) = wall_entry((x, y), pole);
+                    if walls[row][column] {
+                        continue;
+                    }
+
+                    let direction = Cell::from(pole);
+                    if grid.get_next_cell_coords((x, y), direction).is_ok() {
+                        grid.carve_passage((x, y), direction).unwrap();
+                    } else {
+                        grid.carve_opening((x, y), direction).unwrap();
+                    }
+                }
+            }
+        }
+
+        Ok(maze)
+    }
+
+    /// Returns the maze as a JSON adjacency list, i.e. an object mapping every cell to the list of
+    /// the cells connected to it through carved passages, e.g. for graph libraries
+    ///
+    /// Cells are keyed by their coords as `"x,y"` strings, and listed with the same keys. Every
+    /// cell of the maze is a key, including the ones without any passages. Passages wrapping around
+    /// a [torus](Grid::new_torus) join the cells on the opposite edges, while the openings in the
+    /// outer walls are not a part of the graph and are left out. Thus, the export is lossy, and
+    /// [from_adjacency_json](OrthogonalMaze::from_adjacency_json) restores the passages without
+    /// the openings.
+    ///
+    /// Requires the `serde` feature.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let mut maze = OrthogonalMaze::new(2, 2);
+    /// let grid = maze.get_grid_mut();
+    /// grid.carve_passage((0, 0), Cell::EAST).unwrap();
+    /// grid.carve_passage((1, 0), Cell::SOUTH).unwrap();
+    ///
+    /// let json = maze.to_adjacency_json();
+    ///
+    /// assert_eq!(json, r#"{"0,0":["1,0"],"0,1":[],"1,0":["1,1","0,0"],"1,1":["1,0"]}"#);
+    /// assert_eq!(OrthogonalMaze::from_adjacency_json(&json).unwrap(), maze);
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn to_adjacency_json(&self) -> String {
+        let key = |(x, y): Coords| format!("{},{}", x, y);
+        let adjacency: BTreeMap<String, Vec<String>> = self
+            .iter()
+            .map(|(coords, _)| {
+                let neighbours = self.connected_neighbours(coords).map(key).collect();
+                (key(coords), neighbours)
+            })
+            .collect();
+
+        serde_json::to_string(&adjacency).expect("An adjacency list is a map of strings")
+    }
+
+    /// Returns a new instance of an orthogonal maze with the passages of a given JSON adjacency
+    /// list, as returned by [to_adjacency_json](OrthogonalMaze::to_adjacency_json)
+    ///
+    /// The size of the maze is implied by the cells farthest from the origin, so all the cells
+    /// must be listed, and every passage must be listed from both of its cells. A passage between
+    /// the cells on the opposite edges makes the maze a [torus](Grid::new_torus), so a torus
+    /// without such passages is restored as a plain maze. Returns a [ParseError] if the JSON isn't
+    /// an object of lists of `"x,y"` strings, any cell is missing, the maze is too large to
+    /// address, or cells are connected one way only or aren't adjacent.
+    ///
+    /// Requires the `serde` feature.
+    #[cfg(feature = "serde")]
+    pub fn from_adjacency_json(json: &str) -> Result<OrthogonalMaze, ParseError> {
+        let adjacency: BTreeMap<String, Vec<String>> = serde_json::from_str(json)
+            .map_err(|error| ParseError::reason(format!("Invalid adjacency list: {}", error)))?;
+
+        let parse = |key: &str| {
+            key.split_once(',')
+                .and_then(|(x, y)| Some((x.trim().parse().ok()?, y.trim().parse().ok()?)))
+                .ok_or_else(|| {
+                    ParseError::reason(format!("Invalid cell `{}`, expected `x,y`", key))
+                })
+        };
+        let mut cells: BTreeMap<Coords, Vec<Coords>> = BTreeMap::new();
+        for (key, neighbours) in &adjacency {
+            let neighbours = neighbours
+                .iter()
+                .map(|neighbour| parse(neighbour))
+                .collect::<Result<_, _>>()?;
+            cells.insert(parse(key)?, neighbours);
+        }
+
+        if cells.is_empty() {
+            return Err(ParseError::reason("An adjacency list has no cells"));
+        }
+        let too_large = || ParseError::reason("An adjacency list lists cells of too large a maze");
+        let side = |coord: fn(&Coords) -> usize| {
+            cells
+                .keys()
+                .map(|coords| coord(coords).checked_add(1))
+                .max()
+                .flatten()
+                .ok_or_else(too_large)
+        };
+        let (width, height) = (side(|(x, _)| *x)?, side(|(_, y)| *y)?);
+        let count = width.checked_mul(height).ok_or_else(too_large)?;
+        if cells.len() != count {
+            return Err(ParseError::reason(format!(
+                "An adjacency list of a {}x{} maze must list all of its {} cells, but it lists {}",
+                width,
+                height,
+                count,
+                cells.len()
+            )));
+        }
+
+        let mut edges = vec![];
+        for (&from, neighbours) in &cells {
+            for &to in neighbours {
+                if !cells.get(&to).is_some_and(|back| back.contains(&from)) {
+                    return Err(ParseError::reason(format!(
+                        "Cell ({}, {}) is connected to ({}, {}), but not the other way around",
+                        from.0, from.1, to.0, to.1
+                    )));
+                }
+                edges.push((from, to));
+            }
+        }
+
+        // Only a torus joins cells that are not next to each other
+        let torus = edges
+            .iter()
+            .any(|(from, to)| from.0.abs_diff(to.0) + from.1.abs_diff(to.1) != 1);
+        let mut maze = OrthogonalMaze::new(width, height);
+        if torus {
+            maze.grid = Grid::new_torus(width, height);
+        }
+        maze.grid
+            .carve_edges(&edges)
+            .map_err(|error| ParseError::reason(error.to_string()))?;
+
+        Ok(maze)
+    }
+
+    /// Writes the maze in a compact binary format into a given writer, e.g. a file, a socket or a
+    /// compressing stream
+    ///
+    /// The format starts with the `KNOS` magic bytes, a version byte and a byte of flags, where the
+    /// lowest bit marks a maze wrapping around like a [torus](Grid::new_torus), followed by the
+    /// width and the height of the maze as little-endian `u32`. Then, every row of cells takes half a byte
+    /// per cell, where the lower 4 bits of a byte are the [bits](Cell) of the western cell of a
+    /// pair and the higher ones of the eastern cell, and a row of an odd width ends with a zero
+    /// half of a byte. Thus, the format keeps all the passages and openings of the maze. Returns
+    /// an error of the writer, or an [InvalidInput](io::ErrorKind::InvalidInput) error if a side
+    /// of the maze exceeds [u32::MAX] cells.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let maze = OrthogonalMazeBuilder::new().width(7).height(5).build().unwrap();
+    ///
+    /// let mut bytes = vec![];
+    /// maze.write_to(&mut bytes).unwrap();
+    ///
+    /// assert_eq!(bytes.len(), 4 + 1 + 1 + 8 + 4 * 5);
+    /// assert_eq!(OrthogonalMaze::read_from(bytes.as_slice()).unwrap(), maze);
+    /// ```
+    pub fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let (width, height) = (self.grid.width(), self.grid.height());
+        writer.write_all(&BINARY_MAGIC)?;
+        let flags = if self.grid.is_torus() {
+            BINARY_TORUS
+        } else {
+            0
+        };
+        writer.write_all(&[BINARY_VERSION, flags])?;
+        for side in [width, height] {
+            let side = u32::try_from(side).map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "A {}x{} maze is too large for the binary format",
+                        width, height
+                    ),
+                )
+            })?;
+            writer.write_all(&side.to_le_bytes())?;
+        }
+
+        let mut row = Vec::with_capacity(width.div_ceil(2));
+        for y in 0..height {
+            row.clear();
+            row.extend((0..width).step_by(2).map(|x| {
+                let east = if x + 1 < width {
+                    self.grid[(x + 1, y)].bits()
+                } else {
+                    0
+                };
+                self.grid[(x, y)].bits() | east << 4
+            }));
+            writer.write_all(&row)?;
+        }
+
+        writer.flush()
+    }
+
+    /// Returns the maze in the binary format of [write_to](OrthogonalMaze::write_to)
+    ///
+    /// # Panics
+    ///
+    /// Panics if a side of the maze exceeds [u32::MAX] cells.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        self.write_to(&mut bytes)
+            .expect("A maze exceeds the binary format size");
+        bytes
+    }
+
+    /// Reads a maze in the binary format of [write_to](OrthogonalMaze::write_to) from a given
+    /// reader, without buffering the whole input first
+    ///
+    /// The header is validated as it is read, so an input with wrong magic bytes or an unsupported
+    /// version fails before anything else is read. Mazes written in an older version of the format
+    /// are read as well, while a version newer than the one of this release fails with an error
+    /// asking to update. Cells are read in small chunks, so a header claiming a huge maze can't
+    /// allocate more than the input actually has. Returns a [ParseError] if the header is invalid,
+    /// e.g. has unknown flags, the maze has no cells, two neighbouring cells disagree on the wall
+    /// between them, or the reader fails or ends too early.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let maze = OrthogonalMazeBuilder::new().build().unwrap();
+    /// let bytes = maze.to_bytes();
+    ///
+    /// assert_eq!(OrthogonalMaze::read_from(&bytes[..]).unwrap(), maze);
+    /// assert!(OrthogonalMaze::read_from(&b"PNG!"[..]).is_err());
+    /// ```
+    pub fn read_from<R: Read>(mut reader: R) -> Result<OrthogonalMaze, ParseError> {
+        let mut read = |buf: &mut [u8], part: &str| {
+            reader.read_exact(buf).map_err(|err| {
+                ParseError::reason(format!(
+                    "Cannot read {} of the binary format: {}",
+                    part, err
+                ))
+            })
+        };
+
+        let mut magic = [0; 4];
+        read(&mut magic, "the magic bytes")?;
+        if magic != BINARY_MAGIC {
+            return Err(ParseError::reason(format!(
+                "Unknown magic bytes {:?} of the binary format, expected {:?}",
+                magic, BINARY_MAGIC
+            )));
+        }
+
+        let mut version = [0];
+        read(&mut version, "the version")?;
+        let mut flags = [0];
+        match version[0] {
+            // The first version had no flags
+            1 => {}
+            BINARY_VERSION => read(&mut flags, "the flags")?,
+            newer if newer > BINARY_VERSION => {
+                return Err(ParseError::reason(format!(
+                    "Version {} of the binary format is newer than the supported version {}, the maze requires a newer release",
+                    newer, BINARY_VERSION
+                )));
+            }
+            unknown => {
+                return Err(ParseError::reason(format!(
+                    "Unsupported version {} of the binary format, expected {}",
+                    unknown, BINARY_VERSION
+                )));
+            }
+        }
+        if flags[0] & !BINARY_TORUS != 0 {
+            return Err(ParseError::reason(format!(
+                "Unknown flags {:#010b} of the binary format",
+                flags[0]
+            )));
+        }
+
+        let mut side = [0; 4];
+        read(&mut side, "the width")?;
+        let width = u32::from_le_bytes(side) as usize;
+        read(&mut side, "the height")?;
+        let height = u32::from_le_bytes(side) as usize;
+        let row_bytes = width.div_ceil(2);
+        let Some(total) = row_bytes.checked_mul(height).filter(|total| *total > 0) else {
+            return Err(ParseError::reason(format!(
+                "A maze in the binary format must have at least a single cell and fit into memory, but it is {}x{}",
+                width, height
+            )));
+        };
+
+        let mut cells = vec![];
+        let mut chunk = [0; 4096];
+        let mut remaining = total;
+        while remaining > 0 {
+            let len = remaining.min(chunk.len());
+            read(&mut chunk[..len], "the cells")?;
+            for (i, byte) in chunk[..len].iter().enumerate() {
+                let column = (total - remaining + i) % row_bytes * 2;
+                cells.push(Cell::from_bits_truncate(byte & 0b1111));
+                if column + 1 < width {
+                    cells.push(Cell::from_bits_truncate(byte >> 4));
+                }
+            }
+            remaining -= len;
+        }
+
+        let mut maze = OrthogonalMaze::new(width, height);
+        let grid = maze.get_grid_mut();
+        if flags[0] & BINARY_TORUS != 0 {
+            *grid = Grid::new_torus(width, height);
+        }
+        for (index, cell) in cells.into_iter().enumerate() {
+            let (x, y) = (index % width, index / width);
+            for pole in Pole::all() {
+                let direction = Cell::from(pole);
+                let carved = cell.contains(direction);
+                // The cells read before carved their passages to this one, which are mostly the
+                // northern and the western ones, unless the maze wraps around
+                let next = grid.get_next_cell_coords((x, y), direction);
+                let earlier = next.as_ref().is_ok_and(|(nx, ny)| ny * width + nx < index);
+                match next {
+                    Ok((nx, ny)) if earlier && grid.is_carved((x, y), direction) != carved => {
+                        return Err(ParseError::reason(format!(
+                            "Cells ({}, {}) and ({}, {}) of the binary format disagree on the wall between them",
+                            nx, ny, x, y
+                        )));
+                    }
+                    Ok(_) if carved && !earlier => {
+                        grid.carve_passage((x, y), direction).unwrap();
+                    }
+                    Err(_) if carved => grid.carve_opening((x, y), direction).unwrap(),
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(maze)
+    }
+
+    /// Returns a maze from bytes in the binary format of [write_to](OrthogonalMaze::write_to),
+    /// or a [ParseError] on the same conditions as [read_from](OrthogonalMaze::read_from)
+    pub fn from_bytes(bytes: &[u8]) -> Result<OrthogonalMaze, ParseError> {
+        OrthogonalMaze::read_from(bytes)
+    }
+
+    /// Returns coords of all the dead ends, i.e. cells with a single carved passage
+    pub fn dead_ends(&self) -> Vec<Coords> {
+        self.iter()
+            .filter(|(_, cell)| cell.passage_count() == 1)
+            .map(|(coords, _)| coords)
+            .collect()
+    }
+
+    /// Returns coords of all the junctions, i.e. cells with three or more carved passages where a
+    /// solver has to choose a way to go
+    pub fn junctions(&self) -> Vec<Coords> {
+        self.iter()
+            .filter(|(_, cell)| cell.passage_count() >= 3)
+            .map(|(coords, _)| coords)
+            .collect()
+    }
+
+    /// Returns the numbers of cells with 0, 1, 2, 3 and 4 carved passages, indexed by the number
+    /// of passages
+    ///
+    /// Openings in the outer walls are counted as passages, like for [dead
+    /// ends](OrthogonalMaze::dead_ends) and [junctions](OrthogonalMaze::junctions). The histogram
+    /// characterizes the structure of a maze, e.g. a perfect maze has no cells without passages,
+    /// and algorithms differ in the shares of dead ends, corridors and junctions they produce.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let maze = OrthogonalMazeBuilder::new().width(5).height(5).build().unwrap();
+    /// let histogram = maze.degree_histogram();
+    ///
+    /// assert_eq!(histogram.iter().sum::<usize>(), 25);
+    /// assert_eq!(histogram[0], 0);
+    /// assert_eq!(histogram[1], maze.dead_ends().len());
+    /// ```
+    pub fn degree_histogram(&self) -> [usize; 5] {
+        let mut histogram = [0; 5];
+        for (_, cell) in self.iter() {
+            histogram[cell.passage_count()] += 1;
+        }
+
+        histogram
+    }
+
+    /// Culls dead ends by carving a passage from a dead end into one of its walled neighbours,
+    /// which turns a dead end into a loop. Neighbours that are dead ends themselves are preferred,
+    /// so a single passage may cull two dead ends at once.
+    ///
+    /// The `probability` closure receives the coords of a dead end and returns the chance of it
+    /// being culled, where `0.0` always keeps it and `1.0` always culls it. Values outside of this
+    /// range are clamped. This allows culling dead ends more aggressively in some regions of the
+    /// maze than in others. Dead ends are culled with the random number generator the maze was
+    /// generated with, so a [seeded](super::OrthogonalMazeBuilder::seed) maze is culled the same
+    /// way every time.
+    ///
+    /// Returns the number of culled dead ends, where a passage joining two dead ends counts both.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let mut maze = OrthogonalMazeBuilder::new().width(20).height(20).build().unwrap();
+    ///
+    /// // Keep dead ends dense in the center and sparse at the edges
+    /// maze.cull_dead_ends(|(x, y)| {
+    ///     let dx = x.abs_diff(10) as f64 / 10.0;
+    ///     let dy = y.abs_diff(10) as f64 / 10.0;
+    ///     dx.max(dy)
+    /// });
+    /// ```
+    pub fn cull_dead_ends<F>(&mut self, probability: F) -> usize
+    where
+        F: Fn(Coords) -> f64,
+    {
+        let mut rng = self
+            .rng
+            .take()
+            .unwrap_or_else(|| StdRng::from_rng(&mut rand::rng()));
+        let mut culled = 0;
+
+        for coords in self.dead_ends() {
+            // A previously carved passage might have already culled this dead end
+            if self.grid[coords].passage_count() != 1 {
+                continue;
+            }
+
+            let chance = probability(coords);
+            if chance.is_nan() || !rng.random_bool(chance.clamp(0.0, 1.0)) {
+                continue;
+            }
+
+            let mut dirs: Vec<Cell> = self.walled_neighbours(coords).map(|(dir, _)| dir).collect();
+            shuffle(&mut dirs, &mut rng);
+
+            let dir = dirs
+                .iter()
+                .find(|dir| {
+                    self.grid
+                        .get_next_cell_coords(coords, **dir)
+                        .is_ok_and(|next| self.grid[next].passage_count() == 1)
+                })
+                .or_else(|| dirs.first());
+
+            if let Some(dir) = dir {
+                let joins_dead_end = self
+                    .grid
+                    .get_next_cell_coords(coords, *dir)
+                    .is_ok_and(|next| self.grid[next].passage_count() == 1);
+                self.grid.carve_passage(coords, *dir).unwrap();
+                culled += if joins_dead_end { 2 } else { 1 };
+            }
+        }
+
+        self.rng = Some(rng);
+        culled
+    }
+
+    /// Culls random dead ends until there are no more than a given number of them, and returns the
+    /// final number of dead ends
+    ///
+    /// Dead ends are culled the same way as with [cull_dead_ends](OrthogonalMaze::cull_dead_ends),
+    /// except that a passage joining two dead ends is avoided when it would go below the target.
+    /// Dead ends are never added, so a maze with fewer dead ends than the target is left as is.
+    /// The exact target isn't always achievable, e.g. two adjacent dead ends may only be culled
+    /// together, so the final number may be off by one, or higher when the remaining dead ends have
+    /// no walled neighbours. Dead ends are chosen with the random number generator the maze was
+    /// generated with, so a [seeded](super::OrthogonalMazeBuilder::seed) maze is adjusted the same
+    /// way every time.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let mut maze = OrthogonalMazeBuilder::new().width(20).height(20).seed(1).build().unwrap();
+    ///
+    /// assert_eq!(maze.adjust_dead_ends(15), 15);
+    /// assert_eq!(maze.dead_ends().len(), 15);
+    /// assert!(maze.is_valid());
+    /// ```
+    pub fn adjust_dead_ends(&mut self, target: usize) -> usize {
+        let mut rng = self
+            .rng
+            .take()
+            .unwrap_or_else(|| StdRng::from_rng(&mut rand::rng()));
+
+        let count = loop {
+            let mut dead_ends = self.dead_ends();
+            let excess = dead_ends.len().saturating_sub(target);
+            if excess == 0 {
+                break dead_ends.len();
+            }
+            shuffle(&mut dead_ends, &mut rng);
+
+            // Joining two dead ends culls both, which overshoots a target that is one away
+            let passage = dead_ends.iter().find_map(|coords| {
+                let mut dirs: Vec<(Cell, usize)> = self
+                    .walled_neighbours(*coords)
+                    .map(|(dir, next)| (dir, self.grid[next].passage_count()))
+                    .filter(|(_, passages)| excess > 1 || *passages != 1)
+                    .collect();
+                shuffle(&mut dirs, &mut rng);
+                dirs.sort_by_key(|(_, passages)| *passages != 1);
+                dirs.first().map(|(dir, _)| (*coords, *dir))
+            });
+
+            let Some((coords, dir)) = passage else {
+                break dead_ends.len();
+            };
+            self.grid.carve_passage(coords, dir).unwrap();
+        };
+
+        self.rng = Some(rng);
+        count
+    }
+
+    /// Carves passages between all the adjacent boundary cells, so that they form a ring of an
+    /// open corridor along the outer walls, e.g. for players circling the maze
+    ///
+    /// The ring joins cells that are already connected, so a perfect maze intentionally stops
+    /// being perfect. In a single row or column, the boundary cells are joined into a straight
+    /// corridor instead. Returns the number of newly carved passages.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let mut maze = OrthogonalMazeBuilder::new().width(6).height(4).build().unwrap();
+    /// maze.carve_border_loop();
+    ///
+    /// assert!(maze.solve((0, 0), (5, 3)).is_some_and(|solution| solution.length() == 8));
+    /// ```
+    pub fn carve_border_loop(&mut self) -> usize {
+        let boundary = self.grid.boundary_cells();
+        let next = boundary.iter().cycle().skip(1);
+        let mut carved = 0;
+
+        for (&from, &to) in boundary.iter().zip(next) {
+            let direction = Pole::all().into_iter().map(Cell::from).find(|dir| {
+                self.grid
+                    .get_next_cell_coords(from, *dir)
+                    .is_ok_and(|coords| coords == to)
+            });
+
+            // The last and the first cells of a single row or column are not adjacent
+            if let Some(direction) = direction {
+                if !self.grid.is_carved(from, direction) {
+                    self.grid.carve_passage(from, direction).unwrap();
+                    carved += 1;
+                }
+            }
+        }
+
+        carved
+    }
+
+    /// Returns a larger maze where every cell of this maze is replaced with a `sub_size` by
+    /// `sub_size` sub-maze, so that the structure repeats at two scales
+    ///
+    /// Sub-mazes are generated with [RecursiveBacktracking]. Every passage between two cells of
+    /// this maze becomes a single passage between the middle cells of the adjacent sides of their
+    /// sub-mazes, and every opening in the outer walls is carved in the middle of the side of its
+    /// sub-maze. Thus, a perfect maze remains a perfect maze, and the whole maze stays connected.
+    /// A [torus](Grid::new_torus) becomes a larger torus. Sub-mazes are generated from a copy of
+    /// the random number generator the maze was generated with, so a
+    /// [seeded](super::OrthogonalMazeBuilder::seed) maze is fractalized the same way every time.
+    ///
+    /// Returns a [MazeError] if `sub_size` is 0.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let maze = OrthogonalMazeBuilder::new().width(4).height(3).build().unwrap();
+    /// let fractal = maze.fractalize(5).unwrap();
+    ///
+    /// assert!(fractal.is_valid());
+    /// ```
+    pub fn fractalize(&self, sub_size: usize) -> Result<OrthogonalMaze, MazeError> {
+        if sub_size == 0 {
+            return Err(MazeError::reason("Sub-mazes must be at least 1x1"));
+        }
+
+        let (width, height) = (self.grid.width(), self.grid.height());
+        let mut maze = OrthogonalMaze::new(width * sub_size, height * sub_size);
+        if self.grid.is_torus() {
+            maze.grid = Grid::new_torus(width * sub_size, height * sub_size);
+        }
+        let mut rng = self
+            .rng
+            .clone()
+            .unwrap_or_else(|| StdRng::from_rng(&mut rand::rng()));
+        let middle = sub_size / 2;
+
+        for (coords, cell) in self.iter() {
+            let origin = (coords.0 * sub_size, coords.1 * sub_size);
+            let offset = |(x, y): Coords| (origin.0 + x, origin.1 + y);
+
+            let mut sub_grid = Grid::new(sub_size, sub_size);
+            RecursiveBacktracking.generate(&mut sub_grid, None, &mut rng);
+            for y in 0..sub_size {
+                for x in 0..sub_size {
+                    for direction in [Cell::EAST, Cell::SOUTH] {
+                        if sub_grid.is_carved((x, y), direction) {
+                            maze.grid.carve_passage(offset((x, y)), direction).unwrap();
+                        }
+                    }
+                }
+            }
+
+            // The middle cell of each side of the sub-maze joins its neighbour or the outside
+            for pole in Pole::all() {
+                let direction = Cell::from(pole);
+                if !cell.contains(direction) {
+                    continue;
+                }
+
+                let side = match pole {
+                    Pole::N => (middle, 0),
+                    Pole::S => (middle, sub_size - 1),
+                    Pole::W => (0, middle),
+                    Pole::E => (sub_size - 1, middle),
+                };
+                if self.grid.get_next_cell_coords(coords, direction).is_ok() {
+                    maze.grid.carve_passage(offset(side), direction).unwrap();
+                } else {
+                    maze.grid.carve_opening(offset(side), direction).unwrap();
+                }
+            }
+        }
+
+        Ok(maze)
+    }
+
+    /// Connects disconnected parts of the maze into one by carving the fewest passages needed,
+    /// i.e. one less than the number of parts, and returns the number of carved passages
+    ///
+    /// Parts are detected with a union-find over the carved passages, and each carved passage goes
+    /// through a randomly chosen wall between two cells of different parts. Thus, a maze with
+    /// isolated pockets becomes solvable, and a connected maze is left as is. The solid cells of a
+    /// [mask](super::OrthogonalMazeBuilder::mask) keep all of their walls, so they stay parts of
+    /// their own. Walls are chosen with the random number generator the maze was generated with, so
+    /// a [seeded](super::OrthogonalMazeBuilder::seed) maze is connected the same way every time.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let mut maze = OrthogonalMaze::new(4, 3);
+    ///
+    /// assert_eq!(maze.connect_components(), 11);
+    /// assert!(maze.is_valid());
+    /// assert_eq!(maze.connect_components(), 0);
+    /// ```
+    pub fn connect_components(&mut self) -> usize {
+        let mut rng = self
+            .rng
+            .take()
+            .unwrap_or_else(|| StdRng::from_rng(&mut rand::rng()));
+        let generator = self
+            .generator
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        let carved = match &generator.mask {
+            Some(mask) => {
+                let open = |from: Coords, to: Coords| !mask.is_solid(from) && !mask.is_solid(to);
+                join_parts(&mut self.grid, Some(&open), &mut rng)
+            }
+            None => join_parts(&mut self.grid, None, &mut rng),
+        };
+
+        drop(generator);
+        self.rng = Some(rng);
+        carved
+    }
+
+    /// Returns the number of disconnected parts of the maze, where every isolated cell, e.g. a solid
+    /// cell of a [mask](super::OrthogonalMazeBuilder::mask), is a part of its own
+    ///
+    /// A perfect maze has a single part, while the parts of a
+    /// [sparse](super::OrthogonalMazeBuilder::sparsity) maze show how fragmented it became.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let mut maze = OrthogonalMaze::new(4, 3);
+    /// assert_eq!(maze.component_count(), 12);
+    ///
+    /// maze.connect_components();
+    /// assert_eq!(maze.component_count(), 1);
+    /// ```
+    pub fn component_count(&self) -> usize {
+        let width = self.grid.width();
+        let node = |(x, y): Coords| NodeId(y * width + x);
+
+        let mut arena = ArenaTree::new();
+        for _ in 0..width * self.grid.height() {
+            arena.new_node();
+        }
+
+        let mut components = width * self.grid.height();
+        for y in 0..self.grid.height() {
+            for x in 0..width {
+                for direction in [Cell::EAST, Cell::SOUTH] {
+                    let Ok(next) = self.grid.get_next_cell_coords((x, y), direction) else {
+                        continue;
+                    };
+
+                    if self.grid.is_carved((x, y), direction)
+                        && !arena.connected(node((x, y)), node(next))
+                    {
+                        arena.connect(node((x, y)), node(next));
+                        components -= 1;
+                    }
+                }
+            }
+        }
+
+        components
+    }
+
+    /// Returns `true` if the maze is perfect, i.e. there is exactly one path between any two of its
+    /// cells. Otherwise, returns `false`
+    ///
+    /// A maze is perfect when it has a [single part](OrthogonalMaze::component_count) without any
+    /// loops, which makes it a tree of exactly one passage less than cells. Openings in the outer
+    /// walls don't count, while the isolated solid cells of a
+    /// [mask](super::OrthogonalMazeBuilder::mask) make a maze imperfect.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let mut maze = OrthogonalMazeBuilder::new().width(5).height(5).build().unwrap();
+    /// assert!(maze.is_perfect());
+    ///
+    /// maze.carve_border_loop();
+    /// assert!(!maze.is_perfect());
+    /// ```
+    pub fn is_perfect(&self) -> bool {
+        let passages = (0..self.grid.height())
+            .flat_map(|y| (0..self.grid.width()).map(move |x| (x, y)))
+            .map(|coords| {
+                [Cell::EAST, Cell::SOUTH]
+                    .into_iter()
+                    .filter(|direction| {
+                        self.grid.is_carved(coords, *direction)
+                            && self.grid.get_next_cell_coords(coords, *direction).is_ok()
+                    })
+                    .count()
+            })
+            .sum::<usize>();
+
+        passages + 1 == self.grid.width() * self.grid.height() && self.component_count() == 1
+    }
+
+    /// Carves `n` openings through the outer walls of distinct randomly chosen boundary cells, e.g.
+    /// entrances for multiple players. No two openings are carved on the same cell.
+    ///
+    /// Returns the coords of the chosen cells, or a [MazeError] if `n` exceeds the number of
+    /// boundary cells. The cells are chosen with the [openings
+    /// seed](super::OrthogonalMazeBuilder::openings_seed) of the maze, if there is one, or with the
+    /// random number generator the maze was generated with, so a
+    /// [seeded](super::OrthogonalMazeBuilder::structure_seed) maze gets the same entrances every
+    /// time.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let mut maze = OrthogonalMazeBuilder::new().build().unwrap();
+    /// let spawn_points = maze.add_random_entrances(4).unwrap();
+    ///
+    /// assert_eq!(spawn_points.len(), 4);
+    /// ```
+    pub fn add_random_entrances(&mut self, n: usize) -> Result<Vec<Coords>, MazeError> {
+        let boundary = self.grid.boundary_cells();
+        if n > boundary.len() {
+            return Err(MazeError::reason(format!(
+                "Cannot place {} entrances on {} boundary cells",
+                n,
+                boundary.len()
+            )));
+        }
+
+        let rng = match self.openings_rng.as_mut() {
+            Some(rng) => rng,
+            None => self
+                .rng
+                .get_or_insert_with(|| StdRng::from_rng(&mut rand::rng())),
+        };
+        let entrances: Vec<Coords> = boundary.choose_multiple(rng, n).cloned().collect();
+
+        for coords in &entrances {
+            let poles = self.grid.boundary_poles(*coords);
+            let pole = poles
+                .choose(rng)
+                .expect("A boundary cell always faces the outside of the grid");
+            self.grid.carve_opening(*coords, Cell::from(*pole)).unwrap();
+        }
+
+        Ok(entrances)
+    }
+
+    /// Carves an entrance and an exit through the outer walls on the opposite sides of a given
+    /// axis, e.g. on the left and the right sides for the [Horizontal](Axis::Horizontal) axis, and
+    /// returns the coords of the entrance and the exit cells
+    ///
+    /// The entrance is on the western or the northern side, and the exit is on the opposite one.
+    /// Positions along both sides are chosen randomly with the [openings
+    /// seed](super::OrthogonalMazeBuilder::openings_seed) of the maze, if there is one, or with the
+    /// random number generator the maze was generated with. Returns a
+    /// [MazeError] if the maze has no cells, or the sides wrap around a [torus](Grid::new_torus).
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let mut maze = OrthogonalMazeBuilder::new().width(8).height(6).build().unwrap();
+    /// let (entrance, exit) = maze.add_openings_opposite(Axis::Horizontal).unwrap();
+    ///
+    /// assert_eq!((entrance.0, exit.0), (0, 7));
+    /// assert!(maze[entrance].contains(Cell::WEST));
+    /// assert!(maze.solve(entrance, exit).is_some());
+    /// ```
+    pub fn add_openings_opposite(&mut self, axis: Axis) -> Result<(Coords, Coords), MazeError> {
+        let (width, height) = (self.grid.width(), self.grid.height());
+        if width == 0 || height == 0 {
+            return Err(MazeError::reason(format!(
+                "Cannot place openings on the sides of an empty {}x{} maze",
+                width, height
+            )));
+        }
+
+        let (from, to) = axis.poles();
+        if !self.grid.boundary_poles((0, 0)).contains(&from) {
+            return Err(MazeError::reason(format!(
+                "Cannot place openings on the sides of a {}x{} torus, which wrap around",
+                width, height
+            )));
+        }
+
+        let rng = match self.openings_rng.as_mut() {
+            Some(rng) => rng,
+            None => self
+                .rng
+                .get_or_insert_with(|| StdRng::from_rng(&mut rand::rng())),
+        };
+        let mut carve = |pole: Pole| {
+            let coords = match pole {
+                Pole::N => (rng.random_range(0..width), 0),
+                Pole::S => (rng.random_range(0..width), height - 1),
+                Pole::W => (0, rng.random_range(0..height)),
+                Pole::E => (width - 1, rng.random_range(0..height)),
+            };
+            self.grid.carve_opening(coords, Cell::from(pole)).unwrap();
+            coords
+        };
+
+        Ok((carve(from), carve(to)))
+    }
+
+    /// Returns the shortest [Solution] from a given start cell to a given goal cell, or `None` if
+    /// any of the cells is out of the maze bounds or the goal is unreachable
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let maze = OrthogonalMazeBuilder::new().build().unwrap();
+    /// let solution = maze.solve((0, 0), (9, 9)).unwrap();
+    ///
+    /// assert_eq!(solution.path.first(), Some(&(0, 0)));
+    /// assert_eq!(solution.path.last(), Some(&(9, 9)));
+    /// ```
+    pub fn solve(&self, start: Coords, goal: Coords) -> Option<Solution> {
+        let (width, height) = (self.grid.width(), self.grid.height());
+        if start.0 >= width || start.1 >= height || goal.0 >= width || goal.1 >= height {
+            return None;
+        }
+
+        // Breadth-first search over the carved passages, remembering where each cell came from
+        let mut parents: Vec<Option<Coords>> = vec![None; width * height];
+        let mut visited = vec![false; width * height];
+        let mut queue = VecDeque::from([start]);
+        visited[start.1 * width + start.0] = true;
+
+        while let Some(coords) = queue.pop_front() {
+            if coords == goal {
+                let mut path = vec![goal];
+                let mut current = goal;
+                while let Some(parent) = parents[current.1 * width + current.0] {
+                    path.push(parent);
+                    current = parent;
+                }
+                path.reverse();
+                return Some(Solution::new(path));
+            }
+
+            for (nx, ny) in self.connected_neighbours(coords) {
+                let idx = ny * width + nx;
+                if !visited[idx] {
+                    visited[idx] = true;
+                    parents[idx] = Some(coords);
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Returns the shortest [Solution] from a given start cell to a given goal cell found with the
+    /// A* search, along with the number of cells the search expanded, or `None` if any of the
+    /// cells is out of the maze bounds or the goal is unreachable
+    ///
+    /// The heuristic estimates the number of steps from a cell to the goal, e.g.
+    /// [manhattan_distance](OrthogonalMaze::manhattan_distance). As long as it never overestimates
+    /// them, the solution is as short as the one of [solve](OrthogonalMaze::solve), while a closer
+    /// estimate expands fewer cells on the way.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let maze = OrthogonalMazeBuilder::new().build().unwrap();
+    /// let (solution, expanded) = maze
+    ///     .solve_astar((0, 0), (9, 9), OrthogonalMaze::manhattan_distance)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(solution, maze.solve((0, 0), (9, 9)).unwrap());
+    /// assert!(expanded <= 100);
+    /// ```
+    pub fn solve_astar<H>(
+        &self,
+        start: Coords,
+        goal: Coords,
+        heuristic: H,
+    ) -> Option<(Solution, usize)>
+    where
+        H: Fn(Coords, Coords) -> f64,
+    {
+        let (width, height) = (self.grid.width(), self.grid.height());
+        if start.0 >= width || start.1 >= height || goal.0 >= width || goal.1 >= height {
+            return None;
+        }
+
+        // The fewest steps found so far to every cell, and where each cell came from with them
+        let mut steps: Vec<Option<usize>> = vec![None; width * height];
+        let mut parents: Vec<Option<Coords>> = vec![None; width * height];
+        let mut frontier = BinaryHeap::from([Frontier {
+            estimate: heuristic(start, goal),
+            steps: 0,
+            coords: start,
+        }]);
+        steps[start.1 * width + start.0] = Some(0);
+        let mut expanded = 0;
+
+        while let Some(Frontier {
+            steps: g, coords, ..
+        }) = frontier.pop()
+        {
+            // A cell is pushed again every time a shorter path to it is found
+            if steps[coords.1 * width + coords.0].is_some_and(|best| best < g) {
+                continue;
+            }
+            expanded += 1;
+
+            if coords == goal {
+                let mut path = vec![goal];
+                let mut current = goal;
+                while let Some(parent) = parents[current.1 * width + current.0] {
+                    path.push(parent);
+                    current = parent;
+                }
+                path.reverse();
+                return Some((Solution::new(path), expanded));
+            }
+
+            for (nx, ny) in self.connected_neighbours(coords) {
+                let idx = ny * width + nx;
+                if steps[idx].is_none_or(|best| g + 1 < best) {
+                    steps[idx] = Some(g + 1);
+                    parents[idx] = Some(coords);
+                    frontier.push(Frontier {
+                        estimate: (g + 1) as f64 + heuristic((nx, ny), goal),
+                        steps: g + 1,
+                        coords: (nx, ny),
+                    });
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Returns the Manhattan distance between two given cells, i.e. the number of steps between
+    /// them if there were no walls, which is the default heuristic of
+    /// [solve_astar](OrthogonalMaze::solve_astar)
+    pub const fn manhattan_distance(from: Coords, to: Coords) -> f64 {
+        (from.0.abs_diff(to.0) + from.1.abs_diff(to.1)) as f64
+    }
+
+    /// Returns `true` if there is exactly one simple path between two given cells. Otherwise,
+    /// returns `false`, including when the cells are not connected or out of the maze bounds
+    ///
+    /// A perfect maze always has a unique solution, while loops, e.g. after culling dead ends, may
+    /// open alternative paths. Loops that touch the solution in a single cell only don't count, as
+    /// a simple path can't visit a cell twice.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let mut maze = OrthogonalMazeBuilder::new().width(5).height(5).build().unwrap();
+    /// assert!(maze.has_unique_solution((0, 0), (4, 4)));
+    ///
+    /// maze.cull_dead_ends(|_| 1.0);
+    /// maze.carve_border_loop();
+    /// assert!(!maze.has_unique_solution((0, 0), (4, 4)));
+    /// ```
+    pub fn has_unique_solution(&self, start: Coords, goal: Coords) -> bool {
+        let Some(solution) = self.solve(start, goal) else {
+            return false;
+        };
+
+        let width = self.grid.width();
+        let mut steps: Vec<Option<usize>> = vec![None; width * self.grid.height()];
+        for (step, (x, y)) in solution.path.iter().enumerate() {
+            steps[y * width + x] = Some(step);
+        }
+
+        // Another simple path has to leave the solution somewhere and join it again elsewhere, so
+        // the cells of the solution must be disconnected once its own passages are removed
+        let mut visited = vec![false; steps.len()];
+        for &from in &solution.path {
+            let step = steps[from.1 * width + from.0];
+            let mut queue = VecDeque::from([from]);
+            visited[from.1 * width + from.0] = true;
+
+            while let Some(coords) = queue.pop_front() {
+                let current = steps[coords.1 * width + coords.0];
+                for (nx, ny) in self.connected_neighbours(coords) {
+                    let idx = ny * width + nx;
+                    let next = steps[idx];
+                    let along_solution = current
+                        .zip(next)
+                        .is_some_and(|(current, next)| current.abs_diff(next) == 1);
+                    if along_solution {
+                        continue;
+                    }
+                    if next.is_some() && next != step {
+                        return false;
+                    }
+                    if !visited[idx] {
+                        visited[idx] = true;
+                        queue.push_back((nx, ny));
+                    }
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Returns every cell lying on at least one shortest path from a given start cell to a given
+    /// goal cell, or an empty set if any of the cells is out of the maze bounds or the goal is
+    /// unreachable
+    ///
+    /// A cell lies on a shortest path when its distances from the start and from the goal add up
+    /// to the length of the shortest path. In a perfect maze, the cells are the ones of the unique
+    /// [solution](OrthogonalMaze::solve), while loops may widen them into a band of equally short
+    /// alternatives.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let maze = OrthogonalMazeBuilder::new().build().unwrap();
+    /// let cells = maze.all_shortest_path_cells((0, 0), (9, 9));
+    /// let solution = maze.solve((0, 0), (9, 9)).unwrap();
+    ///
+    /// assert_eq!(cells.len(), solution.path.len());
+    /// assert!(solution.path.iter().all(|coords| cells.contains(coords)));
+    /// ```
+    pub fn all_shortest_path_cells(&self, start: Coords, goal: Coords) -> HashSet<Coords> {
+        let (width, height) = (self.grid.width(), self.grid.height());
+        if start.0 >= width || start.1 >= height || goal.0 >= width || goal.1 >= height {
+            return HashSet::new();
+        }
+
+        let from_start = self.distances_from(start);
+        let Some(length) = from_start[goal.1 * width + goal.0] else {
+            return HashSet::new();
+        };
+        let from_goal = self.distances_from(goal);
+
+        (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .filter(|&(x, y)| {
+                let idx = y * width + x;
+                from_start[idx]
+                    .zip(from_goal[idx])
+                    .is_some_and(|(to_start, to_goal)| to_start + to_goal == length)
+            })
+            .collect()
+    }
+
+    /// Returns the number of steps from a given cell to every cell of the maze, or `None` for the
+    /// unreachable ones, indexed by rows
+    fn distances_from(&self, from: Coords) -> Vec<Option<usize>> {
+        let width = self.grid.width();
+        let mut distances = vec![None; width * self.grid.height()];
+        self.breadth_first(from, usize::MAX, |(x, y), steps, _| {
+            distances[y * width + x] = Some(steps);
+        });
+
+        distances
+    }
+
+    /// Visits the cells reachable from a given cell in at most `limit` steps through carved
+    /// passages, calling `visit` with the coords of every cell, the number of steps to it and the
+    /// pole it is entered through, which the given cell has none of
+    ///
+    /// Cells are visited in the order of their distance, and the neighbours of a cell in the order
+    /// of [Pole::all]. The search stops at the cells `limit` steps away, so its cost depends on the
+    /// size of the range rather than the size of the maze. The given cell must be within the maze
+    /// bounds.
+    fn breadth_first<F>(&self, from: Coords, limit: usize, mut visit: F)
+    where
+        F: FnMut(Coords, usize, Option<Pole>),
+    {
+        let width = self.grid.width();
+        let mut visited = vec![false; width * self.grid.height()];
+        let mut queue = VecDeque::from([(from, 0)]);
+        visited[from.1 * width + from.0] = true;
+        visit(from, 0, None);
+
+        while let Some((coords, steps)) = queue.pop_front() {
+            if steps == limit {
+                continue;
+            }
+
+            for pole in Pole::all() {
+                let Some((nx, ny)) = self.move_from(coords, pole) else {
+                    continue;
+                };
+                if !visited[ny * width + nx] {
+                    visited[ny * width + nx] = true;
+                    visit((nx, ny), steps + 1, Some(pole));
+                    queue.push_back(((nx, ny), steps + 1));
+                }
+            }
+        }
+    }
+
+    /// Returns the average number of alternative passages per cell of the shortest path between
+    /// two given cells, or `0` if the goal is unreachable or any of the cells is out of the maze
+    /// bounds
+    ///
+    /// Alternative passages lead from a cell of the path to cells off the path, so they are the
+    /// turns a solver may take by mistake. The higher the average is, the more misleading the maze
+    /// is, regardless of the length of its solution. Openings in the outer walls are not counted.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let maze = OrthogonalMazeBuilder::new().build().unwrap();
+    /// let branching = maze.avg_branching((0, 0), (9, 9));
+    ///
+    /// // A cell has at most 4 passages, and 2 of them may be on the path
+    /// assert!((0.0..=2.0).contains(&branching));
+    /// ```
+    pub fn avg_branching(&self, start: Coords, goal: Coords) -> f64 {
+        let Some(solution) = self.solve(start, goal) else {
+            return 0.0;
+        };
+
+        let path = &solution.path;
+        let alternatives: usize = path
+            .iter()
+            .enumerate()
+            .map(|(idx, coords)| {
+                self.connected_neighbours(*coords)
+                    .filter(|next| {
+                        (idx == 0 || path[idx - 1] != *next) && path.get(idx + 1) != Some(next)
+                    })
+                    .count()
+            })
+            .sum();
+
+        alternatives as f64 / path.len() as f64
+    }
+
+    /// Returns the cell reachable from a given cell that is the closest to the geometric center of
+    /// the maze, or `None` if the given cell is out of the maze bounds
+    ///
+    /// The distance to the center is Euclidean. When a few reachable cells are equally close to the
+    /// center, e.g. when the center falls on a joint of cells for even dimensions, the one with the
+    /// shortest path from the given cell is returned.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let maze = OrthogonalMazeBuilder::new().width(5).height(5).build().unwrap();
+    ///
+    /// // A perfect maze has every cell reachable, so the central cell is reachable too
+    /// assert_eq!(maze.central_reachable((0, 0)), Some((2, 2)));
+    /// ```
+    pub fn central_reachable(&self, from: Coords) -> Option<Coords> {
+        let (width, height) = (self.grid.width(), self.grid.height());
+        if from.0 >= width || from.1 >= height {
+            return None;
+        }
+
+        // Doubled coords keep the distance to a center that falls on a cell joint integral
+        let distance = |(x, y): Coords| {
+            let dx = (2 * x).abs_diff(width - 1);
+            let dy = (2 * y).abs_diff(height - 1);
+            dx * dx + dy * dy
+        };
+
+        let mut closest = from;
+        self.breadth_first(from, usize::MAX, |coords, _, _| {
+            if distance(coords) < distance(closest) {
+                closest = coords;
+            }
+        });
+
+        Some(closest)
+    }
+
+    /// Returns up to `k` cells spread as far as possible from a given entrance and from each other
+    /// through carved passages, e.g. to place treasures in the most remote nooks of a maze
+    ///
+    /// The cells are chosen one by one, each being the reachable cell farthest from the entrance
+    /// and the cells chosen before it, i.e. the one whose nearest of them is the most steps away,
+    /// where ties go to the cell that comes first row by row. So the first cell is the farthest one
+    /// from the entrance. Fewer cells are returned once every reachable cell is chosen, and no
+    /// cells if the entrance is out of the maze bounds.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let maze = OrthogonalMazeBuilder::new().width(10).height(10).build().unwrap();
+    /// let treasures = maze.spread_points((0, 0), 3);
+    ///
+    /// assert_eq!(treasures.len(), 3);
+    /// assert!(!treasures.contains(&(0, 0)));
+    /// let farthest = (0..100).map(|i| maze.solve((0, 0), (i % 10, i / 10)).unwrap().length()).max();
+    /// assert_eq!(Some(maze.solve((0, 0), treasures[0]).unwrap().length()), farthest);
+    /// ```
+    pub fn spread_points(&self, entrance: Coords, k: usize) -> Vec<Coords> {
+        let width = self.grid.width();
+        if entrance.0 >= width || entrance.1 >= self.grid.height() {
+            return vec![];
+        }
+
+        // Steps from every cell to the nearest of the entrance and the chosen cells
+        let mut nearest = self.distances_from(entrance);
+        let mut points = vec![];
+        while points.len() < k {
+            let Some((index, _)) = nearest
+                .iter()
+                .enumerate()
+                .filter_map(|(index, steps)| steps.map(|steps| (index, steps)))
+                .filter(|(_, steps)| *steps > 0)
+                .max_by(|(i, a), (j, b)| a.cmp(b).then(j.cmp(i)))
+            else {
+                break;
+            };
+
+            let coords = (index % width, index / width);
+            for (steps, from_point) in nearest.iter_mut().zip(self.distances_from(coords)) {
+                if let (Some(steps), Some(from_point)) = (steps.as_mut(), from_point) {
+                    *steps = (*steps).min(from_point);
+                }
+            }
+            points.push(coords);
+        }
+
+        points
+    }
+
+    /// Returns the cells reachable from a given cell in at most `n` steps through carved passages,
+    /// including the cell itself, or no cells if it is out of the maze bounds
+    ///
+    /// Cells are ordered by their distance from the given cell, e.g. for a movement range or a
+    /// light radius. The search stops at the cells `n` steps away, so its cost depends on the size
+    /// of the range rather than the size of the maze.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let maze = OrthogonalMazeBuilder::new().width(5).height(5).build().unwrap();
+    /// let cells = maze.within_steps((2, 2), 3);
+    ///
+    /// assert_eq!(cells[0], (2, 2));
+    /// assert!(cells.len() <= 25);
+    /// assert!(cells
+    ///     .iter()
+    ///     .all(|coords| maze.solve((2, 2), *coords).unwrap().length() <= 3));
+    /// ```
+    pub fn within_steps(&self, from: Coords, n: usize) -> Vec<Coords> {
+        let (width, height) = (self.grid.width(), self.grid.height());
+        if from.0 >= width || from.1 >= height {
+            return vec![];
+        }
+
+        let mut cells = vec![];
+        self.breadth_first(from, n, |coords, _, _| cells.push(coords));
+
+        cells
+    }
+
+    /// Returns the number of cells reachable from a given cell through carved passages, including
+    /// the cell itself, or `0` if the cell is out of the maze bounds
+    ///
+    /// In a perfect maze every cell is reachable, so the count equals the number of cells, while
+    /// masks or walls splitting the maze leave only the region containing the given cell.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let maze = OrthogonalMazeBuilder::new().width(5).height(5).build().unwrap();
+    ///
+    /// assert_eq!(maze.reachable_count((0, 0)), 25);
+    /// assert_eq!(maze.reachable_count((5, 0)), 0);
+    /// ```
+    pub fn reachable_count(&self, from: Coords) -> usize {
+        let (width, height) = (self.grid.width(), self.grid.height());
+        if from.0 >= width || from.1 >= height {
+            return 0;
+        }
+
+        let mut count = 0;
+        self.breadth_first(from, usize::MAX, |_, _, _| count += 1);
+
+        count
+    }
+
+    /// Returns the Manhattan distance from every cell to the closest walled cell, indexed by rows
+    /// first, i.e. `field[y][x]`
+    ///
+    /// A walled cell, i.e. a cell with at least one wall, is at a distance of `1` from a wall, so in
+    /// a perfect maze every cell is at `1`. Cells with all the passages carved, e.g. in rooms, are
+    /// further away, the more so the closer they are to the middle of a room. Distances grow
+    /// through carved passages only. If there are no walls at all, every distance is `usize::MAX`.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let maze = OrthogonalMazeBuilder::new().width(5).height(5).build().unwrap();
+    ///
+    /// assert!(maze.wall_distance_field().iter().flatten().all(|distance| *distance == 1));
+    /// ```
+    pub fn wall_distance_field(&self) -> Vec<Vec<usize>> {
+        let (width, height) = (self.grid.width(), self.grid.height());
+        let mut field = vec![vec![usize::MAX; width]; height];
+
+        // A multi-source breadth-first search from all the walled cells at once
+        let mut queue = VecDeque::new();
+        for ((x, y), cell) in self.iter() {
+            if *cell != Cell::all() {
+                field[y][x] = 1;
+                queue.push_back((x, y));
+            }
+        }
+
+        while let Some((x, y)) = queue.pop_front() {
+            let distance = field[y][x] + 1;
+            for (nx, ny) in self.connected_neighbours((x, y)) {
+                if field[ny][nx] == usize::MAX {
+                    field[ny][nx] = distance;
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+
+        field
+    }
+
+    /// Returns the direction from every cell towards its parent in a tree of the shortest paths to a
+    /// given root cell, indexed by rows first, i.e. `tree[y][x]`
+    ///
+    /// Following the directions from any cell leads along a shortest path to the root, e.g. to draw
+    /// arrows showing the way home or to move agents over a flow field. The root itself, the cells
+    /// unreachable from it and all the cells when the root is out of the maze bounds have no
+    /// direction. When several paths are equally short, the parents are picked in the order of
+    /// [Pole::all].
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let maze = OrthogonalMazeBuilder::new().width(6).height(4).build().unwrap();
+    /// let tree = maze.path_tree((0, 0));
+    ///
+    /// // Every cell leads to the root in as many steps as the solution takes
+    /// let mut coords = (5, 3);
+    /// let mut steps = 0;
+    /// while let Some(pole) = tree[coords.1][coords.0] {
+    ///     coords = maze.move_from(coords, pole).unwrap();
+    ///     steps += 1;
+    /// }
+    /// assert_eq!(coords, (0, 0));
+    /// assert_eq!(steps, maze.solve((5, 3), (0, 0)).unwrap().length());
+    /// ```
+    pub fn path_tree(&self, root: Coords) -> Vec<Vec<Option<Pole>>> {
+        let (width, height) = (self.grid.width(), self.grid.height());
+        let mut tree = vec![vec![None; width]; height];
+        if root.0 >= width || root.1 >= height {
+            return tree;
+        }
+
+        self.breadth_first(root, usize::MAX, |(x, y), _, pole| {
+            tree[y][x] = pole.map(|pole| pole.opposite());
+        });
+
+        tree
+    }
+
+    /// Indicates if a player standing in a given cell can move towards a given pole, i.e. the wall
+    /// in that direction is carved and leads to a cell within the maze bounds
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let mut maze = OrthogonalMaze::new(2, 1);
+    /// maze.get_grid_mut().carve_passage((0, 0), Cell::EAST).unwrap();
+    ///
+    /// assert!(maze.can_move((0, 0), Pole::E));
+    /// assert!(!maze.can_move((0, 0), Pole::S));
+    /// ```
+    pub fn can_move(&self, from: Coords, pole: Pole) -> bool {
+        self.move_from(from, pole).is_some()
+    }
+
+    /// Returns coords of the cell a player standing in a given cell gets to by moving towards a
+    /// given pole, or `None` if the move is blocked by a wall or leads out of the maze bounds
+    ///
+    /// An opening in the outer wall doesn't lead to any cell, so moving through it returns `None`
+    /// too.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let mut maze = OrthogonalMaze::new(2, 1);
+    /// maze.get_grid_mut().carve_passage((0, 0), Cell::EAST).unwrap();
+    ///
+    /// assert_eq!(maze.move_from((0, 0), Pole::E), Some((1, 0)));
+    /// assert_eq!(maze.move_from((1, 0), Pole::E), None);
+    /// ```
+    pub fn move_from(&self, from: Coords, pole: Pole) -> Option<Coords> {
+        if from.0 >= self.grid.width() || from.1 >= self.grid.height() {
+            return None;
+        }
+
+        let direction = Cell::from(pole);
+        if !self.grid.is_carved(from, direction) {
+            return None;
+        }
+        self.grid.get_next_cell_coords(from, direction).ok()
+    }
+
+    /// Returns directions and coords of the neighbours separated from a given cell with walls
+    fn walled_neighbours(&self, coords: Coords) -> impl Iterator<Item = (Cell, Coords)> + '_ {
+        Pole::all()
+            .into_iter()
+            .map(Cell::from)
+            .filter(move |direction| !self.grid.is_carved(coords, *direction))
+            .filter_map(move |direction| {
+                self.grid
+                    .get_next_cell_coords(coords, direction)
+                    .ok()
+                    .map(|next| (direction, next))
+            })
+    }
+
+    /// Returns coords of the neighbours connected to a given cell with carved passages
+    fn connected_neighbours(&self, coords: Coords) -> impl Iterator<Item = Coords> + '_ {
+        Pole::all()
+            .into_iter()
+            .map(Cell::from)
+            .filter(move |direction| self.grid.is_carved(coords, *direction))
+            .filter_map(move |direction| self.grid.get_next_cell_coords(coords, direction).ok())
+    }
+
+    /// Returns [BiasMetrics] of the maze, i.e. counts and ratios of horizontal and vertical
+    /// passages, as well as of straight and turning cells
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let maze = OrthogonalMazeBuilder::new()
+    ///     .algorithm(Box::new(BinaryTree::new(Bias::NorthWest)))
+    ///     .build()
+    ///     .unwrap();
+    /// let metrics = maze.bias_metrics();
+    ///
+    /// // A perfect maze has one passage less than cells
+    /// assert_eq!(metrics.horizontal_passages + metrics.vertical_passages, 99);
+    /// ```
+    pub fn bias_metrics(&self) -> BiasMetrics {
+        let (mut horizontal, mut vertical) = (0, 0);
+        let (mut straight, mut turning) = (0, 0);
+
+        for y in 0..self.grid.height() {
+            for x in 0..self.grid.width() {
+                // Openings in the outer walls lead to no cells, so they are left out
+                let cell: Cell = Pole::all()
+                    .into_iter()
+                    .map(Cell::from)
+                    .filter(|direction| {
+                        self.grid.is_carved((x, y), *direction)
+                            && self.grid.get_next_cell_coords((x, y), *direction).is_ok()
+                    })
+                    .collect();
+
+                // Count passages from the western and northern cells only, so each counts once
+                if cell.contains(Cell::EAST) {
+                    horizontal += 1;
+                }
+                if cell.contains(Cell::SOUTH) {
+                    vertical += 1;
+                }
+
+                if cell == Cell::NORTH | Cell::SOUTH || cell == Cell::EAST | Cell::WEST {
+                    straight += 1;
+                } else if cell.passage_count() == 2 {
+                    turning += 1;
+                }
+            }
+        }
+
+        BiasMetrics::new(horizontal, vertical, straight, turning)
+    }
+
+    /// Returns the fraction of the internal walls that remain standing, from `0.0` when all the
+    /// walls between cells are carved to `1.0` when none of them is
+    ///
+    /// A perfect maze has one passage less than cells, so its density only depends on its size,
+    /// while loops, e.g. from [culled dead ends](OrthogonalMaze::cull_dead_ends), lower it. Outer
+    /// walls and openings in them are not counted, while the walls wrapping around a
+    /// [torus](Grid::new_torus) are internal ones. A maze without internal walls, e.g. a 1x1 maze,
+    /// has the `0.0` density.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let mut maze = OrthogonalMazeBuilder::new().width(5).height(5).build().unwrap();
+    /// // 24 of 40 internal walls are carved
+    /// assert_eq!(maze.wall_density(), 0.4);
+    ///
+    /// maze.carve_border_loop();
+    /// assert!(maze.wall_density() < 0.4);
+    /// ```
+    pub fn wall_density(&self) -> f64 {
+        let (width, height) = (self.grid.width(), self.grid.height());
+        // An axis of a torus longer than one cell has a wall wrapping around every row or column
+        let lines = |len: usize| {
+            if self.grid.is_torus() && len > 1 {
+                len
+            } else {
+                len.saturating_sub(1)
+            }
+        };
+        let walls = lines(width) * height + width * lines(height);
+        if walls == 0 {
+            return 0.0;
+        }
+
+        let metrics = self.bias_metrics();
+        let passages = metrics.horizontal_passages + metrics.vertical_passages;
+
+        (walls - passages) as f64 / walls as f64
+    }
+
+    /// Returns the total length of all the walls of the maze, including the outer ones, where a wall
+    /// of a single cell is `unit` long, e.g. to estimate how long a pen plotter takes to draw it
+    ///
+    /// Collinear walls merged into longer lines have the same total length, since walls only meet
+    /// at the joints between them, so every part of a line is counted once. Openings in the outer
+    /// walls are not counted. A wall wrapping around a [torus](Grid::new_torus) is counted once,
+    /// even though it is on both sides of a flat rendering.
+    ///
+    /// # Example
+    /// ```
+    /// use bevy_knossos::maze::*;
+    ///
+    /// let maze = OrthogonalMazeBuilder::new().width(5).height(5).build().unwrap();
+    ///
+    /// // 20 outer walls and 16 walls of the 40 internal ones are left, 10 mm each
+    /// assert_eq!(maze.total_wall_length(10.0), 360.0);
+    /// ```
+    pub fn total_wall_length(&self, unit: f64) -> f64 {
+        let (width, height) = (self.grid.width(), self.grid.height());
+        let torus = self.grid.is_torus();
+        // The last column and row of walls of a torus repeat the first ones on the opposite sides
+        let segments = walls(&self.grid)
+            .into_iter()
+            .filter(|wall| match *wall {
+                Wall::Post(..) => false,
+                Wall::Vertical(i, _) => !(torus && width > 1 && i == width),
+                Wall::Horizontal(_, j) => !(torus && height > 1 && j == height),
+            })
+            .count();
+
+        segments as f64 * unit
+    }
+
+    /// Returns an iterator over the maze where `index == y * Maze::width + x`.
+    ///
+    /// The iterator yields all items, `(Coords, Cell)`, from start to end.
+    pub const fn iter(&self) -> OrthogonalMazeIterator<'_> {
+        OrthogonalMazeIterator {
+            maze: self,
+            index: 0,
+        }
+    }
+}
+
+/// Returns the column and the row of the wall of a given cell towards a given pole in a wall grid
+const fn wall_entry((x, y): Coords, pole: Pole) -> Coords {
+    let (column, row) = (2 * x + 1, 2 * y + 1);
+    match pole {
+        Pole::N => (column, row - 1),
+        Pole::S => (column, row + 1),
+        Pole::W => (column - 1, row),
+        Pole::E => (column + 1, row),
+    }
+}
+
+impl std::ops::Index<Coords> for OrthogonalMaze {
+    type Output = Cell;
+
+    fn index(&self, index: Coords) -> &Self::Output {
+        &self.grid[index]
+    }
+}
+
+impl fmt::Debug for OrthogonalMaze {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OrthogonalMaze")
+            .field("grid", &self.grid)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for OrthogonalMaze {
+    fn eq(&self, other: &Self) -> bool {
+        self.grid == other.grid
+    }
+}
+
+impl Eq for OrthogonalMaze {}
+
+impl fmt::Display for OrthogonalMaze {
+    /// Writes a maze formatted as [AsciiNarrow](super::AsciiNarrow) into a buffer
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        AsciiNarrow.write_grid(&self.grid, f)
+    }
+}
+
+pub struct OrthogonalMazeIterator<'a> {
+    maze: &'a OrthogonalMaze,
+    index: usize,
+}
+
+impl<'a> Iterator for OrthogonalMazeIterator<'a> {
+    type Item = (Coords, &'a Cell);
+    fn next(&mut self) -> Option<Self::Item> {
+        let grid = &self.maze.grid;
+        let width = grid.width();
+        if self.index < width * grid.height() {
+            let coords = (self.index % width, self.index / width);
+            let result = Some((coords, &grid[coords]));
+            self.index += 1;
+            result
+        } else {
+            None
+        }
+    }
+}
+
+pub struct OrthogonalMazeIntoIterator {
+    maze: OrthogonalMaze,
+    index: usize,
+    width: usize,
+}
+
+impl IntoIterator for OrthogonalMaze {
+    type Item = (Coords, Cell);
+    type IntoIter = OrthogonalMazeIntoIterator;
+
+    fn into_iter(self) -> OrthogonalMazeIntoIterator {
+        OrthogonalMazeIntoIterator {
+            width: self.grid.width(),
+            maze: self,
+            index: 0,
+        }
+    }
+}
+
+impl Iterator for OrthogonalMazeIntoIterator {
+    type Item = (Coords, Cell);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let width = self.width;
+        if self.index >= width * self.maze.grid.height() {
+            return None;
+        }
+        let coords = (self.index % width, self.index / width);
+        self.index += 1;
+
+        Some((coords, self.maze.grid[coords]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::maze::{
+        grid::cell::Cell, Eller, GrowingTree, HuntAndKill, Method, OrthogonalMazeBuilder,
+        Sidewinder,
+    };
+
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn iterators_have_size() {
+        let grid = generate_valid_maze();
+        let maze = from_grid(grid);
+
+        let iter_count = maze.iter().count();
+
+        assert_eq!(iter_count, 16);
+        assert_eq!(
+            maze.iter().nth(5).unwrap(),
+            ((1usize, 1usize), &(Cell::SOUTH | Cell::EAST | Cell::WEST))
+        );
+    }
+
+    #[test]
+    fn into_iterators_have_size() {
+        let grid = generate_valid_maze();
+        let maze = from_grid(grid);
+
+        let iter_count = maze.clone().into_iter().count();
+
+        assert_eq!(iter_count, 16);
+        assert_eq!(
+            maze.into_iter().nth(5).unwrap(),
+            ((1usize, 1usize), (Cell::SOUTH | Cell::EAST | Cell::WEST))
+        );
+    }
+
+    #[test]
+    fn display_orthogonal_maze() {
+        let mut expected = String::new();
+        expected.push_str(" _______ \n");
+        expected.push_str("| |___  |\n");
+        expected.push_str("|_   _| |\n");
+        expected.push_str("|  _____|\n");
+        expected.push_str("|_______|\n");
+
+        let grid = generate_valid_maze();
+        let maze = from_grid(grid);
+        let actual = maze.to_string();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn display_matches_ascii_narrow_formatter() {
+        let maze = from_grid(generate_valid_maze());
+
+        assert_eq!(maze.to_string(), AsciiNarrow.format(&maze.grid).0);
+        assert_eq!(maze.to_string(), maze.grid.to_string());
+    }
+
+    #[test]
+    fn valid_maze() {
+        let grid = generate_valid_maze();
+        let maze = from_grid(grid);
+        assert!(maze.is_valid());
+    }
+
+    #[test]
+    fn invalid_maze() {
+        let grid = generate_invalid_maze();
+        let maze = from_grid(grid);
+        assert!(!maze.is_valid());
+    }
+
+    #[test]
+    fn access_by_index_maze() {
+        let grid = generate_valid_maze();
+        let maze = from_grid(grid);
+
+        let cell = maze[(3, 1)];
+        assert_eq!(cell, Cell::from_bits(0b0011).unwrap());
+    }
+
+    #[test]
+    fn into_iterators_correct_index() {
+        let grid = generate_valid_maze();
+        let maze = from_grid(grid);
+        let width = maze.grid.width();
+
+        maze.into_iter()
+            .enumerate()
+            .for_each(|(idx, (coord, cell))| {
+                assert_eq!(idx, coord.1 * width + coord.0);
+                assert!(!cell.is_empty())
+            });
+    }
+
+    #[test]
+    fn iterators_correct_index() {
+        let grid = generate_valid_maze();
+        let maze = from_grid(grid);
+        let width = maze.grid.width();
+
+        maze.iter().enumerate().for_each(|(idx, (coord, cell))| {
+            assert_eq!(idx, coord.1 * width + coord.0);
+            assert!(!cell.is_empty())
+        });
+    }
+
+    #[test]
+    fn dead_ends() {
+        let grid = generate_valid_maze();
+        let maze = from_grid(grid);
+
+        assert_eq!(maze.dead_ends(), vec![(0, 0), (1, 0), (2, 1), (3, 3)]);
+    }
+
+    #[test]
+    fn degree_histogram() {
+        let mut maze = from_grid(generate_valid_maze());
+
+        assert_eq!(maze.degree_histogram(), [0, 4, 10, 2, 0]);
+
+        let grid = maze.get_grid_mut();
+        grid.carve_passage((1, 1), Cell::NORTH).unwrap();
+        grid.carve_opening((0, 0), Cell::WEST).unwrap();
+        assert_eq!(maze.degree_histogram(), [0, 2, 12, 1, 1]);
+        assert_eq!(
+            OrthogonalMaze::new(2, 3).degree_histogram(),
+            [6, 0, 0, 0, 0]
+        );
+    }
+
+    #[test]
+    fn junctions() {
+        let grid = generate_valid_maze();
+        let mut maze = from_grid(grid);
+
+        assert_eq!(maze.junctions(), vec![(1, 1), (1, 2)]);
+
+        maze.get_grid_mut()
+            .carve_passage((1, 1), Cell::NORTH)
+            .unwrap();
+        assert_eq!(maze.junctions(), vec![(1, 1), (1, 2)]);
+        maze.get_grid_mut()
+            .carve_passage((2, 1), Cell::EAST)
+            .unwrap();
+        assert_eq!(maze.junctions(), vec![(1, 1), (3, 1), (1, 2)]);
+    }
+
+    #[test]
+    fn cull_no_dead_ends() {
+        let grid = generate_valid_maze();
+        let mut maze = from_grid(grid);
+
+        assert_eq!(maze.cull_dead_ends(|_| 0.0), 0);
+        assert_eq!(maze.dead_ends().len(), 4);
+    }
+
+    #[test]
+    fn cull_all_dead_ends() {
+        let grid = generate_valid_maze();
+        let mut maze = from_grid(grid);
+
+        assert!(maze.cull_dead_ends(|_| 1.0) > 0);
+        assert!(maze.dead_ends().is_empty());
+        assert!(maze.is_valid());
+    }
+
+    #[test]
+    fn cull_dead_ends_by_region() {
+        let grid = generate_valid_maze();
+        let mut maze = from_grid(grid);
+
+        // Cull dead ends on the first row only
+        maze.cull_dead_ends(|(_, y)| if y == 0 { 1.0 } else { 0.0 });
+
+        assert_eq!(maze.dead_ends(), vec![(2, 1), (3, 3)]);
+    }
+
+    #[test]
+    fn cull_adjacent_dead_ends() {
+        // A U-shaped passage with two dead ends next to each other on the first row
+        let mut grid = Grid::new(2, 2);
+        grid.carve_passage((0, 0), Cell::SOUTH).unwrap();
+        grid.carve_passage((0, 1), Cell::EAST).unwrap();
+        grid.carve_passage((1, 1), Cell::NORTH).unwrap();
+        let mut maze = from_grid(grid);
+
+        // Culling the first dead end joins it with the second one
+        assert_eq!(maze.cull_dead_ends(|coords| f64::from(coords == (0, 0))), 2);
+        assert!(maze.dead_ends().is_empty());
+        assert!(maze.grid.is_carved((0, 0), Cell::EAST));
+    }
+
+    #[test]
+    fn add_random_entrances() {
+        let grid = generate_valid_maze();
+        let mut maze = from_grid(grid);
+
+        let entrances = maze.add_random_entrances(5).unwrap();
+        let boundary = maze.grid.boundary_cells();
+
+        assert_eq!(entrances.len(), 5);
+        for (idx, coords) in entrances.iter().enumerate() {
+            assert!(boundary.contains(coords));
+            assert!(!entrances[idx + 1..].contains(coords));
+            assert!(maze
+                .grid
+                .boundary_poles(*coords)
+                .iter()
+                .any(|pole| maze.grid.is_carved(*coords, Cell::from(*pole))));
+        }
+        assert!(maze.is_valid());
+
+        let build = || {
+            let mut maze = OrthogonalMazeBuilder::new()
+                .width(9)
+                .height(7)
+                .structure_seed(3)
+                .build()
+                .unwrap();
+            maze.add_random_entrances(4).unwrap()
+        };
+        assert_eq!(build(), build());
+    }
+
+    #[test]
+    fn add_openings_opposite() {
+        for axis in [Axis::Horizontal, Axis::Vertical] {
+            let mut maze = from_grid(generate_valid_maze());
+            let (entrance, exit) = maze.add_openings_opposite(axis).unwrap();
+            let (from, to) = axis.poles();
+
+            match axis {
+                Axis::Horizontal => assert_eq!((entrance.0, exit.0), (0, 3)),
+                Axis::Vertical => assert_eq!((entrance.1, exit.1), (0, 3)),
+            }
+            assert!(maze.grid.is_carved(entrance, Cell::from(from)));
+            assert!(maze.grid.is_carved(exit, Cell::from(to)));
+            assert!(maze.is_valid());
+        }
+
+        let build = || {
+            let mut maze = OrthogonalMazeBuilder::new()
+                .width(9)
+                .height(7)
+                .openings_seed(5)
+                .build()
+                .unwrap();
+            maze.add_openings_opposite(Axis::Vertical).unwrap()
+        };
+        assert_eq!(build(), build());
+
+        assert_eq!(
+            OrthogonalMaze::new(0, 3)
+                .add_openings_opposite(Axis::Horizontal)
+                .unwrap_err()
+                .reason,
+            "Cannot place openings on the sides of an empty 0x3 maze"
+        );
+
+        // Only the sides along a single cell of a torus don't wrap around
+        let mut torus = from_grid(Grid::new_torus(4, 1));
+        assert_eq!(
+            torus
+                .add_openings_opposite(Axis::Horizontal)
+                .unwrap_err()
+                .reason,
+            "Cannot place openings on the sides of a 4x1 torus, which wrap around"
+        );
+        assert!(torus.add_openings_opposite(Axis::Vertical).is_ok());
+    }
+
+    #[test]
+    fn add_entrances_to_all_boundary_cells() {
+        let grid = generate_valid_maze();
+        let mut maze = from_grid(grid);
+
+        let mut entrances = maze.add_random_entrances(12).unwrap();
+        entrances.sort();
+        let mut boundary = maze.grid.boundary_cells();
+        boundary.sort();
+
+        assert_eq!(entrances, boundary);
+    }
+
+    #[test]
+    fn carve_border_loop() {
+        let mut maze = from_grid(generate_valid_maze());
+
+        assert_eq!(maze.carve_border_loop(), 3);
+        assert_eq!(maze.carve_border_loop(), 0);
+
+        let boundary = maze.grid.boundary_cells();
+        for (i, &coords) in boundary.iter().enumerate() {
+            let next = boundary[(i + 1) % boundary.len()];
+            let solution = maze.solve(coords, next).unwrap();
+            assert_eq!(solution.length(), 1);
+        }
+
+        // A perfect 4x4 maze has 15 passages, and each extra passage closes a loop
+        let passages: usize = maze.iter().map(|(_, cell)| cell.passage_count()).sum();
+        assert_eq!(passages / 2, 15 + 3);
+        assert!(maze.is_valid());
+    }
+
+    #[test]
+    fn carve_border_loop_in_a_single_row() {
+        let mut maze = OrthogonalMaze::new(4, 1);
+
+        assert_eq!(maze.carve_border_loop(), 3);
+        assert_eq!(maze.solve((0, 0), (3, 0)).unwrap().length(), 3);
+        assert_eq!(OrthogonalMaze::new(1, 1).carve_border_loop(), 0);
+    }
+
+    #[test]
+    fn fractalize() {
+        let mut maze = from_grid(generate_valid_maze());
+        maze.get_grid_mut()
+            .carve_opening((3, 3), Cell::EAST)
+            .unwrap();
+
+        let fractal = maze.fractalize(3).unwrap();
+        let grid = fractal.grid();
+
+        assert_eq!((grid.width(), grid.height()), (12, 12));
+        assert!(fractal.is_valid());
+        // A perfect maze has one passage less than cells, not counting the opening
+        let passages: usize = fractal.iter().map(|(_, cell)| cell.passage_count()).sum();
+        assert_eq!((passages - 1) / 2, 12 * 12 - 1);
+        // Passages between sub-mazes line up with passages of the parent maze
+        assert!(grid.is_carved((1, 2), Cell::SOUTH));
+        assert!(grid.is_carved((2, 4), Cell::EAST));
+        assert!(!grid.is_carved((2, 1), Cell::EAST));
+        assert!(grid.is_carved((11, 10), Cell::EAST));
+    }
+
+    #[test]
+    fn fractalize_seeded_maze() {
+        let build = |seed| {
+            OrthogonalMazeBuilder::new()
+                .width(4)
+                .height(3)
+                .seed(seed)
+                .build()
+                .unwrap()
+        };
+        let maze = build(2);
+
+        assert_eq!(maze.fractalize(4).unwrap(), maze.fractalize(4).unwrap());
+        assert_eq!(maze.fractalize(4).unwrap(), build(2).fractalize(4).unwrap());
+    }
+
+    #[test]
+    fn fractalize_by_one_keeps_maze() {
+        let maze = from_grid(generate_valid_maze());
+
+        assert_eq!(maze.fractalize(1).unwrap(), maze);
+        assert_eq!(
+            maze.fractalize(0).unwrap_err().reason,
+            "Sub-mazes must be at least 1x1"
+        );
+    }
+
+    #[test]
+    fn wall_density() {
+        let mut maze = from_grid(generate_valid_maze());
+        maze.get_grid_mut()
+            .carve_opening((0, 0), Cell::NORTH)
+            .unwrap();
+
+        assert_eq!(maze.wall_density(), 9.0 / 24.0);
+
+        maze.carve_border_loop();
+        assert_eq!(maze.wall_density(), 6.0 / 24.0);
+
+        assert_eq!(OrthogonalMaze::new(3, 2).wall_density(), 1.0);
+        assert_eq!(OrthogonalMaze::new(1, 1).wall_density(), 0.0);
+    }
+
+    #[test]
+    fn wall_density_of_torus() {
+        // 3 walls wrap around every row and column, but none around a single row
+        let mut maze = from_grid(Grid::new_torus(3, 3));
+        assert_eq!(maze.wall_density(), 1.0);
+        maze.get_grid_mut()
+            .carve_passage((0, 0), Cell::WEST)
+            .unwrap();
+        assert_eq!(maze.wall_density(), 17.0 / 18.0);
+        for coords in [(0, 0), (1, 1), (2, 2)] {
+            maze.get_grid_mut().open_cell(coords).unwrap();
+        }
+        assert_eq!(maze.wall_density(), 6.0 / 18.0);
+        for coords in [(1, 0), (2, 1), (0, 2)] {
+            maze.get_grid_mut().open_cell(coords).unwrap();
+        }
+        assert_eq!(maze.wall_density(), 0.0);
+        assert_eq!(from_grid(Grid::new_torus(3, 1)).wall_density(), 1.0);
+
+        // A perfect 5x5 torus keeps 26 of 50 walls, and loops lower it further
+        let mut maze = OrthogonalMazeBuilder::new()
+            .width(5)
+            .height(5)
+            .torus(true)
+            .seed(1)
+            .build()
+            .unwrap();
+        assert_eq!(maze.wall_density(), 0.52);
+        let culled = maze.cull_dead_ends(|_| 1.0);
+        assert!(culled > 0);
+        let metrics = maze.bias_metrics();
+        let passages = metrics.horizontal_passages + metrics.vertical_passages;
+        assert_eq!(maze.wall_density(), (50 - passages) as f64 / 50.0);
+        assert!(maze.wall_density() < 0.52);
+    }
+
+    #[test]
+    fn total_wall_length() {
+        let mut maze = OrthogonalMaze::new(2, 1);
+        assert_eq!(maze.total_wall_length(1.0), 7.0);
+
+        let grid = maze.get_grid_mut();
+        grid.carve_passage((0, 0), Cell::EAST).unwrap();
+        grid.carve_opening((1, 0), Cell::EAST).unwrap();
+        assert_eq!(maze.total_wall_length(2.5), 5.0 * 2.5);
+
+        let maze = from_grid(generate_valid_maze());
+        assert_eq!(maze.total_wall_length(1.0), (16 + 9) as f64);
+    }
+
+    #[test]
+    fn total_wall_length_of_torus() {
+        // The walls wrapping around are counted once, but the ones along a single row twice
+        assert_eq!(from_grid(Grid::new_torus(2, 2)).total_wall_length(1.0), 8.0);
+        let mut maze = from_grid(Grid::new_torus(3, 1));
+        assert_eq!(maze.total_wall_length(1.0), 9.0);
+
+        maze.get_grid_mut()
+            .carve_passage((0, 0), Cell::WEST)
+            .unwrap();
+        assert_eq!(maze.total_wall_length(1.0), 8.0);
+    }
+
+    #[test]
+    fn connect_components() {
+        let mut maze = from_grid(generate_valid_maze());
+        let grid = maze.get_grid_mut();
+        // Split off the top right corner and the bottom row
+        grid.remove_passage((3, 0), Cell::SOUTH).unwrap();
+        grid.remove_passage((0, 2), Cell::SOUTH).unwrap();
+        assert!(!maze.is_valid());
+
+        assert_eq!(maze.connect_components(), 2);
+        assert!(maze.is_valid());
+        let passages: usize = maze.iter().map(|(_, cell)| cell.passage_count()).sum();
+        assert_eq!(passages / 2, 4 * 4 - 1);
+    }
+
+    #[test]
+    fn algorithm_name() {
+        let build = |algorithm| {
+            OrthogonalMazeBuilder::new()
+                .algorithm(algorithm)
+                .build()
+                .unwrap()
+        };
+
+        assert_eq!(build(Box::new(Eller)).algorithm_name(), Some("Eller"));
+        assert_eq!(
+            build(Box::new(GrowingTree::new(Method::Newest))).algorithm_name(),
+            Some("GrowingTree")
+        );
+        let maze = build(Box::new(Sidewinder));
+        assert_eq!(maze.clone().algorithm_name(), Some("Sidewinder"));
+
+        let mut read = OrthogonalMaze::from_bytes(&maze.to_bytes()).unwrap();
+        assert_eq!(read.algorithm_name(), None);
+        read.regenerate(1);
+        assert_eq!(read.algorithm_name(), Some("RecursiveBacktracking"));
+    }
+
+    #[test]
+    fn id() {
+        let mut maze = from_grid(generate_valid_maze());
+        let id = maze.id();
+
+        assert_eq!(id.len(), "4x4-XXXX-XXXX".len());
+        assert!(id.starts_with("4x4-"));
+        assert!(id[4..]
+            .chars()
+            .all(|c| c == '-' || c.is_ascii_digit() || c.is_ascii_uppercase()));
+        assert_eq!(from_grid(generate_valid_maze()).id(), id);
+
+        // Every wall, opening and the seed change the id
+        let mut ids = HashSet::from([id]);
+        maze.get_grid_mut().toggle_wall((1, 1), Pole::S).unwrap();
+        assert!(ids.insert(maze.id()));
+        maze.get_grid_mut()
+            .carve_opening((0, 0), Cell::WEST)
+            .unwrap();
+        assert!(ids.insert(maze.id()));
+        maze.set_seed(Some(0));
+        assert!(ids.insert(maze.id()));
+        maze.regenerate(1);
+        assert!(ids.insert(maze.id()));
+        assert!(ids.insert(OrthogonalMaze::new(4, 3).id()));
+        assert!(ids.insert(from_grid(Grid::new_torus(4, 3)).id()));
+    }
+
+    #[test]
+    fn component_count() {
+        let mut maze = from_grid(generate_valid_maze());
+        assert_eq!(maze.component_count(), 1);
+
+        let grid = maze.get_grid_mut();
+        grid.remove_passage((3, 0), Cell::SOUTH).unwrap();
+        grid.remove_passage((0, 2), Cell::SOUTH).unwrap();
+        assert_eq!(maze.component_count(), 3);
+
+        // Loops and openings don't change the parts
+        maze.carve_border_loop();
+        maze.get_grid_mut()
+            .carve_opening((0, 0), Cell::NORTH)
+            .unwrap();
+        assert_eq!(maze.component_count(), 1);
+        assert_eq!(OrthogonalMaze::new(3, 2).component_count(), 6);
+    }
+
+    #[test]
+    fn is_perfect() {
+        let mut maze = from_grid(generate_valid_maze());
+        assert!(maze.is_perfect());
+
+        // Openings don't make loops
+        maze.get_grid_mut()
+            .carve_opening((0, 0), Cell::NORTH)
+            .unwrap();
+        assert!(maze.is_perfect());
+
+        // Split parts, as well as loops, make a maze imperfect
+        let mut split = maze.clone();
+        split
+            .get_grid_mut()
+            .remove_passage((3, 0), Cell::SOUTH)
+            .unwrap();
+        assert!(!split.is_perfect());
+        maze.carve_border_loop();
+        assert!(!maze.is_perfect());
+
+        assert!(!OrthogonalMaze::new(2, 2).is_perfect());
+        assert!(OrthogonalMaze::new(1, 1).is_perfect());
+    }
+
+    #[test]
+    fn connect_components_around_mask() {
+        let build = || {
+            OrthogonalMazeBuilder::new()
+                .width(8)
+                .height(8)
+                .seed(3)
+                .mask(Mask::new().solid_rect((2, 2), 3, 3))
+                .sparsity(0.5)
+                .build()
+                .unwrap()
+        };
+        let mut maze = build();
+        let mut other = build();
+
+        assert!(maze.connect_components() > 0);
+        assert!(maze.is_valid());
+        for y in 2..5 {
+            for x in 2..5 {
+                assert!(maze[(x, y)].is_empty(), "at ({x}, {y})");
+            }
+        }
+
+        other.connect_components();
+        assert_eq!(maze, other);
+    }
+
+    #[test]
+    fn connect_connected_components() {
+        let mut maze = from_grid(generate_valid_maze());
+        maze.carve_border_loop();
+        let expected = maze.clone();
+
+        assert_eq!(maze.connect_components(), 0);
+        assert_eq!(maze, expected);
+    }
+
+    #[test]
+    fn add_too_many_random_entrances() {
+        let grid = generate_valid_maze();
+        let mut maze = from_grid(grid);
+
+        let err = maze.add_random_entrances(13).unwrap_err();
+        assert_eq!(err.reason, "Cannot place 13 entrances on 12 boundary cells");
+    }
+
+    #[test]
+    fn regenerate_reuses_grid() {
+        let mut maze = OrthogonalMaze::new(8, 6);
+        let cells = maze.grid.storage.as_ptr();
+
+        maze.regenerate(3);
+        let first = maze.clone();
+        maze.regenerate(4);
+        assert_ne!(maze, first);
+        maze.regenerate(3);
+
+        assert!(maze.is_valid());
+        assert_eq!(maze, first);
+        assert_eq!(maze.grid.storage.as_ptr(), cells);
+        assert_eq!((maze.grid.width(), maze.grid.height()), (8, 6));
+    }
+
+    #[test]
+    fn observer_skips_straightened_corridors() {
+        let carved = Arc::new(Mutex::new(0));
+        let counter = Arc::clone(&carved);
+
+        let maze = OrthogonalMazeBuilder::new()
+            .width(12)
+            .height(12)
+            .min_corridor(4)
+            .observer(Box::new(move |_, _| *counter.lock().unwrap() += 1))
+            .seed(1)
+            .build()
+            .unwrap();
+
+        assert!(maze.is_valid());
+        assert_eq!(*carved.lock().unwrap(), 12 * 12 - 1);
+    }
+
+    #[test]
+    fn regenerate_keeps_algorithm() {
+        let build = || {
+            OrthogonalMazeBuilder::new()
+                .width(9)
+                .height(7)
+                .algorithm(Box::new(HuntAndKill::new()))
+                .start_coords((2, 2))
+                .seed(11)
+        };
+        let mut maze = build().seed(10).build().unwrap();
+
+        maze.regenerate(11);
+
+        assert!(maze.is_valid());
+        assert_eq!(maze, build().build().unwrap());
+    }
+
+    #[test]
+    fn regenerate_discards_post_processing() {
+        let mut maze = OrthogonalMazeBuilder::new().seed(5).build().unwrap();
+        let expected = maze.clone();
+
+        maze.add_random_entrances(3).unwrap();
+        maze.regenerate(5);
+
+        assert_eq!(maze, expected);
+    }
+
+    fn from_grid(grid: Grid) -> OrthogonalMaze {
+        let mut maze = OrthogonalMaze::new(grid.width(), grid.height());
+        *maze.get_grid_mut() = grid;
+        maze
+    }
+
+    fn generate_valid_maze() -> Grid {
+        let mut grid = Grid::new(4, 4);
+
+        grid.carve_passage((0, 0), Cell::SOUTH).unwrap();
+        grid.carve_passage((0, 1), Cell::EAST).unwrap();
+        grid.carve_passage((0, 2), Cell::EAST).unwrap();
+        grid.carve_passage((0, 2), Cell::SOUTH).unwrap();
+        grid.carve_passage((0, 3), Cell::EAST).unwrap();
+
+        grid.carve_passage((1, 0), Cell::EAST).unwrap();
+        grid.carve_passage((1, 1), Cell::EAST).unwrap();
+        grid.carve_passage((1, 1), Cell::SOUTH).unwrap();
+        grid.carve_passage((1, 2), Cell::EAST).unwrap();
+        grid.carve_passage((1, 3), Cell::EAST).unwrap();
+
+        grid.carve_passage((2, 0), Cell::EAST).unwrap();
+        grid.carve_passage((2, 2), Cell::EAST).unwrap();
+        grid.carve_passage((2, 3), Cell::EAST).unwrap();
+
+        grid.carve_passage((3, 1), Cell::NORTH).unwrap();
+        grid.carve_passage((3, 1), Cell::SOUTH).unwrap();
+
+        grid
+    }
+
+    #[test]
+    fn solve() {
+        let maze = from_grid(generate_valid_maze());
+
+        let solution = maze.solve((0, 0), (3, 0)).unwrap();
+
+        assert_eq!(
+            solution.path,
+            vec![
+                (0, 0),
+                (0, 1),
+                (1, 1),
+                (1, 2),
+                (2, 2),
+                (3, 2),
+                (3, 1),
+                (3, 0)
+            ]
+        );
+        assert_eq!(solution.length(), 7);
+    }
+
+    #[test]
+    fn has_unique_solution() {
+        let mut maze = from_grid(generate_valid_maze());
+        assert!(maze.has_unique_solution((0, 0), (3, 0)));
+        assert!(maze.has_unique_solution((2, 1), (2, 1)));
+
+        // A loop touching the solution in (1, 2) only
+        let grid = maze.get_grid_mut();
+        grid.carve_passage((1, 2), Cell::SOUTH).unwrap();
+        assert!(maze.has_unique_solution((0, 0), (3, 0)));
+
+        // A loop going around (1, 1) -> (1, 2) -> (2, 2) of the solution
+        let grid = maze.get_grid_mut();
+        grid.carve_passage((2, 1), Cell::SOUTH).unwrap();
+        assert!(!maze.has_unique_solution((0, 0), (3, 0)));
+        assert!(maze.has_unique_solution((0, 0), (1, 1)));
+    }
+
+    #[test]
+    fn has_unique_solution_without_path() {
+        let maze = OrthogonalMaze::new(3, 3);
+
+        assert!(!maze.has_unique_solution((0, 0), (2, 2)));
+        assert!(!maze.has_unique_solution((0, 0), (3, 3)));
+        assert!(maze.has_unique_solution((1, 1), (1, 1)));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn adjacency_json_round_trip() {
+        let mut maze = from_grid(generate_valid_maze());
+        maze.carve_border_loop();
+
+        let json = maze.to_adjacency_json();
+        let adjacency: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(adjacency.as_object().unwrap().len(), 16);
+        for (coords, cell) in maze.iter() {
+            let neighbours = adjacency[format!("{},{}", coords.0, coords.1)]
+                .as_array()
+                .unwrap();
+            assert_eq!(neighbours.len(), cell.passage_count(), "at {coords:?}");
+        }
+        assert_eq!(OrthogonalMaze::from_adjacency_json(&json).unwrap(), maze);
+
+        // Openings are not a part of the graph, so they are lost
+        let mut opened = maze.clone();
+        opened
+            .get_grid_mut()
+            .carve_opening((0, 0), Cell::NORTH)
+            .unwrap();
+        assert_eq!(opened.to_adjacency_json(), json);
+        assert_eq!(OrthogonalMaze::from_adjacency_json(&json).unwrap(), maze);
+
+        // Passages wrapping around a torus restore it
+        let torus = OrthogonalMazeBuilder::new()
+            .width(6)
+            .height(4)
+            .torus(true)
+            .seed(1)
+            .build()
+            .unwrap();
+        let restored = OrthogonalMaze::from_adjacency_json(&torus.to_adjacency_json()).unwrap();
+        assert!(restored.grid().is_torus());
+        assert_eq!(restored, torus);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn invalid_adjacency_json() {
+        let read = |json: &str| {
+            OrthogonalMaze::from_adjacency_json(json)
+                .err()
+                .unwrap()
+                .reason
+        };
+
+        assert!(read("[]").starts_with("Invalid adjacency list"));
+        assert_eq!(read("{}"), "An adjacency list has no cells");
+        assert_eq!(read(r#"{"0:0": []}"#), "Invalid cell `0:0`, expected `x,y`");
+        assert_eq!(
+            read(r#"{"0,0": ["x,1"], "0,1": []}"#),
+            "Invalid cell `x,1`, expected `x,y`"
+        );
+        assert_eq!(
+            read(r#"{"0,0": [], "1,1": []}"#),
+            "An adjacency list of a 2x2 maze must list all of its 4 cells, but it lists 2"
+        );
+        assert_eq!(
+            read(r#"{"0,0": ["0,1"], "0,1": []}"#),
+            "Cell (0, 0) is connected to (0, 1), but not the other way around"
+        );
+        assert_eq!(
+            read(r#"{"0,0": ["1,1"], "0,1": [], "1,0": [], "1,1": ["0,0"]}"#),
+            "Cannot move to a cell. Reason: Cell is not adjacent to the (1, 1) cell. Coords: x = 0, y = 0"
+        );
+        assert_eq!(
+            read(r#"{"18446744073709551615,0": []}"#),
+            "An adjacency list lists cells of too large a maze"
+        );
+        assert_eq!(
+            read(r#"{"4294967296,4294967296": []}"#),
+            "An adjacency list lists cells of too large a maze"
+        );
+    }
+
+    #[test]
+    fn spread_points() {
+        let mut maze = OrthogonalMaze::new(1, 5);
+        for y in 0..4 {
+            maze.get_grid_mut()
+                .carve_passage((0, y), Cell::SOUTH)
+                .unwrap();
+        }
+
+        assert_eq!(maze.spread_points((0, 0), 2), vec![(0, 4), (0, 2)]);
+        // Ties go to the first cell, and there are only four cells to choose
+        assert_eq!(
+            maze.spread_points((0, 0), 10),
+            vec![(0, 4), (0, 2), (0, 1), (0, 3)]
+        );
+        assert_eq!(maze.spread_points((0, 2), 2), vec![(0, 0), (0, 4)]);
+        assert!(maze.spread_points((0, 0), 0).is_empty());
+        assert!(maze.spread_points((1, 0), 2).is_empty());
+
+        // Unreachable cells are never chosen
+        let mut maze = from_grid(generate_valid_maze());
+        maze.get_grid_mut()
+            .remove_passage((3, 0), Cell::SOUTH)
+            .unwrap();
+        let reachable = maze.within_steps((0, 0), usize::MAX);
+        let points = maze.spread_points((0, 0), 16);
+        assert_eq!(points.len(), reachable.len() - 1);
+        assert!(points.iter().all(|coords| reachable.contains(coords)));
+    }
+
+    #[test]
+    fn within_steps() {
+        let maze = from_grid(generate_valid_maze());
+
+        assert_eq!(maze.within_steps((0, 0), 0), vec![(0, 0)]);
+        assert_eq!(maze.within_steps((0, 0), 2), vec![(0, 0), (0, 1), (1, 1)]);
+        // Branches of (1, 1) lead both east and south
+        let mut cells = maze.within_steps((0, 0), 3);
+        cells.sort();
+        assert_eq!(cells, vec![(0, 0), (0, 1), (1, 1), (1, 2), (2, 1)]);
+        assert_eq!(maze.within_steps((0, 0), 100).len(), 16);
+        assert!(maze.within_steps((4, 0), 1).is_empty());
+    }
+
+    #[test]
+    fn all_shortest_path_cells() {
+        let mut maze = from_grid(generate_valid_maze());
+        let solution = maze.solve((0, 0), (3, 0)).unwrap();
+        assert_eq!(
+            maze.all_shortest_path_cells((0, 0), (3, 0)),
+            solution.path.into_iter().collect()
+        );
+
+        // A loop with two equally short sides (1, 2) and (2, 1) between (1, 1) and (2, 2)
+        let grid = maze.get_grid_mut();
+        grid.carve_passage((2, 1), Cell::SOUTH).unwrap();
+        let cells = maze.all_shortest_path_cells((0, 0), (3, 0));
+        assert_eq!(cells.len(), 9);
+        assert!(cells.contains(&(1, 2)) && cells.contains(&(2, 1)));
+
+        // A longer way round the loop isn't one of the shortest paths
+        let cells = maze.all_shortest_path_cells((1, 1), (1, 2));
+        assert_eq!(cells, HashSet::from([(1, 1), (1, 2)]));
+    }
+
+    #[test]
+    fn all_shortest_path_cells_in_open_grid() {
+        let mut maze = OrthogonalMaze::new(3, 3);
+        assert!(maze.all_shortest_path_cells((0, 0), (2, 2)).is_empty());
+        assert!(maze.all_shortest_path_cells((0, 0), (3, 3)).is_empty());
+
+        let grid = maze.get_grid_mut();
+        for y in 0..3 {
+            for x in 0..3 {
+                grid.open_cell((x, y)).unwrap();
+            }
+        }
+
+        assert_eq!(maze.all_shortest_path_cells((0, 0), (2, 2)).len(), 9);
+        assert_eq!(
+            maze.all_shortest_path_cells((0, 0), (2, 0)),
+            HashSet::from([(0, 0), (1, 0), (2, 0)])
+        );
+        assert_eq!(
+            maze.all_shortest_path_cells((1, 1), (1, 1)),
+            HashSet::from([(1, 1)])
+        );
+    }
 
-use super::{
-    errors::MazeSaveError,
-    formatters::{Formatter, Saveable},
-    grid::{cell::Cell, Grid},
-    validate::validate,
-};
-use std::fmt;
+    #[test]
+    fn avg_branching() {
+        let maze = from_grid(generate_valid_maze());
 
-/// An orthogonal maze
-///
-/// Represents a standard orthogonal maze where each cell is a square containing zero or maximum
-/// three walls
-#[derive(Debug, Clone, PartialEq, Eq, Resource)]
-pub struct OrthogonalMaze {
-    grid: Grid,
-}
+        // Side passages from (1, 1), (1, 2) and (3, 0) along the path of 8 cells
+        assert_eq!(maze.avg_branching((0, 0), (3, 0)), 3.0 / 8.0);
+        // A side passage from (0, 1) to (1, 1)
+        assert_eq!(maze.avg_branching((0, 0), (0, 1)), 0.5);
+        assert_eq!(maze.avg_branching((1, 1), (1, 1)), 3.0);
+    }
 
-impl OrthogonalMaze {
-    /// Returns a new instance of an orthogonal maze with a given width and height
-    pub fn new(width: usize, height: usize) -> OrthogonalMaze {
-        OrthogonalMaze {
-            grid: Grid::new(width, height),
-        }
+    #[test]
+    fn avg_branching_without_path() {
+        let maze = OrthogonalMaze::new(3, 3);
+
+        assert_eq!(maze.avg_branching((0, 0), (2, 2)), 0.0);
+        assert_eq!(maze.avg_branching((0, 0), (3, 3)), 0.0);
     }
 
-    /// Returns a mutable ref to a grid
-    pub fn get_grid_mut(&mut self) -> &mut Grid {
-        &mut self.grid
+    #[test]
+    fn adjust_dead_ends() {
+        let build = || {
+            OrthogonalMazeBuilder::new()
+                .width(12)
+                .height(12)
+                .seed(3)
+                .build()
+                .unwrap()
+        };
+        let mut maze = build();
+        let before = maze.dead_ends().len();
+
+        // Dead ends are never added
+        assert_eq!(maze.adjust_dead_ends(before + 5), before);
+        assert_eq!(maze, build());
+
+        for target in [before - 1, before / 2, 3, 0] {
+            let count = maze.adjust_dead_ends(target);
+            assert_eq!(count, maze.dead_ends().len());
+            assert!((target..=target + 1).contains(&count));
+            assert!(maze.is_valid());
+        }
+
+        let (mut first, mut second) = (build(), build());
+        first.adjust_dead_ends(4);
+        second.adjust_dead_ends(4);
+        assert_eq!(first, second);
     }
 
-    /// Returns `true` if a maze is valid. Otherwise, returns `false`
-    pub fn is_valid(&self) -> bool {
-        validate(&self.grid)
+    #[test]
+    fn adjust_dead_ends_without_walled_neighbours() {
+        let mut maze = OrthogonalMaze::new(3, 1);
+        let grid = maze.get_grid_mut();
+        grid.carve_passage((0, 0), Cell::EAST).unwrap();
+        grid.carve_passage((1, 0), Cell::EAST).unwrap();
+
+        assert_eq!(maze.adjust_dead_ends(0), 2);
     }
 
-    /// Saves a maze into a file to a given path using a given formatter
-    pub fn save<F, T>(&self, path: &str, formatter: F) -> Result<String, MazeSaveError>
-    where
-        F: Formatter<T>,
-        T: Saveable,
-    {
-        let data = formatter.format(&self.grid);
-        Saveable::save(&data, path)
+    #[test]
+    fn wall_distance_field() {
+        let mut maze = OrthogonalMaze::new(5, 4);
+        // A 5x4 room without inner walls
+        let grid = maze.get_grid_mut();
+        for y in 0..4 {
+            for x in 0..5 {
+                if x < 4 {
+                    grid.carve_passage((x, y), Cell::EAST).unwrap();
+                }
+                if y < 3 {
+                    grid.carve_passage((x, y), Cell::SOUTH).unwrap();
+                }
+            }
+        }
+
+        assert_eq!(
+            maze.wall_distance_field(),
+            vec![
+                vec![1, 1, 1, 1, 1],
+                vec![1, 2, 2, 2, 1],
+                vec![1, 2, 2, 2, 1],
+                vec![1, 1, 1, 1, 1],
+            ]
+        );
+
+        // An opening in the outer wall is not a wall
+        maze.get_grid_mut()
+            .carve_opening((2, 0), Cell::NORTH)
+            .unwrap();
+        assert_eq!(maze.wall_distance_field()[0][2], 2);
     }
 
-    /// Returns an iterator over the maze where `index == y * Maze::width + x`.
-    ///
-    /// The iterator yields all items, `(Coords, Cell)`, from start to end.
-    pub const fn iter(&self) -> OrthogonalMazeIterator {
-        OrthogonalMazeIterator {
-            maze: self,
-            index: 0,
+    #[test]
+    fn wall_distance_field_in_large_room() {
+        let mut maze = OrthogonalMaze::new(7, 1);
+        for x in 0..6 {
+            maze.get_grid_mut()
+                .carve_passage((x, 0), Cell::EAST)
+                .unwrap();
+        }
+        assert_eq!(maze.wall_distance_field(), vec![vec![1; 7]]);
+
+        let mut maze = OrthogonalMaze::new(7, 7);
+        let grid = maze.get_grid_mut();
+        for y in 0..7 {
+            for x in 0..7 {
+                if x < 6 {
+                    grid.carve_passage((x, y), Cell::EAST).unwrap();
+                }
+                if y < 6 {
+                    grid.carve_passage((x, y), Cell::SOUTH).unwrap();
+                }
+            }
         }
+        let field = maze.wall_distance_field();
+        assert_eq!(field[3], vec![1, 2, 3, 4, 3, 2, 1]);
+        assert_eq!(field[1][1], 2);
     }
-}
 
-impl std::ops::Index<Coords> for OrthogonalMaze {
-    type Output = Cell;
+    #[test]
+    fn move_from() {
+        let maze = from_grid(generate_valid_maze());
 
-    fn index(&self, index: Coords) -> &Self::Output {
-        &self.grid[index]
+        assert_eq!(maze.move_from((0, 0), Pole::S), Some((0, 1)));
+        assert_eq!(maze.move_from((3, 1), Pole::N), Some((3, 0)));
+        assert_eq!(maze.move_from((0, 0), Pole::E), None);
+        assert_eq!(maze.move_from((0, 0), Pole::N), None);
+        assert_eq!(maze.move_from((4, 0), Pole::W), None);
+        assert!(maze.can_move((1, 0), Pole::E));
+        assert!(!maze.can_move((1, 0), Pole::S));
     }
-}
 
-impl fmt::Display for OrthogonalMaze {
-    /// Writes a formatted maze into a buffer
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.grid)?;
-        Ok(())
+    #[test]
+    fn move_through_opening() {
+        let mut maze = OrthogonalMaze::new(1, 1);
+        maze.get_grid_mut()
+            .carve_opening((0, 0), Cell::NORTH)
+            .unwrap();
+
+        assert!(maze[(0, 0)].contains(Cell::NORTH));
+        assert!(!maze.can_move((0, 0), Pole::N));
     }
-}
 
-pub struct OrthogonalMazeIterator<'a> {
-    maze: &'a OrthogonalMaze,
-    index: usize,
-}
+    #[test]
+    fn central_reachable() {
+        let maze = from_grid(generate_valid_maze());
 
-impl<'a> Iterator for OrthogonalMazeIterator<'a> {
-    type Item = (Coords, &'a Cell);
-    fn next(&mut self) -> Option<Self::Item> {
-        let width = self.maze.grid.width();
-        if self.index < self.maze.grid.cells.len() {
-            let result = Some((
-                (self.index % width, self.index / width),
-                &self.maze.grid.cells[self.index],
-            ));
-            self.index += 1;
-            result
-        } else {
-            None
-        }
+        assert_eq!(maze.central_reachable((3, 3)), Some((1, 2)));
+        assert_eq!(maze.central_reachable((4, 0)), None);
     }
-}
 
-pub struct OrthogonalMazeIntoIterator {
-    maze: OrthogonalMaze,
-    index: usize,
-    width: usize,
-}
+    #[test]
+    fn central_reachable_prefers_closest_to_start() {
+        let mut maze = OrthogonalMaze::new(4, 4);
+        // (2, 1) and (1, 2) are equally close to the center, but (2, 1) is reached first
+        let grid = maze.get_grid_mut();
+        grid.carve_passage((3, 0), Cell::SOUTH).unwrap();
+        grid.carve_passage((3, 1), Cell::WEST).unwrap();
+        grid.carve_passage((3, 1), Cell::SOUTH).unwrap();
+        grid.carve_passage((3, 2), Cell::WEST).unwrap();
+        grid.carve_passage((2, 2), Cell::WEST).unwrap();
 
-impl IntoIterator for OrthogonalMaze {
-    type Item = (Coords, Cell);
-    type IntoIter = OrthogonalMazeIntoIterator;
+        assert_eq!(maze.central_reachable((3, 0)), Some((2, 1)));
+    }
 
-    fn into_iter(self) -> OrthogonalMazeIntoIterator {
-        OrthogonalMazeIntoIterator {
-            width: self.grid.width(),
-            maze: self,
-            index: 0,
-        }
+    #[test]
+    fn central_reachable_in_isolated_region() {
+        let mut maze = OrthogonalMaze::new(5, 5);
+        maze.get_grid_mut()
+            .carve_passage((0, 0), Cell::EAST)
+            .unwrap();
+
+        assert_eq!(maze.central_reachable((0, 0)), Some((1, 0)));
+        assert_eq!(maze.central_reachable((4, 4)), Some((4, 4)));
     }
-}
 
-impl Iterator for OrthogonalMazeIntoIterator {
-    type Item = (Coords, Cell);
+    #[test]
+    fn wall_grid_round_trip() {
+        let mut grid = generate_valid_maze();
+        grid.carve_opening((0, 0), Cell::WEST).unwrap();
+        grid.carve_opening((3, 3), Cell::SOUTH).unwrap();
+        let maze = from_grid(grid);
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let width = self.width;
-        if self.maze.grid.cells.is_empty() {
-            return None;
-        }
-        let cell = self.maze.get_grid_mut().cells.remove(0);
-        let coords = (self.index % width, self.index / width);
-        self.index += 1;
+        let walls = maze.to_wall_grid();
 
-        Some((coords, cell))
+        assert_eq!((walls.len(), walls[0].len()), (9, 9));
+        assert!(!walls[1][0] && !walls[8][7]);
+        assert!(walls[0][1] && walls[2][2]);
+        assert_eq!(OrthogonalMaze::from_wall_grid(&walls).unwrap(), maze);
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use crate::maze::grid::cell::Cell;
+    #[test]
+    fn wall_grid_of_torus() {
+        let mut maze = from_grid(Grid::new_torus(3, 1));
+        maze.get_grid_mut()
+            .carve_passage((0, 0), Cell::WEST)
+            .unwrap();
 
-    use super::*;
+        let walls = maze.to_wall_grid();
+
+        // The passage wrapping around the row is on both sides
+        assert!(!walls[1][0] && !walls[1][6]);
+        assert!(walls[1][2] && walls[1][4]);
+        let flat = OrthogonalMaze::from_wall_grid(&walls).unwrap();
+        assert!(!flat.grid().is_torus());
+        assert_eq!((flat[(0, 0)], flat[(2, 0)]), (Cell::WEST, Cell::EAST));
+    }
 
     #[test]
-    fn iterators_have_size() {
-        let grid = generate_valid_maze();
-        let maze = OrthogonalMaze { grid };
+    fn from_wall_grid_ignores_cells_and_joints() {
+        let walls = vec![
+            vec![false, true, false, true, false],
+            vec![true, true, false, false, true],
+            vec![false, true, false, true, false],
+        ];
 
-        let iter_count = maze.iter().count();
+        let maze = OrthogonalMaze::from_wall_grid(&walls).unwrap();
 
-        assert_eq!(iter_count, 16);
+        assert_eq!(maze[(0, 0)], Cell::EAST);
+        assert_eq!(maze[(1, 0)], Cell::WEST);
+    }
+
+    #[test]
+    fn from_invalid_wall_grids() {
+        for walls in [
+            vec![],
+            vec![vec![true; 3]; 1],
+            vec![vec![true; 3]; 4],
+            vec![vec![true; 4]; 3],
+            vec![vec![true; 5], vec![true; 5], vec![true; 3]],
+        ] {
+            assert!(OrthogonalMaze::from_wall_grid(&walls).is_err());
+        }
+    }
+
+    #[test]
+    fn binary_round_trip() {
+        let mut maze = OrthogonalMaze::new(3, 1);
+        let grid = maze.get_grid_mut();
+        grid.carve_passage((0, 0), Cell::EAST).unwrap();
+        grid.carve_opening((0, 0), Cell::WEST).unwrap();
+
+        let bytes = maze.to_bytes();
         assert_eq!(
-            maze.iter().nth(5).unwrap(),
-            ((1usize, 1usize), &(Cell::SOUTH | Cell::EAST | Cell::WEST))
+            bytes,
+            [b'K', b'N', b'O', b'S', 2, 0, 3, 0, 0, 0, 1, 0, 0, 0, 0x8c, 0x00]
         );
+        assert_eq!(OrthogonalMaze::from_bytes(&bytes).unwrap(), maze);
+
+        // The first version without flags
+        let v1 = [
+            b'K', b'N', b'O', b'S', 1, 3, 0, 0, 0, 1, 0, 0, 0, 0x8c, 0x00,
+        ];
+        assert_eq!(OrthogonalMaze::from_bytes(&v1).unwrap(), maze);
+
+        let mut maze = from_grid(generate_valid_maze());
+        maze.add_random_entrances(3).unwrap();
+        let mut bytes = vec![];
+        maze.write_to(&mut bytes).unwrap();
+        assert_eq!(OrthogonalMaze::read_from(bytes.as_slice()).unwrap(), maze);
+
+        let torus = OrthogonalMazeBuilder::new()
+            .width(5)
+            .height(2)
+            .torus(true)
+            .seed(2)
+            .build()
+            .unwrap();
+        let bytes = torus.to_bytes();
+        assert_eq!(bytes[5], 0b1);
+        assert_eq!(OrthogonalMaze::from_bytes(&bytes).unwrap(), torus);
     }
 
     #[test]
-    fn into_iterators_have_size() {
-        let grid = generate_valid_maze();
-        let maze = OrthogonalMaze { grid };
+    fn read_binary_stops_at_unknown_magic() {
+        struct Unreachable;
 
-        let iter_count = maze.clone().into_iter().count();
+        impl Read for Unreachable {
+            fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+                panic!("Read past the magic bytes")
+            }
+        }
 
-        assert_eq!(iter_count, 16);
+        let error = OrthogonalMaze::read_from(b"KNOX".chain(Unreachable)).unwrap_err();
         assert_eq!(
-            maze.into_iter().nth(5).unwrap(),
-            ((1usize, 1usize), (Cell::SOUTH | Cell::EAST | Cell::WEST))
+            error.reason,
+            "Unknown magic bytes [75, 78, 79, 88] of the binary format, expected [75, 78, 79, 83]"
         );
     }
 
     #[test]
-    fn display_orthogonal_maze() {
-        let mut expected = String::new();
-        expected.push_str(" _______ \n");
-        expected.push_str("| |___  |\n");
-        expected.push_str("|_   _| |\n");
-        expected.push_str("|  _____|\n");
-        expected.push_str("|_______|\n");
+    fn read_invalid_binary() {
+        let bytes = from_grid(generate_valid_maze()).to_bytes();
+        let read = |bytes: &[u8]| OrthogonalMaze::from_bytes(bytes).unwrap_err().reason;
 
-        let grid = generate_valid_maze();
-        let maze = OrthogonalMaze { grid };
-        let actual = maze.to_string();
+        let mut version = bytes.clone();
+        version[4] = 3;
+        assert_eq!(
+            read(&version),
+            "Version 3 of the binary format is newer than the supported version 2, the maze requires a newer release"
+        );
+        version[4] = 0;
+        assert_eq!(
+            read(&version),
+            "Unsupported version 0 of the binary format, expected 2"
+        );
 
-        assert_eq!(actual, expected);
+        let mut flags = bytes.clone();
+        flags[5] = 0b110;
+        assert_eq!(
+            read(&flags),
+            "Unknown flags 0b00000110 of the binary format"
+        );
+
+        let mut empty = bytes[..14].to_vec();
+        empty[6..10].copy_from_slice(&0u32.to_le_bytes());
+        assert_eq!(
+            read(&empty),
+            "A maze in the binary format must have at least a single cell and fit into memory, but it is 0x4"
+        );
+
+        assert_eq!(
+            read(&bytes[..bytes.len() - 1]),
+            "Cannot read the cells of the binary format: failed to fill whole buffer"
+        );
+        assert!(read(&bytes[..5]).starts_with("Cannot read the flags"));
+        assert!(read(&bytes[..8]).starts_with("Cannot read the width"));
+
+        // The first cell of the second row keeps its passage to the East, while the next one loses
+        // the way back
+        let mut walled = bytes.clone();
+        walled[16] &= 0b0111_1111;
+        assert_eq!(
+            read(&walled),
+            "Cells (0, 1) and (1, 1) of the binary format disagree on the wall between them"
+        );
     }
 
     #[test]
-    fn valid_maze() {
-        let grid = generate_valid_maze();
-        let maze = OrthogonalMaze { grid };
-        assert!(maze.is_valid());
+    fn path_tree() {
+        let maze = from_grid(generate_valid_maze());
+        let tree = maze.path_tree((0, 0));
+
+        assert_eq!(tree[0][0], None);
+        assert_eq!(tree[1][0], Some(Pole::N));
+        assert_eq!(tree[1][1], Some(Pole::W));
+        assert_eq!(tree[0][3], Some(Pole::S));
+        for ((x, y), _) in maze.iter() {
+            let mut coords = (x, y);
+            let mut steps = 0;
+            while let Some(pole) = tree[coords.1][coords.0] {
+                coords = maze.move_from(coords, pole).unwrap();
+                steps += 1;
+            }
+            assert_eq!(coords, (0, 0));
+            assert_eq!(steps, maze.solve((x, y), (0, 0)).unwrap().length());
+        }
     }
 
     #[test]
-    fn invalid_maze() {
-        let grid = generate_invalid_maze();
-        let maze = OrthogonalMaze { grid };
-        assert!(!maze.is_valid());
+    fn path_tree_of_unreachable_cells() {
+        let mut maze = OrthogonalMaze::new(3, 2);
+        maze.get_grid_mut()
+            .carve_passage((1, 0), Cell::EAST)
+            .unwrap();
+
+        let tree = maze.path_tree((2, 0));
+        assert_eq!(tree, vec![vec![None, Some(Pole::E), None], vec![None; 3]]);
+        assert!(maze.path_tree((3, 0)).iter().flatten().all(Option::is_none));
     }
 
     #[test]
-    fn access_by_index_maze() {
-        let grid = generate_valid_maze();
-        let maze = OrthogonalMaze { grid };
+    fn reachable_count() {
+        let maze = from_grid(generate_valid_maze());
 
-        let cell = maze[(3, 1)];
-        assert_eq!(cell, Cell::from_bits(0b0011).unwrap());
+        assert_eq!(maze.reachable_count((3, 3)), 16);
+        assert_eq!(maze.reachable_count((0, 4)), 0);
     }
 
     #[test]
-    fn into_iterators_correct_index() {
-        let grid = generate_valid_maze();
-        let maze = OrthogonalMaze { grid };
-        let width = maze.grid.width();
+    fn reachable_count_in_isolated_regions() {
+        let mut maze = OrthogonalMaze::new(3, 3);
+        let grid = maze.get_grid_mut();
+        grid.carve_passage((0, 0), Cell::EAST).unwrap();
+        grid.carve_passage((1, 0), Cell::SOUTH).unwrap();
+        grid.carve_opening((2, 2), Cell::EAST).unwrap();
 
-        maze.into_iter()
-            .enumerate()
-            .for_each(|(idx, (coord, cell))| {
-                assert_eq!(idx, coord.1 * width + coord.0);
-                assert!(!cell.is_empty())
-            });
+        assert_eq!(maze.reachable_count((1, 1)), 3);
+        // An opening doesn't lead to any cell
+        assert_eq!(maze.reachable_count((2, 2)), 1);
     }
 
     #[test]
-    fn iterators_correct_index() {
-        let grid = generate_valid_maze();
-        let maze = OrthogonalMaze { grid };
-        let width = maze.grid.width();
+    fn bias_metrics() {
+        let maze = from_grid(generate_valid_maze());
 
-        maze.iter().enumerate().for_each(|(idx, (coord, cell))| {
-            assert_eq!(idx, coord.1 * width + coord.0);
-            assert!(!cell.is_empty())
-        });
+        let metrics = maze.bias_metrics();
+
+        assert_eq!(metrics.horizontal_passages, 10);
+        assert_eq!(metrics.vertical_passages, 5);
+        assert_eq!(metrics.straight_cells, 5);
+        assert_eq!(metrics.turning_cells, 5);
+        assert_eq!(metrics.horizontal_ratio, 10.0 / 15.0);
+        assert_eq!(metrics.straight_ratio, 0.5);
     }
 
-    fn generate_valid_maze() -> Grid {
-        let mut grid = Grid::new(4, 4);
+    #[test]
+    fn bias_metrics_ignore_openings() {
+        let mut maze = OrthogonalMaze::new(1, 1);
+        maze.get_grid_mut()
+            .carve_opening((0, 0), Cell::EAST)
+            .unwrap();
+        maze.get_grid_mut()
+            .carve_opening((0, 0), Cell::WEST)
+            .unwrap();
 
-        grid.carve_passage((0, 0), Cell::SOUTH).unwrap();
-        grid.carve_passage((0, 1), Cell::EAST).unwrap();
-        grid.carve_passage((0, 2), Cell::EAST).unwrap();
-        grid.carve_passage((0, 2), Cell::SOUTH).unwrap();
-        grid.carve_passage((0, 3), Cell::EAST).unwrap();
+        let metrics = maze.bias_metrics();
 
-        grid.carve_passage((1, 0), Cell::EAST).unwrap();
-        grid.carve_passage((1, 1), Cell::EAST).unwrap();
-        grid.carve_passage((1, 1), Cell::SOUTH).unwrap();
-        grid.carve_passage((1, 2), Cell::EAST).unwrap();
-        grid.carve_passage((1, 3), Cell::EAST).unwrap();
+        assert_eq!(metrics.horizontal_passages, 0);
+        assert_eq!(metrics.straight_cells, 0);
 
-        grid.carve_passage((2, 0), Cell::EAST).unwrap();
-        grid.carve_passage((2, 2), Cell::EAST).unwrap();
-        grid.carve_passage((2, 3), Cell::EAST).unwrap();
+        // A corridor leading out of the maze is a dead end rather than a straight cell
+        let mut maze = OrthogonalMaze::new(2, 1);
+        let grid = maze.get_grid_mut();
+        grid.carve_passage((0, 0), Cell::EAST).unwrap();
+        grid.carve_opening((1, 0), Cell::EAST).unwrap();
+        grid.carve_opening((0, 0), Cell::NORTH).unwrap();
 
-        grid.carve_passage((3, 1), Cell::NORTH).unwrap();
-        grid.carve_passage((3, 1), Cell::SOUTH).unwrap();
+        let metrics = maze.bias_metrics();
 
-        grid
+        assert_eq!(metrics.horizontal_passages, 1);
+        assert_eq!(metrics.straight_cells, 0);
+        assert_eq!(metrics.turning_cells, 0);
+    }
+
+    #[test]
+    fn solve_same_start_and_goal() {
+        let maze = from_grid(generate_valid_maze());
+
+        let solution = maze.solve((2, 2), (2, 2)).unwrap();
+
+        assert_eq!(solution.path, vec![(2, 2)]);
+        assert_eq!(solution.length(), 0);
+    }
+
+    #[test]
+    fn solve_unreachable_goal() {
+        let maze = OrthogonalMaze::new(2, 1);
+
+        assert_eq!(maze.solve((0, 0), (1, 0)), None);
+        assert_eq!(maze.solve((0, 0), (2, 0)), None);
+    }
+
+    #[test]
+    fn solve_astar() {
+        for seed in 0..5 {
+            let mut maze = OrthogonalMazeBuilder::new()
+                .width(12)
+                .height(9)
+                .seed(seed)
+                .build()
+                .unwrap();
+            let astar = |maze: &OrthogonalMaze| {
+                maze.solve_astar((0, 0), (11, 8), OrthogonalMaze::manhattan_distance)
+                    .unwrap()
+            };
+
+            let (solution, expanded) = astar(&maze);
+            assert_eq!(solution, maze.solve((0, 0), (11, 8)).unwrap());
+            assert!(expanded <= maze.reachable_count((0, 0)));
+
+            // Loops keep the solution as short as the one of the breadth-first search
+            maze.cull_dead_ends(|_| 1.0);
+            let (solution, _) = astar(&maze);
+            assert_eq!(
+                solution.length(),
+                maze.solve((0, 0), (11, 8)).unwrap().length()
+            );
+            assert!(solution.path.windows(2).all(|step| {
+                maze.connected_neighbours(step[0])
+                    .any(|next| next == step[1])
+            }));
+        }
+    }
+
+    #[test]
+    fn solve_astar_expands_fewer_cells() {
+        let mut maze = OrthogonalMaze::new(10, 10);
+        let grid = maze.get_grid_mut();
+        for y in 0..10 {
+            for x in 0..10 {
+                if x < 9 {
+                    grid.carve_passage((x, y), Cell::EAST).unwrap();
+                }
+                if y < 9 {
+                    grid.carve_passage((x, y), Cell::SOUTH).unwrap();
+                }
+            }
+        }
+
+        let (solution, manhattan) = maze
+            .solve_astar((0, 0), (9, 9), OrthogonalMaze::manhattan_distance)
+            .unwrap();
+        let (_, dijkstra) = maze.solve_astar((0, 0), (9, 9), |_, _| 0.0).unwrap();
+
+        // Without walls, the Manhattan distance leads straight to the goal
+        assert_eq!(solution.length(), 18);
+        assert_eq!(manhattan, 19);
+        assert!(dijkstra > 90);
+    }
+
+    #[test]
+    fn solve_around_torus() {
+        let mut flat = from_grid(Grid::new(6, 4));
+        let mut torus = from_grid(Grid::new_torus(6, 4));
+        for maze in [&mut flat, &mut torus] {
+            let grid = maze.get_grid_mut();
+            for x in 0..6 {
+                grid.carve_passage((x, 0), Cell::EAST).ok();
+            }
+        }
+
+        // The row is a loop around the torus, so the shortest way crosses the edge
+        assert_eq!(
+            flat.solve((0, 0), (5, 0)).unwrap().path,
+            vec![(0, 0), (1, 0), (2, 0), (3, 0), (4, 0), (5, 0)]
+        );
+        assert_eq!(
+            torus.solve((0, 0), (5, 0)).unwrap().path,
+            vec![(0, 0), (5, 0)]
+        );
+        assert_eq!(torus.reachable_count((5, 0)), 6);
+        assert_eq!(torus.component_count(), 1 + 6 * 3);
+        assert_ne!(flat.grid, torus.grid);
+    }
+
+    #[test]
+    fn solve_astar_unreachable_goal() {
+        let maze = OrthogonalMaze::new(2, 1);
+
+        assert_eq!(
+            maze.solve_astar((0, 0), (1, 0), OrthogonalMaze::manhattan_distance),
+            None
+        );
+        assert_eq!(
+            maze.solve_astar((0, 0), (2, 0), OrthogonalMaze::manhattan_distance),
+            None
+        );
+        assert_eq!(
+            OrthogonalMaze::new(3, 3).solve_astar((1, 1), (1, 1), |_, _| 0.0),
+            Some((Solution::new(vec![(1, 1)]), 1))
+        );
     }
 
     fn generate_invalid_maze() -> Grid {
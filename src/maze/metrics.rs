@@ -0,0 +1,70 @@
+/// Texture metrics of a maze revealing a bias of the algorithm that generated it
+///
+/// For instance, the Binary Tree algorithm carves long straight corridors along two of the maze
+/// borders, and the Recursive Backtracking algorithm tends to produce long winding corridors with
+/// a low number of junctions.
+///
+/// A passage between two cells counts once, and openings in the outer walls of a maze are not
+/// counted.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct BiasMetrics {
+    /// The number of passages connecting cells in the same row, i.e. going East-West
+    pub horizontal_passages: usize,
+    /// The number of passages connecting cells in the same column, i.e. going North-South
+    pub vertical_passages: usize,
+    /// The number of cells with exactly two passages on opposite sides
+    pub straight_cells: usize,
+    /// The number of cells with exactly two passages on adjacent sides
+    pub turning_cells: usize,
+    /// The proportion of horizontal passages among all passages, or `0` if there are none
+    pub horizontal_ratio: f64,
+    /// The proportion of straight cells among straight and turning cells, or `0` if there are none
+    pub straight_ratio: f64,
+}
+
+impl BiasMetrics {
+    /// Returns metrics with ratios computed from given counts
+    pub fn new(
+        horizontal_passages: usize,
+        vertical_passages: usize,
+        straight_cells: usize,
+        turning_cells: usize,
+    ) -> BiasMetrics {
+        BiasMetrics {
+            horizontal_passages,
+            vertical_passages,
+            straight_cells,
+            turning_cells,
+            horizontal_ratio: ratio(horizontal_passages, vertical_passages),
+            straight_ratio: ratio(straight_cells, turning_cells),
+        }
+    }
+}
+
+fn ratio(part: usize, rest: usize) -> f64 {
+    if part + rest == 0 {
+        return 0.0;
+    }
+
+    part as f64 / (part + rest) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_metrics_ratios() {
+        let metrics = BiasMetrics::new(3, 1, 1, 4);
+
+        assert_eq!(metrics.horizontal_ratio, 0.75);
+        assert_eq!(metrics.straight_ratio, 0.2);
+    }
+
+    #[test]
+    fn new_metrics_without_passages() {
+        let metrics = BiasMetrics::new(0, 0, 0, 0);
+
+        assert_eq!(metrics, BiasMetrics::default());
+    }
+}
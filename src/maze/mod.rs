@@ -4,10 +4,19 @@
 //! mazes.
 
 mod builder;
+mod corridor;
 mod grid;
 #[allow(clippy::module_inception)]
 mod maze;
 mod errors;
+mod mask;
+mod metrics;
+mod observer;
+mod partial;
+mod post_process;
+mod room;
+mod solution;
+mod symmetry;
 mod validate;
 
 pub mod algorithms;
@@ -15,7 +24,17 @@ pub mod formatters;
 
 pub use algorithms::*;
 pub use builder::OrthogonalMazeBuilder;
-pub use formatters::{AsciiNarrow, AsciiBroad, GameMap, Image};
-pub use errors::MazeSaveError;
+pub use formatters::{
+    Ascii, AsciiNarrow, AsciiBroad, Corner, CornerStyle, GameMap, Html, Image, Obj, Svg,
+};
+pub use errors::{BuildError, FormatError, MazeError, MazeSaveError, ParseError, TransitError};
+pub use mask::Mask;
 pub use maze::OrthogonalMaze;
+pub use metrics::BiasMetrics;
+pub use observer::GenerationObserver;
+pub use post_process::PostProcess;
+pub use solution::Solution;
+pub use symmetry::Symmetry;
+pub use grid::Grid;
 pub use grid::cell::Cell;
+pub use grid::pole::{Axis, Pole};
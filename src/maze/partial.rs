@@ -0,0 +1,143 @@
+use rand::RngCore;
+
+use super::grid::{cell::Cell, pole::Pole, Grid};
+use crate::utils::{arena::join_parts, types::Coords};
+
+/// Lays the carved cells of a partial grid over a generated grid and reconnects the rest of the
+/// generated grid to them
+///
+/// Every cell with a passage or an opening in the partial grid keeps its passages and openings,
+/// while the generated passages are only kept between the other cells. The parts this leaves are
+/// joined with passages through randomly chosen walls, where the walls between two carved cells
+/// are only broken once the rest of the walls can't join the parts anymore. So a partial grid
+/// without loops along with a perfect generated maze make a perfect maze again.
+pub(crate) fn join_partial(grid: &mut Grid, partial: &Grid, rng: &mut dyn RngCore) {
+    let in_partial = |coords: Coords| !partial[coords].is_empty();
+
+    for y in 0..grid.height() {
+        for x in 0..grid.width() {
+            for direction in Pole::all().map(Cell::from) {
+                if partial.is_carved((x, y), direction)
+                    && grid.get_next_cell_coords((x, y), direction).is_err()
+                {
+                    grid.carve_opening((x, y), direction).unwrap();
+                }
+            }
+
+            for direction in [Cell::EAST, Cell::SOUTH] {
+                let Ok(next) = grid.get_next_cell_coords((x, y), direction) else {
+                    continue;
+                };
+                let keep = if in_partial((x, y)) || in_partial(next) {
+                    partial.is_carved((x, y), direction)
+                } else {
+                    grid.is_carved((x, y), direction)
+                };
+
+                if keep && !grid.is_carved((x, y), direction) {
+                    grid.carve_passage((x, y), direction).unwrap();
+                } else if !keep && grid.is_carved((x, y), direction) {
+                    grid.remove_passage((x, y), direction).unwrap();
+                }
+            }
+        }
+    }
+
+    // Walls between two carved cells are only broken when the rest can't join the parts anymore
+    let uncarved = |from: Coords, to: Coords| !in_partial(from) || !in_partial(to);
+    join_parts(grid, Some(&uncarved), rng);
+    join_parts(grid, None, rng);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze::{algorithms::Algorithm, validate::validate, Kruskal, Prim, RecursiveBacktracking};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    /// Returns the number of passages between the cells of a grid
+    fn passages(grid: &Grid) -> usize {
+        (0..grid.height())
+            .flat_map(|y| (0..grid.width()).map(move |x| (x, y)))
+            .map(|coords| {
+                [Cell::EAST, Cell::SOUTH]
+                    .into_iter()
+                    .filter(|direction| {
+                        grid.is_carved(coords, *direction)
+                            && grid.get_next_cell_coords(coords, *direction).is_ok()
+                    })
+                    .count()
+            })
+            .sum()
+    }
+
+    /// A corridor along the top row leading down the eastern column, with an entrance in the top
+    /// left corner
+    fn hand_carved() -> Grid {
+        let mut partial = Grid::new(6, 5);
+        for x in 0..5 {
+            partial.carve_passage((x, 0), Cell::EAST).unwrap();
+        }
+        for y in 0..3 {
+            partial.carve_passage((5, y), Cell::SOUTH).unwrap();
+        }
+        partial.carve_opening((0, 0), Cell::WEST).unwrap();
+        partial
+    }
+
+    #[test]
+    fn join_partial_keeps_carved_cells() {
+        let partial = hand_carved();
+        let algorithms: [Box<dyn Algorithm>; 3] = [
+            Box::new(RecursiveBacktracking),
+            Box::new(Kruskal::new()),
+            Box::new(Prim::new()),
+        ];
+
+        for (seed, mut algorithm) in algorithms.into_iter().enumerate() {
+            let mut rng = StdRng::seed_from_u64(seed as u64);
+            let mut grid = Grid::new(6, 5);
+            algorithm.generate(&mut grid, None, &mut rng);
+            join_partial(&mut grid, &partial, &mut rng);
+
+            for y in 0..5 {
+                for x in 0..6 {
+                    assert!(grid[(x, y)].contains(partial[(x, y)]), "at ({x}, {y})");
+                }
+            }
+            // Doors lead into the corridor from the generated cells only
+            let doors = (0..5)
+                .filter(|x| grid.is_carved((*x, 0), Cell::SOUTH))
+                .count()
+                + (0..3)
+                    .filter(|y| grid.is_carved((5, *y), Cell::WEST))
+                    .count();
+            assert!(doors >= 1);
+            assert!(validate(&grid));
+            assert_eq!(passages(&grid), 6 * 5 - 1);
+        }
+    }
+
+    #[test]
+    fn join_partial_breaks_carved_walls_last() {
+        // Two corridors next to each other covering the whole grid
+        let mut partial = Grid::new(3, 2);
+        for y in 0..2 {
+            for x in 0..2 {
+                partial.carve_passage((x, y), Cell::EAST).unwrap();
+            }
+        }
+        let mut grid = Grid::new(3, 2);
+        let mut rng = StdRng::seed_from_u64(1);
+        RecursiveBacktracking.generate(&mut grid, None, &mut rng);
+
+        join_partial(&mut grid, &partial, &mut rng);
+
+        assert!(validate(&grid));
+        assert_eq!(passages(&grid), 3 * 2 - 1);
+        let doors = (0..3)
+            .filter(|x| grid.is_carved((*x, 0), Cell::SOUTH))
+            .count();
+        assert_eq!(doors, 1);
+    }
+}
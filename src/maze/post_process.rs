@@ -0,0 +1,116 @@
+use super::{errors::MazeError, OrthogonalMaze};
+
+/// A post-processing step applied to a maze after it is generated by an
+/// [OrthogonalMazeBuilder](super::OrthogonalMazeBuilder)
+///
+/// Steps run after the whole generation, including a [mask](super::Mask) and a [goal
+/// room](super::OrthogonalMazeBuilder::goal_room), in the order they are added to the builder, and
+/// every step works on the maze left by the previous ones. Thus, the order matters, e.g. carving a
+/// border loop after adjusting dead ends may cull a few more of them. Randomized steps draw from
+/// the random number generator the maze was generated with, and openings are placed from the
+/// [openings seed](super::OrthogonalMazeBuilder::openings_seed), so a
+/// [seeded](super::OrthogonalMazeBuilder::seed) build applies the same steps the same way every
+/// time.
+/// Like any other post-processing, the steps are discarded when the maze is
+/// [regenerated](OrthogonalMaze::regenerate).
+///
+/// # Example
+/// ```
+/// use bevy_knossos::maze::*;
+///
+/// let build = || {
+///     OrthogonalMazeBuilder::new()
+///         .width(20)
+///         .height(20)
+///         .seed(1)
+///         .post_process(PostProcess::CullDeadEnds(0.5))
+///         .post_process(PostProcess::AddRandomEntrances(2))
+///         .post_process(PostProcess::AdjustDeadEnds(10))
+///         .build()
+///         .unwrap()
+/// };
+/// let maze = build();
+///
+/// assert_eq!(maze.dead_ends().len(), 10);
+/// assert_eq!(maze, build());
+/// ```
+pub enum PostProcess {
+    /// Culls every dead end with a given probability, see
+    /// [cull_dead_ends](OrthogonalMaze::cull_dead_ends)
+    CullDeadEnds(f64),
+    /// Culls dead ends until there are no more than a given number of them, see
+    /// [adjust_dead_ends](OrthogonalMaze::adjust_dead_ends)
+    AdjustDeadEnds(usize),
+    /// Carves a ring of a corridor along the outer walls, see
+    /// [carve_border_loop](OrthogonalMaze::carve_border_loop)
+    CarveBorderLoop,
+    /// Connects disconnected parts of the maze, see
+    /// [connect_components](OrthogonalMaze::connect_components)
+    ConnectComponents,
+    /// Carves a given number of openings through the outer walls, see
+    /// [add_random_entrances](OrthogonalMaze::add_random_entrances)
+    AddRandomEntrances(usize),
+    /// Runs a custom closure over the maze
+    Custom(Box<dyn FnOnce(&mut OrthogonalMaze) + Send>),
+}
+
+impl PostProcess {
+    /// Applies the step to a given maze, or returns a [MazeError] if the step cannot be applied
+    pub(crate) fn apply(self, maze: &mut OrthogonalMaze) -> Result<(), MazeError> {
+        match self {
+            PostProcess::CullDeadEnds(probability) => {
+                maze.cull_dead_ends(|_| probability);
+            }
+            PostProcess::AdjustDeadEnds(target) => {
+                maze.adjust_dead_ends(target);
+            }
+            PostProcess::CarveBorderLoop => {
+                maze.carve_border_loop();
+            }
+            PostProcess::ConnectComponents => {
+                maze.connect_components();
+            }
+            PostProcess::AddRandomEntrances(n) => {
+                maze.add_random_entrances(n)?;
+            }
+            PostProcess::Custom(step) => step(maze),
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze::Cell;
+
+    #[test]
+    fn apply_steps() {
+        let mut maze = OrthogonalMaze::new(4, 3);
+
+        PostProcess::ConnectComponents.apply(&mut maze).unwrap();
+        assert!(maze.is_valid());
+
+        PostProcess::Custom(Box::new(|maze| {
+            maze.get_grid_mut()
+                .carve_opening((0, 0), Cell::WEST)
+                .unwrap();
+        }))
+        .apply(&mut maze)
+        .unwrap();
+        assert!(maze[(0, 0)].contains(Cell::WEST));
+
+        PostProcess::CarveBorderLoop.apply(&mut maze).unwrap();
+        assert!(maze
+            .solve((0, 0), (3, 0))
+            .is_some_and(|solution| solution.length() == 3));
+    }
+
+    #[test]
+    fn apply_failing_step() {
+        let mut maze = OrthogonalMaze::new(2, 2);
+
+        assert!(PostProcess::AddRandomEntrances(5).apply(&mut maze).is_err());
+    }
+}
@@ -0,0 +1,169 @@
+use std::collections::VecDeque;
+
+use rand::{seq::IndexedRandom, RngCore};
+
+use super::grid::{cell::Cell, pole::Pole, Grid};
+use crate::utils::{arena::join_parts, types::Coords};
+
+/// Carves a goal room of `size`x`size` cells without inner walls at the deepest point of a grid,
+/// i.e. around the cell with the longest path from a given entrance, and returns the top left
+/// corner of the room
+///
+/// The room is centered on the deepest cell and shifted to fit into the grid. Passages crossing
+/// the room walls are removed, the rest of the grid is reconnected around the room, and a single
+/// randomly chosen passage leads into the room. The room has to be smaller than the grid in both
+/// dimensions, so that the rest of the grid stays connected.
+pub(crate) fn carve_goal_room(
+    grid: &mut Grid,
+    entrance: Coords,
+    size: usize,
+    rng: &mut dyn RngCore,
+) -> Coords {
+    let (width, height) = (grid.width(), grid.height());
+    let (x, y) = deepest_cell(grid, entrance);
+    let (left, top) = (
+        x.saturating_sub(size / 2).min(width - size),
+        y.saturating_sub(size / 2).min(height - size),
+    );
+    let inside =
+        |(x, y): Coords| (left..left + size).contains(&x) && (top..top + size).contains(&y);
+
+    let mut doors = vec![];
+    for y in 0..height {
+        for x in 0..width {
+            for direction in [Cell::EAST, Cell::SOUTH] {
+                let Ok(next) = grid.get_next_cell_coords((x, y), direction) else {
+                    continue;
+                };
+                let carved = grid.is_carved((x, y), direction);
+
+                match (inside((x, y)), inside(next)) {
+                    (true, true) if !carved => {
+                        grid.carve_passage((x, y), direction).unwrap();
+                    }
+                    (true, true) | (false, false) => {}
+                    _ => {
+                        if carved {
+                            grid.remove_passage((x, y), direction).unwrap();
+                        }
+                        doors.push(((x, y), direction));
+                    }
+                }
+            }
+        }
+    }
+
+    // Removed passages may have split the rest of the grid, which is joined again around the room
+    let outside = |from: Coords, to: Coords| !inside(from) && !inside(to);
+    join_parts(grid, Some(&outside), rng);
+
+    let (coords, direction) = doors
+        .choose(rng)
+        .expect("A room smaller than the grid always has a neighbour");
+    grid.carve_passage(*coords, *direction).unwrap();
+
+    (left, top)
+}
+
+/// Returns the reachable cell with the longest path from a given cell
+fn deepest_cell(grid: &Grid, from: Coords) -> Coords {
+    let width = grid.width();
+    let mut visited = vec![false; width * grid.height()];
+    let mut queue = VecDeque::from([from]);
+    visited[from.1 * width + from.0] = true;
+    let mut deepest = from;
+
+    // The last cell a breadth-first search visits is one of the furthest ones
+    while let Some(coords) = queue.pop_front() {
+        deepest = coords;
+
+        for direction in Pole::all().map(Cell::from) {
+            if !grid.is_carved(coords, direction) {
+                continue;
+            }
+            let Ok((nx, ny)) = grid.get_next_cell_coords(coords, direction) else {
+                continue;
+            };
+            if !visited[ny * width + nx] {
+                visited[ny * width + nx] = true;
+                queue.push_back((nx, ny));
+            }
+        }
+    }
+
+    deepest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze::{algorithms::Algorithm, validate::validate, Kruskal, RecursiveBacktracking};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn deepest_cell_of_corridor() {
+        let mut grid = Grid::new(4, 1);
+        for x in 0..3 {
+            grid.carve_passage((x, 0), Cell::EAST).unwrap();
+        }
+
+        assert_eq!(deepest_cell(&grid, (0, 0)), (3, 0));
+        assert_eq!(deepest_cell(&grid, (3, 0)), (0, 0));
+    }
+
+    #[test]
+    fn carve_goal_room_at_deepest_cell() {
+        // A corridor along the top row leading down the eastern column
+        let mut grid = Grid::new(5, 5);
+        for x in 0..4 {
+            grid.carve_passage((x, 0), Cell::EAST).unwrap();
+        }
+        for y in 0..4 {
+            grid.carve_passage((4, y), Cell::SOUTH).unwrap();
+        }
+        let mut rng = StdRng::seed_from_u64(1);
+
+        assert_eq!(carve_goal_room(&mut grid, (0, 0), 2, &mut rng), (3, 3));
+    }
+
+    #[test]
+    fn carve_goal_rooms_in_generated_grids() {
+        for (size, seed) in [(1, 0), (2, 1), (3, 2), (5, 3)] {
+            let mut grid = Grid::new(12, 8);
+            let mut rng = StdRng::seed_from_u64(seed);
+            if seed % 2 == 0 {
+                RecursiveBacktracking.generate(&mut grid, None, &mut rng);
+            } else {
+                Kruskal::new().generate(&mut grid, None, &mut rng);
+            }
+
+            let (left, top) = carve_goal_room(&mut grid, (0, 0), size, &mut rng);
+            let inside =
+                |(x, y): Coords| (left..left + size).contains(&x) && (top..top + size).contains(&y);
+
+            let (mut inner, mut doors, mut outer) = (0, 0, 0);
+            for y in 0..grid.height() {
+                for x in 0..grid.width() {
+                    for direction in [Cell::EAST, Cell::SOUTH] {
+                        let Ok(next) = grid.get_next_cell_coords((x, y), direction) else {
+                            continue;
+                        };
+                        let carved = grid.is_carved((x, y), direction);
+                        match (inside((x, y)), inside(next)) {
+                            (true, true) => assert!(carved, "A wall inside the room"),
+                            (false, false) => outer += carved as usize,
+                            _ => doors += carved as usize,
+                        }
+                        inner += (carved && inside((x, y)) && inside(next)) as usize;
+                    }
+                }
+            }
+
+            assert!(validate(&grid));
+            assert_eq!(doors, 1);
+            assert_eq!(inner, 2 * size * (size - 1));
+            // The rest of the grid stays a perfect maze
+            assert_eq!(outer, 12 * 8 - size * size - 1);
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use crate::utils::types::Coords;
+
+use super::{formatters::Formatter, AsciiBroad, OrthogonalMaze};
+
+/// A solution of a maze, i.e. a path between two cells
+///
+/// The path is ordered from the start to the goal: the first coords are the start cell, the last
+/// coords are the goal cell, and every pair of adjacent coords is connected by a carved passage.
+///
+/// With the `serde` feature enabled, a solution can be serialized for caching.
+///
+/// # Example
+/// ```
+/// use bevy_knossos::maze::*;
+///
+/// let maze = OrthogonalMazeBuilder::new().width(5).height(5).build().unwrap();
+/// let solution = maze.solve((0, 0), (4, 4)).unwrap();
+///
+/// assert_eq!(solution.start(), Some((0, 0)));
+/// assert_eq!(solution.goal(), Some((4, 4)));
+/// assert_eq!(solution.length(), solution.path.len() - 1);
+/// println!("{}", solution.render(&maze));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Solution {
+    /// Coords of the cells from the start to the goal, both inclusive
+    pub path: Vec<Coords>,
+}
+
+impl Solution {
+    /// Returns a new instance of a solution following a given path from the start to the goal
+    pub const fn new(path: Vec<Coords>) -> Solution {
+        Solution { path }
+    }
+
+    /// Returns the number of steps between the start and the goal
+    pub const fn length(&self) -> usize {
+        self.path.len().saturating_sub(1)
+    }
+
+    /// Returns coords of the start cell, or `None` if the path is empty
+    pub fn start(&self) -> Option<Coords> {
+        self.path.first().copied()
+    }
+
+    /// Returns coords of the goal cell, or `None` if the path is empty
+    pub fn goal(&self) -> Option<Coords> {
+        self.path.last().copied()
+    }
+
+    /// Returns `true` if a given cell is on the path. Otherwise, returns `false`
+    pub fn contains(&self, coords: Coords) -> bool {
+        self.path.contains(&coords)
+    }
+
+    /// Renders a given maze as [AsciiBroad] with the cells of the path marked with `*`
+    pub fn render(&self, maze: &OrthogonalMaze) -> String {
+        let ascii = AsciiBroad.format(maze.grid()).0;
+        let mut lines: Vec<Vec<u8>> = ascii.lines().map(|line| line.as_bytes().to_vec()).collect();
+
+        for &(x, y) in &self.path {
+            // Each cell is 3 characters wide, and rows of cells interleave with rows of walls
+            if let Some(ch) = lines
+                .get_mut(y * 2 + 1)
+                .and_then(|line| line.get_mut(x * 4 + 2))
+            {
+                *ch = b'*';
+            }
+        }
+
+        lines
+            .into_iter()
+            .map(|line| String::from_utf8(line).expect("ASCII output is a valid UTF-8") + "\n")
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::maze::Cell;
+
+    use super::*;
+
+    #[test]
+    fn new_solution() {
+        let solution = Solution::new(vec![(0, 0), (0, 1), (1, 1)]);
+
+        assert_eq!(solution.length(), 2);
+        assert_eq!(solution.start(), Some((0, 0)));
+        assert_eq!(solution.goal(), Some((1, 1)));
+        assert!(solution.contains((0, 1)));
+        assert!(!solution.contains((1, 0)));
+    }
+
+    #[test]
+    fn empty_solution() {
+        let solution = Solution::new(vec![]);
+
+        assert_eq!(solution.length(), 0);
+        assert_eq!(solution.start(), None);
+        assert_eq!(solution.goal(), None);
+    }
+
+    #[test]
+    fn render() {
+        let mut maze = OrthogonalMaze::new(2, 2);
+        let grid = maze.get_grid_mut();
+        grid.carve_passage((0, 0), Cell::SOUTH).unwrap();
+        grid.carve_passage((0, 1), Cell::EAST).unwrap();
+        grid.carve_passage((1, 1), Cell::NORTH).unwrap();
+
+        let mut expected = String::new();
+        expected.push_str("+---+---+\n");
+        expected.push_str("| * | * |\n");
+        expected.push_str("+   +   +\n");
+        expected.push_str("| *   * |\n");
+        expected.push_str("+---+---+\n");
+
+        let solution = maze.solve((0, 0), (1, 0)).unwrap();
+
+        assert_eq!(solution.render(&maze), expected);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_roundtrip() {
+        let solution = Solution::new(vec![(0, 0), (0, 1), (1, 1)]);
+
+        let json = serde_json::to_string(&solution).unwrap();
+        assert_eq!(json, r#"{"path":[[0,0],[0,1],[1,1]]}"#);
+        assert_eq!(serde_json::from_str::<Solution>(&json).unwrap(), solution);
+    }
+}
@@ -0,0 +1,308 @@
+use rand::prelude::*;
+
+use super::{
+    algorithms::Algorithm,
+    corridor::straighten_corridors,
+    grid::{cell::Cell, Grid},
+};
+use crate::utils::types::Coords;
+
+/// A symmetry enforced on a generated maze
+///
+/// A symmetric maze is generated by running an algorithm through a half (or a quadrant) of the
+/// grid and mirroring it onto the other half. Halves of an even dimension are disjoint, so they
+/// are stitched with a single passage across the mirror axis, and the maze stays perfect. Halves of
+/// an odd dimension share the middle line of cells instead, so there may be loops through it.
+///
+/// With the 4-fold [Quad](Symmetry::Quad) symmetry, each of the stitching passages is mirrored
+/// too, which always closes a loop around the center of a maze with even dimensions.
+///
+/// # Example
+/// ```
+/// use bevy_knossos::maze::*;
+///
+/// let maze = OrthogonalMazeBuilder::new()
+///     .width(8)
+///     .height(6)
+///     .symmetry(Symmetry::Horizontal)
+///     .build()
+///     .unwrap();
+///
+/// assert!(maze.is_valid());
+/// assert_eq!(maze[(0, 2)].contains(Cell::EAST), maze[(7, 2)].contains(Cell::WEST));
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Symmetry {
+    /// The right half mirrors the left one
+    Horizontal,
+    /// The bottom half mirrors the top one
+    Vertical,
+    /// Every quadrant mirrors its neighbours both horizontally and vertically
+    Quad,
+}
+
+impl Symmetry {
+    const fn mirrors_x(&self) -> bool {
+        matches!(self, Symmetry::Horizontal | Symmetry::Quad)
+    }
+
+    const fn mirrors_y(&self) -> bool {
+        matches!(self, Symmetry::Vertical | Symmetry::Quad)
+    }
+
+    /// Returns the size of the part of a grid an algorithm generates
+    pub(crate) const fn half_size(&self, width: usize, height: usize) -> (usize, usize) {
+        (
+            if self.mirrors_x() {
+                width.div_ceil(2)
+            } else {
+                width
+            },
+            if self.mirrors_y() {
+                height.div_ceil(2)
+            } else {
+                height
+            },
+        )
+    }
+
+    /// Returns the minimum size of a maze whose generated part is at least of a given size
+    pub(crate) const fn min_size(&self, (width, height): (usize, usize)) -> (usize, usize) {
+        (
+            if self.mirrors_x() {
+                (width * 2).saturating_sub(1)
+            } else {
+                width
+            },
+            if self.mirrors_y() {
+                (height * 2).saturating_sub(1)
+            } else {
+                height
+            },
+        )
+    }
+
+    /// Generates a symmetric maze in a given grid by mirroring the part generated by a given
+    /// algorithm
+    ///
+    /// Start coords out of the generated part are mirrored into it, and corridors are straightened
+    /// in the generated part only, so the maze stays symmetric.
+    pub(crate) fn generate(
+        &self,
+        algorithm: &mut dyn Algorithm,
+        grid: &mut Grid,
+        start_coords: Option<Coords>,
+        min_corridor: usize,
+        rng: &mut dyn RngCore,
+    ) {
+        let (width, height) = (grid.width(), grid.height());
+        let (half_width, half_height) = self.half_size(width, height);
+
+        let mut half = Grid::new(half_width, half_height);
+        let start_coords =
+            start_coords.map(|(x, y)| (self.fold_x(x, width), self.fold_y(y, height)));
+        algorithm.generate(&mut half, start_coords, rng);
+        straighten_corridors(&mut half, min_corridor);
+
+        for y in 0..height {
+            for x in 0..width {
+                let east = x + 1 < width
+                    && self
+                        .edge_x(x, width)
+                        .is_some_and(|sx| half.is_carved((sx, self.fold_y(y, height)), Cell::EAST));
+                let south = y + 1 < height
+                    && self
+                        .edge_y(y, height)
+                        .is_some_and(|sy| half.is_carved((self.fold_x(x, width), sy), Cell::SOUTH));
+
+                if east {
+                    grid.carve_passage((x, y), Cell::EAST).unwrap();
+                }
+                if south {
+                    grid.carve_passage((x, y), Cell::SOUTH).unwrap();
+                }
+            }
+        }
+
+        // Disjoint halves of an even dimension are stitched across the mirror axis, and the
+        // stitching passage is mirrored along the other axis as well
+        if self.mirrors_x() && width.is_multiple_of(2) {
+            let y = rng.random_range(0..half_height);
+            self.stitch(grid, (half_width - 1, y), Cell::EAST);
+        }
+        if self.mirrors_y() && height.is_multiple_of(2) {
+            let x = rng.random_range(0..half_width);
+            self.stitch(grid, (x, half_height - 1), Cell::SOUTH);
+        }
+    }
+
+    fn stitch(&self, grid: &mut Grid, (x, y): Coords, direction: Cell) {
+        let (width, height) = (grid.width(), grid.height());
+        let mut passages = vec![(x, y)];
+        if direction == Cell::EAST && self.mirrors_y() {
+            passages.push((x, height - 1 - y));
+        }
+        if direction == Cell::SOUTH && self.mirrors_x() {
+            passages.push((width - 1 - x, y));
+        }
+        passages.dedup();
+
+        for coords in passages {
+            grid.carve_passage(coords, direction).unwrap();
+        }
+    }
+
+    /// Returns a column of the generated part mirroring a given column
+    const fn fold_x(&self, x: usize, width: usize) -> usize {
+        if self.mirrors_x() {
+            fold(x, width)
+        } else {
+            x
+        }
+    }
+
+    /// Returns a row of the generated part mirroring a given row
+    const fn fold_y(&self, y: usize, height: usize) -> usize {
+        if self.mirrors_y() {
+            fold(y, height)
+        } else {
+            y
+        }
+    }
+
+    /// Returns a column of the generated part whose eastern passage mirrors the eastern passage of
+    /// a given column, or `None` if the passage crosses the mirror axis between disjoint halves
+    const fn edge_x(&self, x: usize, width: usize) -> Option<usize> {
+        if self.mirrors_x() {
+            edge(x, width)
+        } else {
+            Some(x)
+        }
+    }
+
+    /// Returns a row of the generated part whose southern passage mirrors the southern passage of a
+    /// given row, or `None` if the passage crosses the mirror axis between disjoint halves
+    const fn edge_y(&self, y: usize, height: usize) -> Option<usize> {
+        if self.mirrors_y() {
+            edge(y, height)
+        } else {
+            Some(y)
+        }
+    }
+}
+
+const fn fold(line: usize, lines: usize) -> usize {
+    if line < lines.div_ceil(2) {
+        line
+    } else {
+        lines - 1 - line
+    }
+}
+
+/// Returns the first of two lines of the generated part a passage between a given line and the
+/// next one mirrors
+const fn edge(line: usize, lines: usize) -> Option<usize> {
+    let half = lines.div_ceil(2);
+    let mirrored = if line + 1 < half {
+        line
+    } else {
+        lines - 2 - line
+    };
+
+    if mirrored + 1 < half {
+        Some(mirrored)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze::{validate::validate, Kruskal, RecursiveBacktracking};
+
+    #[test]
+    fn folds() {
+        assert_eq!(
+            (0..6).map(|x| fold(x, 6)).collect::<Vec<_>>(),
+            [0, 1, 2, 2, 1, 0]
+        );
+        assert_eq!(
+            (0..5).map(|x| fold(x, 5)).collect::<Vec<_>>(),
+            [0, 1, 2, 1, 0]
+        );
+        assert_eq!(
+            (0..5).map(|x| edge(x, 6)).collect::<Vec<_>>(),
+            [Some(0), Some(1), None, Some(1), Some(0)]
+        );
+        assert_eq!(
+            (0..4).map(|x| edge(x, 5)).collect::<Vec<_>>(),
+            [Some(0), Some(1), Some(1), Some(0)]
+        );
+    }
+
+    #[test]
+    fn sizes() {
+        assert_eq!(Symmetry::Horizontal.half_size(7, 4), (4, 4));
+        assert_eq!(Symmetry::Vertical.half_size(7, 4), (7, 2));
+        assert_eq!(Symmetry::Quad.half_size(7, 4), (4, 2));
+        assert_eq!(Symmetry::Quad.min_size((2, 1)), (3, 1));
+        assert_eq!(Symmetry::Horizontal.min_size((1, 1)), (1, 1));
+    }
+
+    #[test]
+    fn generate_symmetric_grids() {
+        for symmetry in [Symmetry::Horizontal, Symmetry::Vertical, Symmetry::Quad] {
+            for (width, height) in [(8, 6), (7, 5), (8, 5), (1, 1), (2, 2)] {
+                let mut grid = Grid::new(width, height);
+                let mut rng = StdRng::seed_from_u64(width as u64);
+                symmetry.generate(&mut Kruskal::new(), &mut grid, None, 0, &mut rng);
+
+                assert!(validate(&grid), "{:?} {}x{}", symmetry, width, height);
+                assert!(is_symmetric(&grid, symmetry));
+            }
+        }
+    }
+
+    #[test]
+    fn even_halves_stay_perfect() {
+        for symmetry in [Symmetry::Horizontal, Symmetry::Vertical] {
+            let mut grid = Grid::new(10, 8);
+            let mut rng = StdRng::seed_from_u64(3);
+            symmetry.generate(
+                &mut RecursiveBacktracking,
+                &mut grid,
+                Some((9, 7)),
+                3,
+                &mut rng,
+            );
+
+            let passages: usize = (0..8)
+                .flat_map(|y| (0..10).map(move |x| (x, y)))
+                .map(|coords| grid[coords].passage_count())
+                .sum();
+            assert_eq!(passages / 2, 10 * 8 - 1);
+            assert!(is_symmetric(&grid, symmetry));
+        }
+    }
+
+    fn is_symmetric(grid: &Grid, symmetry: Symmetry) -> bool {
+        let (width, height) = (grid.width(), grid.height());
+        let mirror = |cell: Cell, a: Cell, b: Cell| {
+            let mut mirrored = cell - a - b;
+            mirrored.set(b, cell.contains(a));
+            mirrored.set(a, cell.contains(b));
+            mirrored
+        };
+
+        (0..height).all(|y| {
+            (0..width).all(|x| {
+                let cell = grid[(x, y)];
+                (!symmetry.mirrors_x()
+                    || grid[(width - 1 - x, y)] == mirror(cell, Cell::EAST, Cell::WEST))
+                    && (!symmetry.mirrors_y()
+                        || grid[(x, height - 1 - y)] == mirror(cell, Cell::NORTH, Cell::SOUTH))
+            })
+        })
+    }
+}
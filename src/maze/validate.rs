@@ -1,6 +1,6 @@
 use crate::utils::types::Coords;
-use rand::prelude::*;
-use super::grid::{Grid, cell::Cell};
+use super::grid::{Grid, cell::Cell, pole::Pole};
+use super::mask::Mask;
 
 /// A utility to validate if a given grid is valid, i.e. all the cells are reachable.
 ///
@@ -14,11 +14,23 @@ pub fn validate(grid: &Grid) -> bool {
     visited.len() == grid.width() * grid.height()
 }
 
-fn visit(coords: Coords, grid: &Grid, visited: &mut Vec<Coords>) {
-    let mut dirs = [Cell::NORTH, Cell::SOUTH, Cell::WEST, Cell::EAST];
-    dirs.shuffle(&mut rand::rng());
+/// A utility to validate if a given grid generated around the solid areas of a given mask is
+/// valid, i.e. all the cells out of the solid areas are reachable.
+pub fn validate_masked(grid: &Grid, mask: &Mask) -> bool {
+    let mut open = (0..grid.height())
+        .flat_map(|y| (0..grid.width()).map(move |x| (x, y)))
+        .filter(|coords| !mask.is_solid(*coords));
+    let Some(start) = open.next() else {
+        return false;
+    };
+
+    let mut visited: Vec<Coords> = vec![start];
+    visit(start, grid, &mut visited);
+    open.all(|coords| visited.contains(&coords))
+}
 
-    for dir in dirs {
+fn visit(coords: Coords, grid: &Grid, visited: &mut Vec<Coords>) {
+    for dir in Pole::shuffled(&mut rand::rng()).map(Cell::from) {
         let next = match grid.get_next_cell_coords(coords, dir) {
             Ok(next) => next,
             Err(_) => continue,
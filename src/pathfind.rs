@@ -1,6 +1,7 @@
 use bevy::prelude::*;
 use pathfinding::prelude::astar;
 use crate::{
+    maze::Solution,
     utils::types::{Goal, Start},
     Cell, CoordsComponent,
 };
@@ -29,11 +30,19 @@ pub enum Algorithm {
 /// Auxiliary struct that holds knowledge for path finding on each [`Cell`]
 #[derive(Debug, Clone, PartialEq, Eq, Resource, Reflect, Default)]
 pub struct MazePath {
-    /// Path from
+    /// Path from [`Start`] to [`Goal`], both inclusive, and its total cost
     pub path: Option<(Vec<CoordsComponent>, u32)>,
 }
 
 impl MazePath {
+    /// Returns the found path as a [`Solution`] ordered from [`Start`] to [`Goal`], or `None` if
+    /// no path was found
+    pub fn solution(&self) -> Option<Solution> {
+        self.path
+            .as_ref()
+            .map(|(path, _cost)| Solution::new(path.iter().map(|coords| coords.coord).collect()))
+    }
+
     /// Heuristic distance function to maze [`Goal`]
     pub const fn distance(coords: &CoordsComponent, other: &CoordsComponent) -> u32 {
         (coords.coord.0.abs_diff(other.coord.0) + coords.coord.1.abs_diff(other.coord.1)) as u32
@@ -50,12 +59,13 @@ impl MazePath {
 
         open_passages
             .iter()
-            .map(|c| match c {
-                Cell::EAST => (current.coord.0 + 1, current.coord.1),
-                Cell::NORTH => (current.coord.0, current.coord.1 + 1),
-                Cell::SOUTH => (current.coord.0, current.coord.1 - 1),
-                Cell::WEST => (current.coord.0 - 1, current.coord.1),
-                _ => current.coord,
+            // Openings in the outer walls lead nowhere, so they have no successors
+            .filter_map(|c| match c {
+                Cell::EAST => Some((current.coord.0 + 1, current.coord.1)),
+                Cell::NORTH => Some((current.coord.0, current.coord.1 + 1)),
+                Cell::SOUTH => Some((current.coord.0, current.coord.1.checked_sub(1)?)),
+                Cell::WEST => Some((current.coord.0.checked_sub(1)?, current.coord.1)),
+                _ => Some(current.coord),
             })
             .map(|coord| (coord.into(), cost.cloned().unwrap_or_default().0))
             .collect()
@@ -158,4 +168,30 @@ mod tests {
 
         assert_eq!(successor.len(), 0);
     }
+
+    #[test]
+    fn successors_through_openings() {
+        let start: CoordsComponent = (0, 0).into();
+        let cell = Cell::from_bits(0b1011).unwrap();
+        let key = CoordsComponent::new(0, 0);
+        let cells = [(&key, (&cell, None::<&Cost>))].into_iter().collect();
+
+        let successor = MazePath::successors(&start, &cells);
+
+        assert_eq!(successor.len(), 1);
+        assert_eq!(successor[0], ((0, 1).into(), 1));
+    }
+
+    #[test]
+    fn solution() {
+        let path = MazePath {
+            path: Some((vec![(0, 0).into(), (0, 1).into(), (1, 1).into()], 2)),
+        };
+
+        let solution = path.solution().unwrap();
+
+        assert_eq!(solution.path, vec![(0, 0), (0, 1), (1, 1)]);
+        assert_eq!(solution.length(), 2);
+        assert_eq!(MazePath::default().solution(), None);
+    }
 }
@@ -1,3 +1,8 @@
+use rand::RngCore;
+
+use super::{rand::shuffle, types::Coords};
+use crate::maze::{Cell, Grid};
+
 pub struct ArenaTree {
     nodes: Vec<Node>,
 }
@@ -62,9 +67,62 @@ impl ArenaTree {
 
 impl Node {}
 
+/// Joins the disconnected parts of a grid by carving passages through randomly chosen walls, and
+/// returns the number of carved passages
+///
+/// Parts are found with a union-find over the carved passages, and a wall is only broken when the
+/// cells on its sides belong to different parts, so every two parts are joined by a single
+/// passage. A wall between two cells rejected by a given filter is never broken, e.g. a wall of a
+/// solid cell.
+pub(crate) fn join_parts(
+    grid: &mut Grid,
+    filter: Option<&dyn Fn(Coords, Coords) -> bool>,
+    rng: &mut dyn RngCore,
+) -> usize {
+    let width = grid.width();
+    let node = |(x, y): Coords| NodeId(y * width + x);
+    let mut arena = ArenaTree::new();
+    for _ in 0..width * grid.height() {
+        arena.new_node();
+    }
+
+    let mut walls = vec![];
+    for y in 0..grid.height() {
+        for x in 0..width {
+            for direction in [Cell::EAST, Cell::SOUTH] {
+                let Ok(next) = grid.get_next_cell_coords((x, y), direction) else {
+                    continue;
+                };
+
+                if grid.is_carved((x, y), direction) {
+                    if !arena.connected(node((x, y)), node(next)) {
+                        arena.connect(node((x, y)), node(next));
+                    }
+                } else if filter.is_none_or(|filter| filter((x, y), next)) {
+                    walls.push(((x, y), direction));
+                }
+            }
+        }
+    }
+
+    shuffle(&mut walls, rng);
+    let mut carved = 0;
+    for (coords, direction) in walls {
+        let next = grid.get_next_cell_coords(coords, direction).unwrap();
+        if !arena.connected(node(coords), node(next)) {
+            arena.connect(node(coords), node(next));
+            grid.carve_passage(coords, direction).unwrap();
+            carved += 1;
+        }
+    }
+
+    carved
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rand::SeedableRng;
 
     #[test]
     fn unconnected_nodes() {
@@ -121,4 +179,26 @@ mod tests {
         let mut arena = ArenaTree::new();
         arena.connect(NodeId(1), NodeId(2));
     }
+
+    #[test]
+    fn join_parts_of_grid() {
+        let mut grid = Grid::new(4, 3);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(1);
+
+        assert_eq!(join_parts(&mut grid, None, &mut rng), 11);
+        assert_eq!(join_parts(&mut grid, None, &mut rng), 0);
+    }
+
+    #[test]
+    fn join_parts_skips_filtered_walls() {
+        let mut grid = Grid::new(4, 3);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(1);
+        let left = |from: Coords, to: Coords| from.0 < 2 && to.0 < 2;
+
+        assert_eq!(join_parts(&mut grid, Some(&left), &mut rng), 5);
+        for y in 0..3 {
+            assert!(!grid.is_carved((1, y), Cell::EAST));
+            assert!(grid[(2, y)].is_empty() && grid[(3, y)].is_empty());
+        }
+    }
 }
@@ -13,6 +13,15 @@ pub enum Color {
     RGB(u8, u8, u8),
 }
 
+impl Color {
+    /// Returns a color linearly interpolated between this color at `0` and another one at `1`
+    pub(crate) fn lerp(&self, other: Color, t: f64) -> Color {
+        let (Color::RGB(r0, g0, b0), Color::RGB(r1, g1, b1)) = (*self, other);
+        let mix = |from: u8, to: u8| (from as f64 + (to as f64 - from as f64) * t).round() as u8;
+        Color::RGB(mix(r0, r1), mix(g0, g1), mix(b0, b1))
+    }
+}
+
 impl std::fmt::Display for Color {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -29,4 +38,13 @@ mod tests {
     fn display_color() {
         assert_eq!(Color::RGB(0, 10, 20).to_string(), format!("rgb(0, 10, 20)"));
     }
+
+    #[test]
+    fn lerp_color() {
+        let (from, to) = (Color::RGB(0, 100, 255), Color::RGB(255, 100, 0));
+
+        assert_eq!(from.lerp(to, 0.0), from);
+        assert_eq!(from.lerp(to, 1.0), to);
+        assert_eq!(from.lerp(to, 0.5), Color::RGB(128, 100, 128));
+    }
 }
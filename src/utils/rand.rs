@@ -1,20 +1,29 @@
+use rand::{seq::SliceRandom, Rng};
+
 use super::types::Coords;
 
+/// Shuffles given items in place with a given random number generator
+///
+/// Algorithms shuffle through this helper with the generator threaded through
+/// [Algorithm::generate](crate::maze::Algorithm::generate), so that a seeded generator reproduces a
+/// maze regardless of the algorithm.
+pub(crate) fn shuffle<T, R: Rng + ?Sized>(items: &mut [T], rng: &mut R) {
+    items.shuffle(rng);
+}
+
 pub struct RandPositions;
 
 impl RandPositions {
     #[cfg(not(test))]
     #[cfg(not(tarpaulin_include))]
     pub fn rand(positions: &mut Vec<Coords>) -> &mut Vec<Coords> {
-        use rand::seq::SliceRandom;
-        let mut rng = rand::rng();
-        positions.shuffle(&mut rng);
+        shuffle(positions, &mut rand::rng());
         positions
     }
 
     #[cfg(test)]
     #[cfg(not(tarpaulin_include))]
-    pub fn rand(positions: &mut Vec<Coords>) -> &mut Vec<Coords> {
+    pub const fn rand(positions: &mut Vec<Coords>) -> &mut Vec<Coords> {
         positions
     }
 }
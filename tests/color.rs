@@ -1,3 +1,5 @@
+#![cfg(feature = "cli")]
+
 use assert_cmd::Command;
 
 struct TestCli {
@@ -1,8 +1,10 @@
+#![cfg(feature = "cli")]
+
 use assert_cmd::Command;
 
 const CLI_HELP_STR: &str = "Rust library for generating and rendering mazes
 
-Usage: bevy_knossos <COMMAND>
+Usage: knossos <COMMAND>
 
 Commands:
   generate  Generates a maze
@@ -15,7 +17,7 @@ Options:
 
 const GENERATE_SHORT_HELP_STR: &str = "Generates a maze
 
-Usage: bevy_knossos generate [OPTIONS] <COMMAND>
+Usage: knossos generate [OPTIONS] <COMMAND>
 
 Commands:
   ascii     Save to a text file with an ASCII representation of a maze
@@ -25,13 +27,15 @@ Commands:
 
 Options:
   -A, --algorithm <ALGORITHM>
-          Maze generation algorithm [default: recursive-backtracking] [possible values: aldous-broder, binary-tree, eller, growing-tree, hunt-and-kill, kruskal, prim, recursive-backtracking, recursive-division, sidewinder]
+          Maze generation algorithm [default: recursive-backtracking] [possible values: aldous-broder, binary-tree, eller, growing-tree, hunt-and-kill, kruskal, prim, recursive-backtracking, recursive-division, river, sidewinder]
   -H, --height <HEIGHT>
           Grid height in a number of cells [default: 10]
   -W, --width <WIDTH>
           Grid width in a number of cells [default: 10]
   -C, --start-coords <START_COORDS>
           Start coordinate for maze algorithm
+  -S, --seed <SEED>
+          Seed for the random number generator, so that the same options generate the same maze
       --bias[=<BIAS>]
           Bias to use for the \"Binary Tree\" algorithm [default: north-east] [possible values: north-west, north-east, south-west, south-east]
       --growing-method[=<GROWING_METHOD>]
@@ -42,7 +46,7 @@ Options:
 
 const GENERATE_LONG_HELP_STR: &str = "Generates a maze
 
-Usage: bevy_knossos generate [OPTIONS] <COMMAND>
+Usage: knossos generate [OPTIONS] <COMMAND>
 
 Commands:
   ascii     Save to a text file with an ASCII representation of a maze
@@ -55,7 +59,7 @@ Options:
           Maze generation algorithm
           
           [default: recursive-backtracking]
-          [possible values: aldous-broder, binary-tree, eller, growing-tree, hunt-and-kill, kruskal, prim, recursive-backtracking, recursive-division, sidewinder]
+          [possible values: aldous-broder, binary-tree, eller, growing-tree, hunt-and-kill, kruskal, prim, recursive-backtracking, recursive-division, river, sidewinder]
 
   -H, --height <HEIGHT>
           Grid height in a number of cells
@@ -70,6 +74,9 @@ Options:
   -C, --start-coords <START_COORDS>
           Start coordinate for maze algorithm
 
+  -S, --seed <SEED>
+          Seed for the random number generator, so that the same options generate the same maze
+
       --bias[=<BIAS>]
           Bias to use for the \"Binary Tree\" algorithm
           
@@ -101,10 +108,10 @@ Options:
 
 const GENERATE_IMAGE_HELP_STR: &str = "Save to PNG or JPG file
 
-Usage: bevy_knossos generate image [OPTIONS] --output-path <OUTPUT_PATH>
+Usage: knossos generate image [OPTIONS] --output-path <OUTPUT_PATH>
 
 Options:
-  -O, --output-path <OUTPUT_PATH>      Output path
+  -O, --output-path <OUTPUT_PATH>      Output path. Defaults to the `.png` extension
       --wall-size <WALL_SIZE>          Wall size in pixels [default: 40]
       --passage-size <PASSAGE_SIZE>    Passage size in pixels [default: 40]
       --margin <MARGIN>                Size of the margin area that implies an empty space between an image borders and grid [default: 50]
@@ -115,20 +122,20 @@ Options:
 
 const GENERATE_ASCII_HELP_STR: &str = "Save to a text file with an ASCII representation of a maze
 
-Usage: bevy_knossos generate ascii [OPTIONS] --output-path <OUTPUT_PATH>
+Usage: knossos generate ascii [OPTIONS]
 
 Options:
-  -O, --output-path <OUTPUT_PATH>    Output path
+  -O, --output-path <OUTPUT_PATH>    Output path, printing to stdout if missing. Defaults to the `.txt` extension
   -T, --output-type[=<OUTPUT_TYPE>]  Output type [default: narrow] [possible values: narrow, broad]
   -h, --help                         Print help
 ";
 
 const GENERATE_GAME_MAP_HELP_STR: &str = "Save to a text file as an ASCII game map for pseudo 3D games that use ray casting for modeling and rendering the map
 
-Usage: bevy_knossos generate game-map [OPTIONS] --output-path <OUTPUT_PATH>
+Usage: knossos generate game-map [OPTIONS]
 
 Options:
-  -O, --output-path <OUTPUT_PATH>  Output path
+  -O, --output-path <OUTPUT_PATH>  Output path, printing to stdout if missing. Defaults to the `.txt` extension
       --span <SPAN>                Distance between any two walls [default: 3]
       --passage <PASSAGE>          ASCII character for a passage [default: .]
       --wall <WALL>                ASCII character for a wall [default: #]
@@ -138,13 +145,13 @@ Options:
 
 #[test]
 fn cli_help() {
-    let mut cmd = Command::cargo_bin(assert_cmd::crate_name!()).unwrap();
+    let mut cmd = Command::cargo_bin("knossos").unwrap();
     cmd.arg("--help").assert().success().stdout(CLI_HELP_STR);
 }
 
 #[test]
 fn command_generate_short_help() {
-    let mut cmd = Command::cargo_bin(assert_cmd::crate_name!()).unwrap();
+    let mut cmd = Command::cargo_bin("knossos").unwrap();
     cmd.arg("generate")
         .arg("-h")
         .assert()
@@ -154,7 +161,7 @@ fn command_generate_short_help() {
 
 #[test]
 fn command_generate_long_help() {
-    let mut cmd = Command::cargo_bin(assert_cmd::crate_name!()).unwrap();
+    let mut cmd = Command::cargo_bin("knossos").unwrap();
     cmd.arg("generate")
         .arg("--help")
         .assert()
@@ -164,7 +171,7 @@ fn command_generate_long_help() {
 
 #[test]
 fn command_generate_image_help() {
-    let mut cmd = Command::cargo_bin(assert_cmd::crate_name!()).unwrap();
+    let mut cmd = Command::cargo_bin("knossos").unwrap();
     cmd.arg("generate")
         .arg("image")
         .arg("--help")
@@ -175,7 +182,7 @@ fn command_generate_image_help() {
 
 #[test]
 fn command_generate_ascii_help() {
-    let mut cmd = Command::cargo_bin(assert_cmd::crate_name!()).unwrap();
+    let mut cmd = Command::cargo_bin("knossos").unwrap();
     cmd.arg("generate")
         .arg("ascii")
         .arg("--help")
@@ -186,7 +193,7 @@ fn command_generate_ascii_help() {
 
 #[test]
 fn command_generate_game_map_help() {
-    let mut cmd = Command::cargo_bin(assert_cmd::crate_name!()).unwrap();
+    let mut cmd = Command::cargo_bin("knossos").unwrap();
     cmd.arg("generate")
         .arg("game-map")
         .arg("--help")
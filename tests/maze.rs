@@ -24,7 +24,7 @@ fn build_valid_maze_with_custom_params() {
     let maze = OrthogonalMazeBuilder::new()
         .height(10)
         .width(20)
-        .algorithm(Box::new(Kruskal))
+        .algorithm(Box::new(Kruskal::new()))
         .build()
         .unwrap();
 
@@ -46,7 +46,16 @@ fn build_valid_maze_with_custom_params_and_start_position() {
 
 #[test]
 fn build_valid_maze_with_aldou_broder_algorithm() {
-    assert!(maze!(AldousBroder).unwrap().is_valid());
+    assert!(maze!(AldousBroder::new()).unwrap().is_valid());
+}
+
+#[test]
+fn build_valid_maze_with_aldous_broder_algorithm_and_momentum() {
+    for momentum in [0.0, 0.25, 0.5, 0.9, 1.0, 2.0, -1.0, f64::NAN] {
+        assert!(maze!(AldousBroder::new().momentum(momentum))
+            .unwrap()
+            .is_valid());
+    }
 }
 
 #[test]
@@ -79,6 +88,52 @@ fn build_valid_maze_with_growing_tree_algorithm() {
         .is_valid());
 }
 
+#[test]
+fn growing_tree_spiral_bias_turns_clockwise() {
+    // Turns of every cell entered from its parent towards its children, clockwise or not
+    let turns = |bias: f64, method: Method| {
+        let maze = OrthogonalMazeBuilder::new()
+            .width(20)
+            .height(20)
+            .start_coords((0, 0))
+            .algorithm(Box::new(GrowingTree::new(method).spiral_bias(bias)))
+            .seed(4)
+            .build()
+            .unwrap();
+        let tree = maze.path_tree((0, 0));
+        let heading = |(x, y): (usize, usize)| tree[y][x].map(|parent| parent.opposite());
+
+        // A perfect maze is connected and has one passage less than cells
+        let passages: usize = maze.iter().map(|(_, cell)| cell.passage_count()).sum();
+        assert!(maze.is_valid());
+        assert_eq!(passages, 2 * (20 * 20 - 1));
+
+        let (mut clockwise, mut counter_clockwise) = (0, 0);
+        for (coords, _) in maze.iter() {
+            let Some(pole) = heading(coords) else {
+                continue;
+            };
+            let parent = maze.move_from(coords, pole.opposite()).unwrap();
+            let Some(parent_pole) = heading(parent) else {
+                continue;
+            };
+            let (from, to) = (Cell::from(parent_pole), Cell::from(pole));
+            if from.rotated_cw() == to {
+                clockwise += 1;
+            } else if to.rotated_cw() == from {
+                counter_clockwise += 1;
+            }
+        }
+        (clockwise, counter_clockwise)
+    };
+
+    let (clockwise, counter_clockwise) = turns(0.0, Method::Newest);
+    assert!(clockwise > 50 && counter_clockwise > 50);
+    let (clockwise, counter_clockwise) = turns(1.0, Method::Newest);
+    assert!(clockwise > 20 && counter_clockwise <= clockwise / 10);
+    turns(0.5, Method::Random);
+}
+
 #[test]
 fn build_valid_maze_with_hunt_and_kill_algorithm() {
     assert!(maze!(HuntAndKill::new()).unwrap().is_valid());
@@ -86,7 +141,37 @@ fn build_valid_maze_with_hunt_and_kill_algorithm() {
 
 #[test]
 fn build_valid_maze_with_kruskal_algorithm() {
-    assert!(maze!(Kruskal).unwrap().is_valid());
+    assert!(maze!(Kruskal::new()).unwrap().is_valid());
+}
+
+#[test]
+fn build_valid_maze_with_kruskal_algorithm_and_room_chance() {
+    for room_chance in [0.0, 0.1, 0.5, 1.0, 2.0, -1.0, f64::NAN] {
+        assert!(maze!(Kruskal::new().room_chance(room_chance))
+            .unwrap()
+            .is_valid());
+    }
+}
+
+#[test]
+fn kruskal_room_chance_carves_loops() {
+    let passages = |room_chance| {
+        let maze = OrthogonalMazeBuilder::new()
+            .width(10)
+            .height(10)
+            .algorithm(Box::new(Kruskal::new().room_chance(room_chance)))
+            .build()
+            .unwrap();
+        maze.into_iter()
+            .map(|(_, cell)| cell.passage_count())
+            .sum::<usize>()
+            / 2
+    };
+
+    // A perfect maze has one passage less than cells, and carving every edge opens all walls
+    assert_eq!(passages(0.0), 10 * 10 - 1);
+    assert!(passages(0.5) > 10 * 10 - 1);
+    assert_eq!(passages(1.0), 2 * 10 * 9);
 }
 
 #[test]
@@ -99,11 +184,124 @@ fn build_valid_maze_with_recursive_backtracking_algorithm() {
     assert!(maze!(RecursiveBacktracking).unwrap().is_valid());
 }
 
+#[test]
+fn recursive_backtracking_from_order_ignores_seed() {
+    let build = |seed| {
+        OrthogonalMazeBuilder::new()
+            .width(3)
+            .height(2)
+            .algorithm(Box::new(
+                RecursiveBacktracking::from_order(&[[Pole::E, Pole::S, Pole::W, Pole::N]]).unwrap(),
+            ))
+            .seed(seed)
+            .build()
+            .unwrap()
+    };
+
+    // East as far as possible, then south and back west
+    let expected = " _____ \n|___  |\n|_____|\n";
+    assert_eq!(build(1).format(AsciiNarrow).0, expected);
+    assert_eq!(build(2).format(AsciiNarrow).0, expected);
+}
+
 #[test]
 fn build_valid_maze_with_recursive_division_algorithm() {
     assert!(maze!(RecursiveDivision).unwrap().is_valid());
 }
 
+#[test]
+fn build_valid_maze_with_river_algorithm() {
+    assert!(maze!(River::new()).unwrap().is_valid());
+    assert!(maze!(River::new().exit((40, 3))).unwrap().is_valid());
+}
+
+#[test]
+fn river_algorithm_lays_a_long_main_route() {
+    let build = |algorithm: Box<dyn Algorithm>| {
+        OrthogonalMazeBuilder::new()
+            .width(20)
+            .height(20)
+            .start_coords((0, 19))
+            .algorithm(algorithm)
+            .seed(5)
+            .build()
+            .unwrap()
+    };
+
+    let river = build(Box::new(River::new()));
+    let prim = build(Box::new(Prim::new()));
+    let route = |maze: &OrthogonalMaze| maze.solve((0, 19), (19, 0)).unwrap().length();
+
+    // The river leads to the opposite corner through a large part of the maze, and the branches
+    // off it are short
+    assert!(route(&river) > 2 * route(&prim));
+    assert!(route(&river) > 20 * 20 / 4);
+    assert!(river.dead_ends().len() > 20 * 20 / 8);
+}
+
+#[test]
+fn build_valid_maze_with_blended_algorithms() {
+    let blend = || {
+        Blend::new(Box::new(Prim::new()))
+            .region((0, 0), (10, 5), Box::new(BinaryTree::new(Bias::NorthEast)))
+            .region((4, 3), (3, 30), Box::new(Sidewinder))
+            .region((50, 50), (5, 5), Box::new(Eller))
+    };
+
+    assert!(maze!(blend()).unwrap().is_valid());
+    assert!(maze!(Blend::new(Box::new(Kruskal::new())))
+        .unwrap()
+        .is_valid());
+
+    // The top row of the binary tree region is a single corridor, and the whole maze stays perfect
+    let maze = OrthogonalMazeBuilder::new()
+        .width(12)
+        .height(12)
+        .algorithm(Box::new(blend()))
+        .seed(3)
+        .build()
+        .unwrap();
+    assert!((0..9).all(|x| maze[(x, 0)].contains(Cell::EAST)));
+    assert!(maze.has_unique_solution((0, 0), (11, 11)));
+}
+
+#[test]
+fn build_valid_torus_mazes() {
+    let algorithms: [Box<dyn Algorithm>; 7] = [
+        Box::new(AldousBroder::new()),
+        Box::new(HuntAndKill::new()),
+        Box::new(Kruskal::new()),
+        Box::new(Prim::new()),
+        Box::new(RecursiveBacktracking),
+        Box::new(River::new()),
+        Box::new(Sidewinder),
+    ];
+
+    for algorithm in algorithms {
+        let name = algorithm.name();
+        let maze = OrthogonalMazeBuilder::new()
+            .width(9)
+            .height(7)
+            .algorithm(algorithm)
+            .torus(true)
+            .seed(2)
+            .build()
+            .unwrap();
+
+        // A perfect maze has one passage less than cells, counting the ones across the edges
+        let passages: usize = maze.iter().map(|(_, cell)| cell.passage_count()).sum();
+        assert!(maze.is_valid(), "{name}");
+        assert_eq!(passages, 2 * (9 * 7 - 1), "{name}");
+        assert!(maze.has_unique_solution((0, 0), (8, 6)), "{name}");
+
+        // Mazes are rendered flat
+        let image = maze.format(Image::new()).0;
+        let flat = OrthogonalMaze::new(9, 7).format(Image::new()).0;
+        assert_eq!(image.dimensions(), flat.dimensions(), "{name}");
+        assert!(maze.fractalize(3).unwrap().is_valid(), "{name}");
+    }
+}
+
 #[test]
 fn build_valid_maze_with_sidewinder_algorithm() {
     assert!(maze!(Sidewinder).unwrap().is_valid());
@@ -210,3 +408,224 @@ fn save_maze_as_png_returns_error() {
     let expected = "The image format could not be determined".to_string();
     assert_save_maze_error!("this is not valid path/", Image::new(), expected);
 }
+
+#[test]
+fn save_maze_as_png_over_max_pixels_returns_error() {
+    let output_dir = TempDir::new().unwrap();
+    let file_path = format!("{}/maze.png", output_dir.path().display());
+    let expected = "A 10x10 maze exceeds the maximum of 100 pixels".to_string();
+
+    assert_save_maze_error!(&file_path, Image::new().max_pixels(100), expected);
+    assert!(!std::path::Path::new(&file_path).exists());
+}
+
+#[test]
+fn save_maze_as_svg() {
+    let output_dir = TempDir::new().unwrap();
+    let file_path = format!("{}/maze.svg", output_dir.path().display());
+    let expected = format!(
+        "Maze was successfully written to a file: {}",
+        to_absolute_path!(&file_path)
+    );
+    assert_save_maze!(&file_path, Svg::new().filled(true), expected);
+}
+
+#[test]
+fn build_valid_maze_with_min_corridor() {
+    for min_corridor in [0, 3, 5] {
+        let maze = OrthogonalMazeBuilder::new()
+            .height(15)
+            .width(12)
+            .algorithm(Box::new(Prim::new()))
+            .min_corridor(min_corridor)
+            .build()
+            .unwrap();
+
+        assert!(maze.is_valid());
+    }
+}
+
+#[test]
+fn build_same_maze_with_same_seed() {
+    let algorithms: Vec<fn() -> Box<dyn Algorithm>> = vec![
+        || Box::new(AldousBroder::new().momentum(0.5)),
+        || Box::new(BinaryTree::new(Bias::NorthEast)),
+        || Box::new(Eller),
+        || Box::new(GrowingTree::new(Method::Newest50Random50)),
+        || Box::new(HuntAndKill::new()),
+        || Box::new(Kruskal::new()),
+        || Box::new(Prim::new()),
+        || Box::new(RecursiveBacktracking),
+        || Box::new(RecursiveDivision),
+        || Box::new(River::new()),
+        || Box::new(Sidewinder),
+    ];
+
+    for algorithm in algorithms {
+        let build = || {
+            OrthogonalMazeBuilder::new()
+                .width(12)
+                .height(9)
+                .algorithm(algorithm())
+                .seed(2024)
+                .build()
+                .unwrap()
+        };
+
+        assert_eq!(build(), build());
+    }
+}
+
+#[test]
+fn build_mazes_of_degenerate_sizes() {
+    let algorithms: Vec<fn() -> Box<dyn Algorithm>> = vec![
+        || Box::new(AldousBroder::new()),
+        || Box::new(BinaryTree::new(Bias::NorthEast)),
+        || Box::new(Eller),
+        || Box::new(GrowingTree::new(Method::Newest50Random50)),
+        || Box::new(HuntAndKill::new()),
+        || Box::new(Kruskal::new()),
+        || Box::new(Prim::new()),
+        || Box::new(RecursiveBacktracking),
+        || Box::new(RecursiveDivision),
+        || Box::new(River::new()),
+        || Box::new(Sidewinder),
+    ];
+
+    for algorithm in algorithms {
+        let name = algorithm().name();
+        let build = |width, height| {
+            OrthogonalMazeBuilder::new()
+                .width(width)
+                .height(height)
+                .algorithm(algorithm())
+                .seed(5)
+                .build()
+        };
+
+        // No built-in algorithm needs more than a single row or column
+        for (width, height) in [(1, 1), (1, 7), (7, 1), (2, 2)] {
+            let maze = build(width, height).unwrap();
+            assert!(maze.is_valid(), "{name} {width}x{height}");
+        }
+
+        for (width, height) in [(0, 3), (3, 0)] {
+            assert_eq!(
+                build(width, height).unwrap_err().reason,
+                format!(
+                    "Algorithm `{name}` requires at least a 1x1 maze, but the maze is {width}x{height}"
+                ),
+            );
+        }
+    }
+}
+
+#[test]
+fn format_maze_as_png_bytes() {
+    let png = bevy_knossos::generate_png_bytes(6, 4, 3);
+    let image = image::load_from_memory(&png).unwrap();
+
+    assert_eq!(png, bevy_knossos::generate_png_bytes(6, 4, 3));
+    assert_eq!(
+        (image.width(), image.height()),
+        (6 * 80 + 40 + 100, 4 * 80 + 40 + 100)
+    );
+}
+
+#[test]
+fn generate_same_maze_in_packed_grid() {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let algorithms: Vec<Box<dyn Algorithm>> = vec![
+        Box::new(AldousBroder::new().momentum(0.5)),
+        Box::new(BinaryTree::new(Bias::SouthWest)),
+        Box::new(Eller),
+        Box::new(GrowingTree::new(Method::Newest50Random50)),
+        Box::new(HuntAndKill::new()),
+        Box::new(Kruskal::new()),
+        Box::new(Prim::new()),
+        Box::new(RecursiveBacktracking),
+        Box::new(RecursiveDivision),
+        Box::new(River::new()),
+        Box::new(Sidewinder),
+    ];
+
+    for mut algorithm in algorithms {
+        let mut grid = Grid::new(13, 8);
+        let mut packed = Grid::new_packed(13, 8);
+        algorithm.generate(&mut grid, None, &mut StdRng::seed_from_u64(7));
+        algorithm.generate(&mut packed, None, &mut StdRng::seed_from_u64(7));
+
+        assert!(packed.is_packed());
+        assert_eq!(packed, grid, "{}", algorithm.name());
+        assert_eq!(packed.to_string(), grid.to_string());
+    }
+}
+
+#[test]
+fn observe_carved_passages() {
+    use std::sync::{Arc, Mutex};
+
+    let carved = Arc::new(Mutex::new(Vec::new()));
+    let recorder = Arc::clone(&carved);
+    let mut maze = OrthogonalMazeBuilder::new()
+        .width(8)
+        .height(6)
+        .algorithm(Box::new(Prim::new()))
+        .observer(Box::new(move |from, to| {
+            recorder.lock().unwrap().push((from, to))
+        }))
+        .build()
+        .unwrap();
+
+    // Replaying the carved passages reproduces the maze
+    let replay = |events: &[((usize, usize), (usize, usize))]| {
+        assert_eq!(events.len(), 8 * 6 - 1);
+        Grid::from_edges(8, 6, events).unwrap()
+    };
+    assert_eq!(
+        format!("{}", replay(&carved.lock().unwrap())),
+        maze.to_string()
+    );
+
+    carved.lock().unwrap().clear();
+    maze.regenerate(5);
+    assert_eq!(
+        format!("{}", replay(&carved.lock().unwrap())),
+        maze.to_string()
+    );
+}
+
+#[test]
+fn build_valid_symmetric_mazes() {
+    let mirror = |cell: Cell, a: Cell, b: Cell| {
+        let mut mirrored = cell - a - b;
+        mirrored.set(a, cell.contains(b));
+        mirrored.set(b, cell.contains(a));
+        mirrored
+    };
+
+    for symmetry in [Symmetry::Horizontal, Symmetry::Vertical, Symmetry::Quad] {
+        for (width, height) in [(10, 10), (9, 7), (12, 5)] {
+            let maze = OrthogonalMazeBuilder::new()
+                .width(width)
+                .height(height)
+                .algorithm(Box::new(Prim::new()))
+                .symmetry(symmetry)
+                .build()
+                .unwrap();
+
+            assert!(maze.is_valid());
+            for ((x, y), cell) in maze.iter() {
+                if symmetry != Symmetry::Vertical {
+                    let mirrored = maze[(width - 1 - x, y)];
+                    assert_eq!(mirrored, mirror(*cell, Cell::EAST, Cell::WEST));
+                }
+                if symmetry != Symmetry::Horizontal {
+                    let mirrored = maze[(x, height - 1 - y)];
+                    assert_eq!(mirrored, mirror(*cell, Cell::NORTH, Cell::SOUTH));
+                }
+            }
+        }
+    }
+}
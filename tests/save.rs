@@ -1,3 +1,5 @@
+#![cfg(feature = "cli")]
+
 use assert_cmd::Command;
 use assert_fs::fixture::TempDir;
 
@@ -7,7 +9,7 @@ fn image_save_success() {
     let file_path = format!("{}/maze.png", output_dir.path().display());
     let expected = format!("Maze was successfully saved as an image: {}\n", &file_path);
 
-    let mut cmd = Command::cargo_bin(assert_cmd::crate_name!()).unwrap();
+    let mut cmd = Command::cargo_bin("knossos").unwrap();
     cmd.args(["generate", "image", "--output-path", &file_path])
         .assert()
         .success()
@@ -20,7 +22,7 @@ fn ascii_save_success() {
     let file_path = format!("{}/maze.txt", output_dir.path().display());
     let expected = format!("Maze was successfully written to a file: {}\n", file_path);
 
-    let mut cmd = Command::cargo_bin(assert_cmd::crate_name!()).unwrap();
+    let mut cmd = Command::cargo_bin("knossos").unwrap();
     cmd.args(["generate", "ascii", "--output-path", &file_path])
         .assert()
         .success()
@@ -33,9 +35,73 @@ fn game_map_save_success() {
     let file_path = format!("{}/maze.txt", output_dir.path().display());
     let expected = format!("Maze was successfully written to a file: {}\n", file_path);
 
-    let mut cmd = Command::cargo_bin(assert_cmd::crate_name!()).unwrap();
+    let mut cmd = Command::cargo_bin("knossos").unwrap();
     cmd.args(["generate", "game-map", "--output-path", &file_path])
         .assert()
         .success()
         .stdout(expected);
 }
+
+#[test]
+fn ascii_save_with_default_extension() {
+    let output_dir = TempDir::new().unwrap();
+    let file_path = format!("{}/maze", output_dir.path().display());
+    let expected = format!(
+        "Maze was successfully written to a file: {}.txt\n",
+        file_path
+    );
+
+    let mut cmd = Command::cargo_bin("knossos").unwrap();
+    cmd.args(["generate", "ascii", "--output-path", &file_path])
+        .assert()
+        .success()
+        .stdout(expected);
+}
+
+#[test]
+fn image_save_with_default_extension() {
+    let output_dir = TempDir::new().unwrap();
+    let file_path = format!("{}/maze", output_dir.path().display());
+    let expected = format!(
+        "Maze was successfully saved as an image: {}.png\n",
+        file_path
+    );
+
+    let mut cmd = Command::cargo_bin("knossos").unwrap();
+    cmd.args(["generate", "image", "--output-path", &file_path])
+        .assert()
+        .success()
+        .stdout(expected);
+}
+
+#[test]
+fn ascii_print_to_stdout() {
+    let mut cmd = Command::cargo_bin("knossos").unwrap();
+    let output = cmd
+        .args(["generate", "-W", "4", "-H", "3", "ascii"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let ascii = String::from_utf8(output).unwrap();
+    assert_eq!(ascii.lines().count(), 4);
+    assert!(ascii.starts_with(" _______ \n"));
+}
+
+#[test]
+fn seeded_generation_is_reproducible() {
+    let generate = |seed: &str| {
+        let mut cmd = Command::cargo_bin("knossos").unwrap();
+        cmd.args(["generate", "--seed", seed, "game-map"])
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone()
+    };
+
+    assert_eq!(generate("42"), generate("42"));
+    assert_ne!(generate("42"), generate("43"));
+}